@@ -1,6 +1,9 @@
+use std::ops::ControlFlow;
+
 use rustc_ast::ptr::P;
 use rustc_ast::token::{self, Delimiter, IdentIsRaw};
 use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_ast::visit::{self, Visitor};
 use rustc_ast::{
     BinOpKind, BorrowKind, DUMMY_NODE_ID, DelimArgs, Expr, ExprKind, ItemKind, MacCall, MethodCall,
     Mutability, Path, PathSegment, Stmt, StructRest, UnOp, UseTree, UseTreeKind,
@@ -20,11 +23,20 @@ pub(super) struct Context<'cx, 'a> {
     // Top-level `let captureN = Capture::new()` statements
     capture_decls: Vec<Capture>,
     cx: &'cx ExtCtxt<'a>,
+    // How deeply nested the current expression is in the tree of captured expressions. Used to
+    // indent `fmt_string`.
+    depth: usize,
     // Formatting string used for debugging
     fmt_string: String,
+    // If the condition mutably borrows something. Variables are then always captured
+    // on-the-fly, so that they don't stay borrowed until the `assert!` expression fails.
+    has_mut_borrows: bool,
     // If the current expression being visited consumes itself. Used to construct
     // `best_case_captures`.
     is_consumed: bool,
+    // If the current expression is the whole `assert!` condition, whose value is already known
+    // when the assertion fails.
+    is_root: bool,
     // Top-level `let __local_bindN = &expr` statements
     local_bind_decls: Vec<Stmt>,
     // Used to avoid capturing duplicated paths
@@ -35,6 +47,9 @@ pub(super) struct Context<'cx, 'a> {
     // ```
     paths: FxHashSet<Ident>,
     span: Span,
+    // If `generic_assert_sub_expressions` is enabled. Intermediate values are then captured as
+    // well, see [Self::manage_value_capture].
+    sub_expressions: bool,
 }
 
 impl<'cx, 'a> Context<'cx, 'a> {
@@ -43,11 +58,15 @@ pub(super) fn new(cx: &'cx ExtCtxt<'a>, span: Span) -> Self {
             best_case_captures: <_>::default(),
             capture_decls: <_>::default(),
             cx,
+            depth: 0,
             fmt_string: <_>::default(),
+            has_mut_borrows: false,
             is_consumed: true,
+            is_root: true,
             local_bind_decls: <_>::default(),
             paths: <_>::default(),
             span,
+            sub_expressions: cx.ecfg.features.generic_assert_sub_expressions(),
         }
     }
 
@@ -70,8 +89,16 @@ pub(super) fn new(cx: &'cx ExtCtxt<'a>, span: Span) -> Self {
     ///   }
     /// }
     /// ```
+    ///
+    /// With `generic_assert_sub_expressions`, intermediate values, like the result of
+    /// `add(elem, 1)` in `assert!(add(elem, 1) == 3)`, are captured as well and displayed above
+    /// the variables they were computed from. See [Self::manage_value_capture]. Variables are
+    /// then captured by reference unless the condition mutably borrows something, so that they
+    /// are displayed even if they can not be copied. Nothing is formatted unless the assertion
+    /// fails.
     pub(super) fn build(mut self, mut cond_expr: P<Expr>, panic_path: Path) -> P<Expr> {
         let expr_str = pprust::expr_to_string(&cond_expr);
+        self.has_mut_borrows = has_mut_borrows(&cond_expr);
         self.manage_cond_expr(&mut cond_expr);
         let initial_imports = self.build_initial_imports();
         let panic = self.build_panic(&expr_str, panic_path);
@@ -108,6 +135,10 @@ fn build_initial_imports(&self) -> Stmt {
                 DUMMY_NODE_ID,
             )
         };
+        let items = thin_vec![
+            nested_tree(self, sym::TryCaptureGeneric),
+            nested_tree(self, sym::TryCapturePrintable),
+        ];
         self.cx.stmt_item(
             self.span,
             self.cx.item(
@@ -115,13 +146,7 @@ fn build_initial_imports(&self) -> Stmt {
                 thin_vec![self.cx.attr_nested_word(sym::allow, sym::unused_imports, self.span)],
                 ItemKind::Use(UseTree {
                     prefix: self.cx.path(self.span, self.cx.std_path(&[sym::asserting])),
-                    kind: UseTreeKind::Nested {
-                        items: thin_vec![
-                            nested_tree(self, sym::TryCaptureGeneric),
-                            nested_tree(self, sym::TryCapturePrintable),
-                        ],
-                        span: self.span,
-                    },
+                    kind: UseTreeKind::Nested { items, span: self.span },
                     span: self.span,
                 }),
             ),
@@ -189,8 +214,26 @@ fn build_panic(&self, expr_str: &str, panic_path: Path) -> P<Expr> {
 
     /// Recursive function called until `cond_expr` and `fmt_str` are fully modified.
     ///
-    /// See [Self::manage_initial_capture] and [Self::manage_try_capture]
+    /// See [Self::manage_initial_capture], [Self::manage_try_capture] and
+    /// [Self::manage_value_capture]
     fn manage_cond_expr(&mut self, expr: &mut P<Expr>) {
+        let is_root = std::mem::replace(&mut self.is_root, false);
+        let value_capture = if self.sub_expressions && !is_root && is_capturable_value(expr) {
+            let capture = self.push_capture(&pprust::expr_to_string(expr));
+            self.depth += 1;
+            Some(capture)
+        } else {
+            None
+        };
+        self.manage_sub_exprs(expr);
+        if let Some(capture) = value_capture {
+            self.depth -= 1;
+            self.manage_value_capture(capture, expr);
+        }
+    }
+
+    /// Visits the sub-expressions of `expr`.
+    fn manage_sub_exprs(&mut self, expr: &mut P<Expr>) {
         match &mut expr.kind {
             ExprKind::AddrOf(_, mutability, local_expr) => {
                 self.with_is_consumed_management(matches!(mutability, Mutability::Mut), |this| {
@@ -247,6 +290,11 @@ fn manage_cond_expr(&mut self, expr: &mut P<Expr>) {
                 self.manage_cond_expr(local_expr);
             }
             ExprKind::MethodCall(call) => {
+                // Receivers are usually places, which must not be replaced by a copy, so only
+                // receivers that are themselves intermediate values are visited.
+                if self.sub_expressions && is_capturable_value(&call.receiver) {
+                    self.manage_cond_expr(&mut call.receiver);
+                }
                 for arg in &mut call.args {
                     self.manage_cond_expr(arg);
                 }
@@ -334,14 +382,22 @@ fn manage_cond_expr(&mut self, expr: &mut P<Expr>) {
     fn manage_initial_capture(&mut self, expr: &mut P<Expr>, path_ident: Ident) {
         if self.paths.contains(&path_ident) {
             return;
-        } else {
-            self.fmt_string.push_str("  ");
-            self.fmt_string.push_str(path_ident.as_str());
-            self.fmt_string.push_str(" = {:?}\n");
-            let _ = self.paths.insert(path_ident);
         }
+        let _ = self.paths.insert(path_ident);
         let curr_capture_idx = self.capture_decls.len();
-        let capture_string = format!("__capture{curr_capture_idx}");
+        let ident = self.push_capture(path_ident.as_str());
+        self.manage_try_capture(ident, curr_capture_idx, expr);
+    }
+
+    /// Pushes a new top-level `let __captureN = Capture::new();` declaration along with its
+    /// `label = {:?}` line in `fmt_str`.
+    fn push_capture(&mut self, label: &str) -> Ident {
+        for _ in 0..=self.depth {
+            self.fmt_string.push_str("  ");
+        }
+        self.fmt_string.push_str(&escape_to_fmt(label));
+        self.fmt_string.push_str(" = {:?}\n");
+        let capture_string = format!("__capture{}", self.capture_decls.len());
         let ident = Ident::new(Symbol::intern(&capture_string), self.span);
         let init_std_path = self.cx.std_path(&[sym::asserting, sym::Capture, sym::new]);
         let init = self.cx.expr_call(
@@ -351,7 +407,7 @@ fn manage_initial_capture(&mut self, expr: &mut P<Expr>, path_ident: Ident) {
         );
         let capture = Capture { decl: self.cx.stmt_let(self.span, true, ident, init), ident };
         self.capture_decls.push(capture);
-        self.manage_try_capture(ident, curr_capture_idx, expr);
+        ident
     }
 
     /// Tries to copy `__local_bindN` into `__captureN`.
@@ -360,6 +416,10 @@ fn manage_initial_capture(&mut self, expr: &mut P<Expr>, path_ident: Ident) {
     ///    (&Wrapper(__local_bindN)).try_capture(&mut __captureN);
     ///    __local_bindN
     /// }
+    ///
+    /// With `generic_assert_sub_expressions`, the reference `__local_bindN` itself is copied
+    /// instead when nothing is mutably borrowed, after the assertion failed, which also works
+    /// for variables that can not be copied.
     fn manage_try_capture(&mut self, capture: Ident, curr_capture_idx: usize, expr: &mut P<Expr>) {
         let local_bind_string = format!("__local_bind{curr_capture_idx}");
         let local_bind = Ident::new(Symbol::intern(&local_bind_string), self.span);
@@ -369,15 +429,56 @@ fn manage_try_capture(&mut self, capture: Ident, curr_capture_idx: usize, expr:
             local_bind,
             self.cx.expr_addr_of(self.span, expr.clone()),
         ));
+        let by_ref = self.sub_expressions && !self.has_mut_borrows;
+        let mut elem_ref = self.cx.expr_path(Path::from_ident(local_bind));
+        if by_ref {
+            elem_ref = self.cx.expr_addr_of(self.span, elem_ref);
+        }
+        let try_capture_call = self.build_try_capture_call(elem_ref, capture);
+        let local_bind_path = self.cx.expr_path(Path::from_ident(local_bind));
+        let rslt = if (self.is_consumed && !by_ref) || self.has_mut_borrows {
+            let ret = self.cx.stmt_expr(local_bind_path);
+            self.cx.expr_block(self.cx.block(self.span, thin_vec![try_capture_call, ret]))
+        } else {
+            self.best_case_captures.push(try_capture_call);
+            local_bind_path
+        };
+        *expr = self.cx.expr_deref(self.span, rslt);
+    }
+
+    /// Tries to copy an intermediate value into `__captureN` without evaluating it twice. Values
+    /// that can not be copied are not captured, as they do not outlive the condition.
+    ///
+    /// match expr {
+    ///     __value => {
+    ///         (&Wrapper(&__value)).try_capture(&mut __captureN);
+    ///         __value
+    ///     }
+    /// }
+    fn manage_value_capture(&mut self, capture: Ident, expr: &mut P<Expr>) {
+        let value = Ident::new(Symbol::intern("__value"), self.span);
+        let try_capture_call = self.build_try_capture_call(
+            self.cx.expr_addr_of(self.span, self.cx.expr_path(Path::from_ident(value))),
+            capture,
+        );
+        let ret = self.cx.stmt_expr(self.cx.expr_path(Path::from_ident(value)));
+        let body = self.cx.expr_block(self.cx.block(self.span, thin_vec![try_capture_call, ret]));
+        let arm = self.cx.arm(self.span, self.cx.pat_ident(self.span, value), body);
+        *expr = self.cx.expr_match(self.span, expr.clone(), thin_vec![arm]);
+    }
+
+    /// The statement that tries to copy the value behind `elem_ref` into `capture`.
+    ///
+    /// (&Wrapper(elem_ref)).try_capture(&mut capture);
+    fn build_try_capture_call(&self, elem_ref: P<Expr>, capture: Ident) -> Stmt {
         let wrapper = self.cx.expr_call(
             self.span,
             self.cx.expr_path(
                 self.cx.path(self.span, self.cx.std_path(&[sym::asserting, sym::Wrapper])),
             ),
-            thin_vec![self.cx.expr_path(Path::from_ident(local_bind))],
+            thin_vec![elem_ref],
         );
-        let try_capture_call = self
-            .cx
+        self.cx
             .stmt_expr(expr_method_call(
                 self.cx,
                 PathSegment {
@@ -393,16 +494,7 @@ fn manage_try_capture(&mut self, capture: Ident, curr_capture_idx: usize, expr:
                 )],
                 self.span,
             ))
-            .add_trailing_semicolon();
-        let local_bind_path = self.cx.expr_path(Path::from_ident(local_bind));
-        let rslt = if self.is_consumed {
-            let ret = self.cx.stmt_expr(local_bind_path);
-            self.cx.expr_block(self.cx.block(self.span, thin_vec![try_capture_call, ret]))
-        } else {
-            self.best_case_captures.push(try_capture_call);
-            local_bind_path
-        };
-        *expr = self.cx.expr_deref(self.span, rslt);
+            .add_trailing_semicolon()
     }
 
     // Calls `f` with the internal `is_consumed` set to `curr_is_consumed` and then
@@ -428,6 +520,50 @@ struct Capture {
     ident: Ident,
 }
 
+/// If `expr` computes a new value that is worth displaying, i.e., the result of a call or of an
+/// operator that isn't only applied to literals.
+fn is_capturable_value(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Binary(_, lhs, rhs) => !is_literal(lhs) || !is_literal(rhs),
+        ExprKind::Call(_, args) => args.iter().any(|arg| !is_literal(arg)),
+        ExprKind::Cast(local_expr, _) | ExprKind::Unary(UnOp::Neg | UnOp::Not, local_expr) => {
+            !is_literal(local_expr)
+        }
+        ExprKind::MethodCall(call) => {
+            !is_literal(&call.receiver) || call.args.iter().any(|arg| !is_literal(arg))
+        }
+        _ => false,
+    }
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Lit(_) => true,
+        ExprKind::Paren(local_expr) | ExprKind::Unary(UnOp::Neg, local_expr) => {
+            is_literal(local_expr)
+        }
+        _ => false,
+    }
+}
+
+/// If `expr` mutably borrows something.
+fn has_mut_borrows(expr: &Expr) -> bool {
+    struct MutBorrowFinder;
+
+    impl<'ast> Visitor<'ast> for MutBorrowFinder {
+        type Result = ControlFlow<()>;
+        fn visit_expr(&mut self, expr: &'ast Expr) -> ControlFlow<()> {
+            if let ExprKind::AddrOf(_, Mutability::Mut, _) = expr.kind {
+                ControlFlow::Break(())
+            } else {
+                visit::walk_expr(self, expr)
+            }
+        }
+    }
+
+    MutBorrowFinder.visit_expr(expr).is_break()
+}
+
 /// Escapes to use as a formatting string.
 fn escape_to_fmt(s: &str) -> String {
     let mut rslt = String::with_capacity(s.len());
@@ -259,6 +259,11 @@ codegen_ssa_self_contained_linker_missing = the self-contained linker was reques
 
 codegen_ssa_shuffle_indices_evaluation = could not evaluate shuffle_indices at compile time
 
+codegen_ssa_size_report_no_symbols = the size report for `{$path}` is empty because it has no symbol table
+    .note = symbols are not available when the binary is stripped, or when they are only stored in a separate PDB file
+
+codegen_ssa_size_report_unreadable = failed to read `{$path}` for the size report: {$error}
+
 codegen_ssa_specify_libraries_to_link = use the `-l` flag to specify native libraries to link
 
 codegen_ssa_static_library_native_artifacts = Link against the following native artifacts when linking against this static library. The order and any duplication can be significant on some platforms.
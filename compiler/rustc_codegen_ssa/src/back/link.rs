@@ -56,7 +56,7 @@
 use super::linker::{self, Linker};
 use super::metadata::{MetadataPosition, create_wrapper_file};
 use super::rpath::{self, RPathConfig};
-use super::{apple, versioned_llvm_target};
+use super::{apple, size_report, versioned_llvm_target};
 use crate::{
     CodegenResults, CompiledModule, CrateInfo, NativeLib, errors, looks_like_rust_object_file,
 };
@@ -153,6 +153,14 @@ pub fn link_binary(
                         &metadata,
                         path.as_ref(),
                     );
+                    if let Some(format) = sess.opts.unstable_opts.size_report {
+                        size_report::write_size_report(
+                            sess,
+                            &codegen_results.crate_info.size_report_symbols,
+                            &out_filename,
+                            format,
+                        );
+                    }
                 }
             }
             if sess.opts.json_artifact_notifications {
@@ -10,6 +10,7 @@
 pub mod lto;
 pub mod metadata;
 pub(crate) mod rpath;
+pub mod size_report;
 pub mod symbol_export;
 pub mod write;
 
@@ -0,0 +1,364 @@
+//! The report written by `-Z size-report`, which attributes the size of a linked binary to its
+//! symbols, to the crates defining them and to the (generic) functions they are instances of.
+//!
+//! The symbols of the binary are looked up in the mono items of the local crate and in the
+//! symbols exported by upstream crates, which are collected into the `CrateInfo` before codegen.
+//! Symbols not found there, like private functions of upstream crates, are attributed by
+//! demangling their names instead.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use object::{Object, ObjectSection, ObjectSymbol, SectionIndex, SymbolKind};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::unord::UnordMap;
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::middle::exported_symbols::ExportedSymbol;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, TyCtxt};
+use rustc_session::Session;
+use rustc_session::config::SizeReportFormat;
+use rustc_span::Symbol;
+
+use crate::back::symbol_export;
+use crate::errors;
+
+/// What a symbol of the binary is an instance of.
+#[derive(Debug, Encodable, Decodable)]
+pub struct SizeReportSymbol {
+    /// The crate defining the item, which is not necessarily the crate instantiating it.
+    pub crate_name: Symbol,
+    /// The path of the item, which is shared by all of its instances.
+    pub item: String,
+    /// The instance, including its generic arguments.
+    pub instance: String,
+}
+
+/// Collects the symbols which the mono items of the local crate and the exported symbols of
+/// upstream crates are known by.
+pub(crate) fn collect_symbols(tcx: TyCtxt<'_>) -> UnordMap<String, SizeReportSymbol> {
+    let mut symbols = UnordMap::default();
+    with_no_trimmed_paths!({
+        for cgu in tcx.collect_and_partition_mono_items(()).codegen_units {
+            for &mono_item in cgu.items().keys() {
+                let instance = match mono_item {
+                    MonoItem::Fn(instance) => instance,
+                    MonoItem::Static(def_id) => Instance::mono(tcx, def_id),
+                    MonoItem::GlobalAsm(_) => continue,
+                };
+                let symbol_name = mono_item.symbol_name(tcx).name.to_string();
+                symbols.entry(symbol_name).or_insert_with(|| size_report_symbol(tcx, instance));
+            }
+        }
+
+        for &cnum in tcx.crates(()) {
+            let exported_symbols = tcx
+                .exported_non_generic_symbols(cnum)
+                .iter()
+                .chain(tcx.exported_generic_symbols(cnum));
+            for &(exported_symbol, _) in exported_symbols {
+                let Some(instance) = exported_symbol_instance(tcx, exported_symbol) else {
+                    continue;
+                };
+                let symbol_name =
+                    symbol_export::symbol_name_for_instance_in_crate(tcx, exported_symbol, cnum);
+                symbols.entry(symbol_name).or_insert_with(|| size_report_symbol(tcx, instance));
+            }
+        }
+    });
+    symbols
+}
+
+fn exported_symbol_instance<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    symbol: ExportedSymbol<'tcx>,
+) -> Option<Instance<'tcx>> {
+    Some(match symbol {
+        ExportedSymbol::NonGeneric(def_id) => Instance::mono(tcx, def_id),
+        ExportedSymbol::Generic(def_id, args) => Instance::new_raw(def_id, args),
+        ExportedSymbol::ThreadLocalShim(def_id) => Instance {
+            def: ty::InstanceKind::ThreadLocalShim(def_id),
+            args: ty::GenericArgs::empty(),
+        },
+        ExportedSymbol::DropGlue(ty) => Instance::resolve_drop_in_place(tcx, ty),
+        ExportedSymbol::AsyncDropGlueCtorShim(ty) => Instance::resolve_async_drop_in_place(tcx, ty),
+        ExportedSymbol::AsyncDropGlue(def_id, ty) => {
+            Instance::resolve_async_drop_in_place_poll(tcx, def_id, ty)
+        }
+        ExportedSymbol::NoDefId(_) => return None,
+    })
+}
+
+fn size_report_symbol<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> SizeReportSymbol {
+    let def_id = instance.def_id();
+    SizeReportSymbol {
+        crate_name: tcx.crate_name(def_id.krate),
+        item: tcx.def_path_str(def_id),
+        instance: instance.to_string(),
+    }
+}
+
+/// A defined symbol of the binary, attributed to a crate and an item.
+struct ReportSymbol<'a> {
+    name: &'a str,
+    size: u64,
+    crate_name: String,
+    item: String,
+    instance: String,
+}
+
+#[derive(Default)]
+struct Total {
+    size: u64,
+    /// The number of symbols of a crate, or the number of instances of an item.
+    count: usize,
+}
+
+/// Writes the size report for the binary at `out_filename` next to it, with the extension of
+/// the report format appended to its file name.
+pub(crate) fn write_size_report(
+    sess: &Session,
+    symbols: &UnordMap<String, SizeReportSymbol>,
+    out_filename: &Path,
+    format: SizeReportFormat,
+) {
+    let _timer = sess.timer("write_size_report");
+    let data = match fs::read(out_filename) {
+        Ok(data) => data,
+        Err(error) => {
+            let error = error.to_string();
+            sess.dcx().emit_warn(errors::SizeReportUnreadable { path: out_filename, error });
+            return;
+        }
+    };
+    let file = match object::File::parse(&*data) {
+        Ok(file) => file,
+        Err(error) => {
+            let error = error.to_string();
+            sess.dcx().emit_warn(errors::SizeReportUnreadable { path: out_filename, error });
+            return;
+        }
+    };
+
+    let report_symbols = defined_symbols(&file, sess.target.is_like_darwin)
+        .into_iter()
+        .map(|(name, size)| attribute_symbol(symbols, name, size))
+        .collect::<Vec<_>>();
+    if report_symbols.is_empty() {
+        sess.dcx().emit_warn(errors::SizeReportNoSymbols { path: out_filename });
+    }
+
+    let report = match format {
+        SizeReportFormat::Text => text_report(out_filename, report_symbols),
+        SizeReportFormat::Json => json_report(out_filename, report_symbols),
+    };
+    let mut path = out_filename.as_os_str().to_owned();
+    path.push(".size-report.");
+    path.push(format.extension());
+    if let Err(error) = fs::write(&path, report) {
+        sess.dcx().emit_err(errors::FailedToWrite { path: path.into(), error });
+    }
+}
+
+/// Returns the names and sizes of the functions and data objects defined by the binary.
+///
+/// Symbols at the same address, like aliases, are only counted once. Where the object file
+/// format does not record the sizes of symbols (as in Mach-O), the size of a symbol extends to
+/// the next symbol or to the end of its section.
+fn defined_symbols<'data>(
+    file: &object::File<'data>,
+    strip_underscore_prefix: bool,
+) -> Vec<(&'data str, u64)> {
+    let mut defined: Vec<(SectionIndex, u64, u64, &'data str)> = file
+        .symbols()
+        .filter(|symbol| {
+            symbol.is_definition() && matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+        })
+        .filter_map(|symbol| {
+            let section_index = symbol.section_index()?;
+            let mut name = symbol.name().ok()?;
+            if strip_underscore_prefix {
+                name = name.strip_prefix('_').unwrap_or(name);
+            }
+            Some((section_index, symbol.address(), symbol.size(), name))
+        })
+        .collect();
+    // Sort by address, and the symbols at the same address by size in descending order, so that
+    // aliases which only cover the start of a symbol are the ones which get skipped.
+    defined.sort_unstable_by(|a, b| (a.0.0, a.1, b.2, a.3).cmp(&(b.0.0, b.1, a.2, b.3)));
+    defined.dedup_by_key(|&mut (section_index, address, _, _)| (section_index, address));
+
+    let mut symbols = Vec::with_capacity(defined.len());
+    for (i, &(section_index, address, size, name)) in defined.iter().enumerate() {
+        let size = if size != 0 {
+            size
+        } else if let Some(&(next_section_index, next_address, _, _)) = defined.get(i + 1)
+            && next_section_index == section_index
+        {
+            next_address - address
+        } else if let Ok(section) = file.section_by_index(section_index) {
+            (section.address() + section.size()).saturating_sub(address)
+        } else {
+            0
+        };
+        if size != 0 {
+            symbols.push((name, size));
+        }
+    }
+    symbols
+}
+
+fn attribute_symbol<'a>(
+    symbols: &UnordMap<String, SizeReportSymbol>,
+    name: &'a str,
+    size: u64,
+) -> ReportSymbol<'a> {
+    if let Some(symbol) = symbols.get(name) {
+        return ReportSymbol {
+            name,
+            size,
+            crate_name: symbol.crate_name.to_string(),
+            item: symbol.item.clone(),
+            instance: symbol.instance.clone(),
+        };
+    }
+
+    match rustc_demangle::try_demangle(name) {
+        Ok(demangled) => {
+            // Without the hash, the demangled names of legacy symbols are shared by all instances
+            // of an item, as the generic arguments are not mangled.
+            let demangled = format!("{demangled:#}");
+            let crate_name = demangled_crate_name(&demangled).to_string();
+            ReportSymbol { name, size, crate_name, item: demangled.clone(), instance: demangled }
+        }
+        Err(_) => ReportSymbol {
+            name,
+            size,
+            crate_name: "[non-Rust]".to_string(),
+            item: name.to_string(),
+            instance: name.to_string(),
+        },
+    }
+}
+
+/// Returns the crate of the first path in a demangled symbol name. Trait impls for primitive and
+/// other types without a path, like `<str as core::fmt::Display>::fmt`, are attributed to the
+/// crate of the trait instead, while their inherent impls cannot be attributed.
+fn demangled_crate_name(demangled: &str) -> &str {
+    let path = demangled.trim_start_matches('<');
+    let end = path.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(path.len());
+    if end > 0 && path[end..].starts_with("::") {
+        &path[..end]
+    } else if let Some((_, trait_path)) = path.split_once(" as ") {
+        demangled_crate_name(trait_path)
+    } else {
+        "[unknown]"
+    }
+}
+
+/// Sums up the sizes of the symbols by `key`, sorted by size in descending order.
+fn totals<'a, 'b>(
+    symbols: &'a [ReportSymbol<'b>],
+    key: impl Fn(&'a ReportSymbol<'b>) -> (&'a str, &'a str),
+) -> Vec<((&'a str, &'a str), Total)> {
+    let mut totals: FxIndexMap<_, Total> = FxIndexMap::default();
+    for symbol in symbols {
+        let total = totals.entry(key(symbol)).or_default();
+        total.size += symbol.size;
+        total.count += 1;
+    }
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_unstable_by(|(a_key, a), (b_key, b)| b.size.cmp(&a.size).then(a_key.cmp(b_key)));
+    totals
+}
+
+fn sort_symbols(symbols: &mut [ReportSymbol<'_>]) {
+    symbols.sort_unstable_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(b.name)));
+}
+
+fn text_report(out_filename: &Path, mut symbols: Vec<ReportSymbol<'_>>) -> String {
+    sort_symbols(&mut symbols);
+    let total_size: u64 = symbols.iter().map(|symbol| symbol.size).sum();
+    let percent = |size: u64| size as f64 * 100.0 / total_size.max(1) as f64;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "size report for `{}`: {total_size} bytes in {} symbols",
+        out_filename.display(),
+        symbols.len()
+    )
+    .unwrap();
+
+    writeln!(out, "\n{:>12} {:>7} {:>9}  crate", "size", "%", "symbols").unwrap();
+    for ((crate_name, _), total) in totals(&symbols, |symbol| (symbol.crate_name.as_str(), "")) {
+        let (size, count) = (total.size, total.count);
+        writeln!(out, "{size:>12} {:>6.2}% {count:>9}  {crate_name}", percent(size)).unwrap();
+    }
+
+    writeln!(out, "\n{:>12} {:>7} {:>9}  function", "size", "%", "instances").unwrap();
+    for ((crate_name, item), total) in
+        totals(&symbols, |symbol| (symbol.crate_name.as_str(), symbol.item.as_str()))
+    {
+        let (size, count) = (total.size, total.count);
+        writeln!(out, "{size:>12} {:>6.2}% {count:>9}  {item} ({crate_name})", percent(size))
+            .unwrap();
+    }
+
+    writeln!(out, "\n{:>12} {:>7}  symbol", "size", "%").unwrap();
+    for symbol in &symbols {
+        let size = symbol.size;
+        writeln!(out, "{size:>12} {:>6.2}%  {} ({})", percent(size), symbol.instance, symbol.name)
+            .unwrap();
+    }
+    out
+}
+
+fn json_report(out_filename: &Path, mut symbols: Vec<ReportSymbol<'_>>) -> String {
+    sort_symbols(&mut symbols);
+    let total_size: u64 = symbols.iter().map(|symbol| symbol.size).sum();
+    let crates: Vec<_> = totals(&symbols, |symbol| (symbol.crate_name.as_str(), ""))
+        .into_iter()
+        .map(|((crate_name, _), total)| {
+            serde_json::json!({
+                "name": crate_name,
+                "size": total.size,
+                "symbols": total.count,
+            })
+        })
+        .collect();
+    let functions: Vec<_> =
+        totals(&symbols, |symbol| (symbol.crate_name.as_str(), symbol.item.as_str()))
+            .into_iter()
+            .map(|((crate_name, item), total)| {
+                serde_json::json!({
+                    "name": item,
+                    "crate": crate_name,
+                    "size": total.size,
+                    "instances": total.count,
+                })
+            })
+            .collect();
+    let symbols: Vec<_> = symbols
+        .iter()
+        .map(|symbol| {
+            serde_json::json!({
+                "name": symbol.name,
+                "instance": symbol.instance,
+                "function": symbol.item,
+                "crate": symbol.crate_name,
+                "size": symbol.size,
+            })
+        })
+        .collect();
+    let report = serde_json::json!({
+        "output": out_filename.display().to_string(),
+        "total_size": total_size,
+        "crates": crates,
+        "functions": functions,
+        "symbols": symbols,
+    });
+    serde_json::to_string_pretty(&report).unwrap()
+}
@@ -36,6 +36,7 @@
 
 use crate::assert_module_sources::CguReuse;
 use crate::back::link::are_upstream_rust_objects_already_included;
+use crate::back::size_report;
 use crate::back::write::{
     ComputedLtoType, OngoingCodegen, compute_per_cgu_lto_type, start_async_codegen,
     submit_codegened_module_to_llvm, submit_post_lto_module_to_llvm, submit_pre_lto_module_to_llvm,
@@ -939,6 +940,11 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             natvis_debugger_visualizers: Default::default(),
             lint_levels: CodegenLintLevels::from_tcx(tcx),
             metadata_symbol: exported_symbols::metadata_symbol_name(tcx),
+            size_report_symbols: if tcx.sess.opts.unstable_opts.size_report.is_some() {
+                size_report::collect_symbols(tcx)
+            } else {
+                Default::default()
+            },
         };
 
         info.native_libraries.reserve(n_crates);
@@ -19,7 +19,7 @@
 use rustc_hashes::Hash64;
 use rustc_hir::def_id::DefId;
 use rustc_hir::definitions::{DefPathData, DefPathDataName, DisambiguatedDefPathData};
-use rustc_hir::{CoroutineDesugaring, CoroutineKind, CoroutineSource, Mutability};
+use rustc_hir::{CoroutineDesugaring, CoroutineKind, CoroutineSource, LangItem, Mutability};
 use rustc_middle::bug;
 use rustc_middle::ty::layout::{IntegerExt, TyAndLayout};
 use rustc_middle::ty::{self, ExistentialProjection, GenericArgKind, GenericArgsRef, Ty, TyCtxt};
@@ -116,6 +116,13 @@ fn push_debuginfo_type_name<'tcx>(
                 );
             } else {
                 push_item_name(tcx, def.did(), qualified, output);
+                // `String` only became generic over its allocator later on, so with the default
+                // allocator it keeps the name debugger visualizers and users expect.
+                let args = if tcx.is_lang_item(def.did(), LangItem::String) {
+                    tcx.mk_args(tcx.generics_of(def.did()).own_args_no_defaults(tcx, args))
+                } else {
+                    args
+                };
                 push_generic_params_internal(tcx, args, output, visited);
             }
         }
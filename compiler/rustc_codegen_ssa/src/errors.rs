@@ -666,6 +666,20 @@ pub(crate) struct FailedToWrite {
     pub error: Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_unreadable)]
+pub(crate) struct SizeReportUnreadable<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_no_symbols)]
+#[note]
+pub(crate) struct SizeReportNoSymbols<'a> {
+    pub path: &'a Path,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_unable_to_write_debugger_visualizer)]
 pub(crate) struct UnableToWriteDebuggerVisualizer {
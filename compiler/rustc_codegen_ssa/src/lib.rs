@@ -234,6 +234,8 @@ pub struct CrateInfo {
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
     pub lint_levels: CodegenLintLevels,
     pub metadata_symbol: String,
+    /// The instances known by the symbols of the binary, only collected for `-Z size-report`.
+    pub size_report_symbols: UnordMap<String, back::size_report::SizeReportSymbol>,
 }
 
 /// Target-specific options that get set in `cfg(...)`.
@@ -582,6 +582,7 @@ pub(super) fn init_fn_call(
             | ty::InstanceKind::CloneShim(..)
             | ty::InstanceKind::FnPtrAddrShim(..)
             | ty::InstanceKind::ThreadLocalShim(..)
+            | ty::InstanceKind::OutlinedColdPath(..)
             | ty::InstanceKind::AsyncDropGlueCtorShim(..)
             | ty::InstanceKind::AsyncDropGlue(..)
             | ty::InstanceKind::FutureDropPollShim(..)
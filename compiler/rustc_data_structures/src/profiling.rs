@@ -79,18 +79,29 @@
 //! invocation) and allocate the corresponding strings together with a mapping
 //! for `DepNodeIndex as StringId`.
 //!
+//!
+//! ## Chrome Trace Output
+//!
+//! With `-Z self-profile-format=chrome`, the events are additionally recorded
+//! in memory and written as Chrome trace-event JSON by `SelfProfilerRef::finish`
+//! at the end of the compilation session, so they can be loaded into Perfetto or
+//! `about:tracing` without post-processing the `measureme` data. See the
+//! `chrome_trace` module.
+//!
 //! [mm]: https://github.com/rust-lang/measureme/
 
+mod chrome_trace;
+
 use std::borrow::Borrow;
 use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fmt::Display;
 use std::intrinsics::unlikely;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
-use std::{fs, process};
+use std::{fs, io, process};
 
 pub use measureme::EventId;
 use measureme::{EventIdBuilder, Profiler, SerializableString, StringId};
@@ -98,6 +109,7 @@
 use smallvec::SmallVec;
 use tracing::warn;
 
+use self::chrome_trace::{ChromeTrace, IntervalGuard};
 use crate::fx::FxHashMap;
 use crate::outline;
 use crate::sync::AtomicU64;
@@ -165,6 +177,15 @@ pub enum TimePassesFormat {
     Json,
 }
 
+/// Which format to use for `-Z self-profile`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SelfProfileFormat {
+    /// Only write the raw `measureme` event data
+    Measureme,
+    /// Also write the events as Chrome trace-event JSON
+    Chrome,
+}
+
 /// A reference to the SelfProfiler. It can be cloned and sent across thread
 /// boundaries at will.
 #[derive(Clone)]
@@ -288,7 +309,7 @@ pub fn generic_activity_with_arg<A>(
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
                 let event_arg = profiler.get_or_alloc_cached_string(event_arg);
-                builder.from_label_and_arg(event_label, event_arg)
+                profiler.event_id_from_label_and_arg(event_label, event_arg)
             } else {
                 builder.from_label(event_label)
             };
@@ -348,7 +369,7 @@ pub fn generic_activity_with_arg_recorder<F>(
                     );
                 }
 
-                builder.from_label_and_args(event_label, &recorder.args)
+                profiler.event_id_from_label_and_args(event_label, &recorder.args)
             } else {
                 builder.from_label(event_label)
             };
@@ -366,10 +387,9 @@ pub fn artifact_size<A>(&self, artifact_kind: &str, artifact_name: A, size: u64)
         A: Borrow<str> + Into<String>,
     {
         drop(self.exec(EventFilter::ARTIFACT_SIZES, |profiler| {
-            let builder = EventIdBuilder::new(&profiler.profiler);
             let event_label = profiler.get_or_alloc_cached_string(artifact_kind);
             let event_arg = profiler.get_or_alloc_cached_string(artifact_name);
-            let event_id = builder.from_label_and_arg(event_label, event_arg);
+            let event_id = profiler.event_id_from_label_and_arg(event_label, event_arg);
             let thread_id = get_thread_id();
 
             profiler.profiler.record_integer_event(
@@ -378,6 +398,14 @@ pub fn artifact_size<A>(&self, artifact_kind: &str, artifact_name: A, size: u64)
                 thread_id,
                 size,
             );
+            if let Some(chrome_trace) = &profiler.chrome_trace {
+                chrome_trace.record_integer(
+                    profiler.artifact_size_event_kind,
+                    event_id,
+                    thread_id,
+                    size,
+                );
+            }
 
             TimingGuard::none()
         }))
@@ -397,7 +425,7 @@ pub fn generic_activity_with_args(
                     .iter()
                     .map(|s| profiler.get_or_alloc_cached_string(&s[..]))
                     .collect();
-                builder.from_label_and_args(event_label, &event_args)
+                profiler.event_id_from_label_and_args(event_label, &event_args)
             } else {
                 builder.from_label(event_label)
             };
@@ -485,14 +513,13 @@ fn instant_query_event(
         event_kind: fn(&SelfProfiler) -> StringId,
         query_invocation_id: QueryInvocationId,
     ) {
-        let event_id = StringId::new_virtual(query_invocation_id.0);
+        let event_id = EventId::from_virtual(StringId::new_virtual(query_invocation_id.0));
         let thread_id = get_thread_id();
         let profiler = self.profiler.as_ref().unwrap();
-        profiler.profiler.record_instant_event(
-            event_kind(profiler),
-            EventId::from_virtual(event_id),
-            thread_id,
-        );
+        profiler.profiler.record_instant_event(event_kind(profiler), event_id, thread_id);
+        if let Some(chrome_trace) = &profiler.chrome_trace {
+            chrome_trace.record_instant(event_kind(profiler), event_id, thread_id);
+        }
     }
 
     pub fn with_profiler(&self, f: impl FnOnce(&SelfProfiler)) {
@@ -552,6 +579,13 @@ pub fn get_self_profiler(&self) -> Option<Arc<SelfProfiler>> {
         self.profiler.clone()
     }
 
+    /// Writes out the data which is only written once the compilation session is finished,
+    /// i.e. the Chrome trace for `-Z self-profile-format=chrome`. Events recorded after this
+    /// are not part of the trace.
+    pub fn finish(&self) -> Result<(), (PathBuf, io::Error)> {
+        self.profiler.as_ref().map_or(Ok(()), |profiler| profiler.finish())
+    }
+
     /// Is expensive recording of query keys and/or function arguments enabled?
     pub fn is_args_recording_enabled(&self) -> bool {
         self.enabled() && self.event_filter_mask.intersects(EventFilter::ARGS)
@@ -613,6 +647,9 @@ pub struct SelfProfiler {
     artifact_size_event_kind: StringId,
     /// Total cache hits per query invocation
     query_cache_hit_count_event_kind: StringId,
+
+    /// The events recorded for `-Z self-profile-format=chrome`.
+    chrome_trace: Option<ChromeTrace>,
 }
 
 impl SelfProfiler {
@@ -621,6 +658,7 @@ pub fn new(
         crate_name: Option<&str>,
         event_filters: Option<&[String]>,
         counter_name: &str,
+        format: SelfProfileFormat,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
         fs::create_dir_all(output_directory)?;
 
@@ -633,6 +671,12 @@ pub fn new(
         let path = output_directory.join(filename);
         let profiler =
             Profiler::with_counter(&path, measureme::counters::Counter::by_name(counter_name)?)?;
+        let chrome_trace = match format {
+            SelfProfileFormat::Measureme => None,
+            SelfProfileFormat::Chrome => {
+                Some(ChromeTrace::new(output_directory.join(format!("{crate_name}-{pid:07}.json"))))
+            }
+        };
 
         let query_event_kind = profiler.alloc_string("Query");
         let generic_activity_event_kind = profiler.alloc_string("GenericActivity");
@@ -690,6 +734,7 @@ pub fn new(
             artifact_size_event_kind,
             query_cache_hit_count_event_kind,
             query_hits: Default::default(),
+            chrome_trace,
         })
     }
 
@@ -747,9 +792,32 @@ pub fn get_or_alloc_cached_string<A>(&self, s: A) -> StringId
         }
     }
 
+    /// Creates an `EventId` from a label and an argument. Event IDs with arguments
+    /// should always be created this way, so that their label and arguments can be
+    /// looked up when writing a Chrome trace.
+    pub fn event_id_from_label_and_arg(&self, label: StringId, arg: StringId) -> EventId {
+        let event_id = EventIdBuilder::new(&self.profiler).from_label_and_arg(label, arg);
+        if let Some(chrome_trace) = &self.chrome_trace {
+            chrome_trace.record_label_and_args(event_id, label, &[arg]);
+        }
+        event_id
+    }
+
+    /// Like `event_id_from_label_and_arg`, but with any number of arguments.
+    pub fn event_id_from_label_and_args(&self, label: StringId, args: &[StringId]) -> EventId {
+        let event_id = EventIdBuilder::new(&self.profiler).from_label_and_args(label, args);
+        if let Some(chrome_trace) = &self.chrome_trace {
+            chrome_trace.record_label_and_args(event_id, label, args);
+        }
+        event_id
+    }
+
     pub fn map_query_invocation_id_to_string(&self, from: QueryInvocationId, to: StringId) {
         let from = StringId::new_virtual(from.0);
         self.profiler.map_virtual_to_concrete_string(from, to);
+        if let Some(chrome_trace) = &self.chrome_trace {
+            chrome_trace.map_query_invocation_id(from, to);
+        }
     }
 
     pub fn bulk_map_query_invocation_id_to_single_string<I>(&self, from: I, to: StringId)
@@ -757,7 +825,15 @@ pub fn bulk_map_query_invocation_id_to_single_string<I>(&self, from: I, to: Stri
         I: Iterator<Item = QueryInvocationId> + ExactSizeIterator,
     {
         let from = from.map(|qid| StringId::new_virtual(qid.0));
-        self.profiler.bulk_map_virtual_to_single_concrete_string(from, to);
+        if let Some(chrome_trace) = &self.chrome_trace {
+            let from: Vec<_> = from.collect();
+            for &from in &from {
+                chrome_trace.map_query_invocation_id(from, to);
+            }
+            self.profiler.bulk_map_virtual_to_single_concrete_string(from.into_iter(), to);
+        } else {
+            self.profiler.bulk_map_virtual_to_single_concrete_string(from, to);
+        }
     }
 
     pub fn query_key_recording_enabled(&self) -> bool {
@@ -767,10 +843,31 @@ pub fn query_key_recording_enabled(&self) -> bool {
     pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
         EventIdBuilder::new(&self.profiler)
     }
+
+    /// Writes the Chrome trace for `-Z self-profile-format=chrome`, returning the path of the
+    /// trace if that fails.
+    pub fn finish(&self) -> Result<(), (PathBuf, io::Error)> {
+        let Some(chrome_trace) = &self.chrome_trace else { return Ok(()) };
+
+        let string_cache = self.string_cache.read();
+        let strings: FxHashMap<_, _> = string_cache.iter().map(|(s, &id)| (id, &s[..])).collect();
+        let event_kinds = [
+            (self.query_event_kind, "Query"),
+            (self.generic_activity_event_kind, "GenericActivity"),
+            (self.incremental_load_result_event_kind, "IncrementalLoadResult"),
+            (self.incremental_result_hashing_event_kind, "IncrementalResultHashing"),
+            (self.query_blocked_event_kind, "QueryBlocked"),
+            (self.query_cache_hit_event_kind, "QueryCacheHit"),
+            (self.artifact_size_event_kind, "ArtifactSize"),
+        ];
+        chrome_trace
+            .write(&strings, &event_kinds)
+            .map_err(|err| (chrome_trace.path().to_path_buf(), err))
+    }
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>, Option<IntervalGuard<'a>>);
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -783,23 +880,31 @@ pub fn start(
         let raw_profiler = &profiler.profiler;
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        let chrome_guard = profiler
+            .chrome_trace
+            .as_ref()
+            .map(|chrome_trace| chrome_trace.start_interval(event_kind, event_id, thread_id));
+        TimingGuard(Some(timing_guard), chrome_guard)
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard(guard, chrome_guard) = self;
+        if let Some(guard) = guard {
             outline(|| {
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
+                if let Some(chrome_guard) = chrome_guard {
+                    chrome_guard.finish_with_override_event_id(event_id);
+                }
             });
         }
     }
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard(None, None)
     }
 
     #[inline(always)]
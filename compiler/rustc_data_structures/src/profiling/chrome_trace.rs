@@ -0,0 +1,229 @@
+//! Recording of self-profile events in the Chrome trace-event format, which can be
+//! loaded into Perfetto or `about:tracing` directly (`-Z self-profile-format=chrome`).
+//!
+//! Events are recorded alongside the `measureme` events, but are kept in memory and
+//! only written out by `SelfProfiler::finish`. Like `measureme`, events refer
+//! to their names by `StringId`, so that recording them stays cheap. The names are
+//! resolved when writing the trace, after the query invocation IDs have been mapped
+//! to their query names.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fs, io, process};
+
+use measureme::{EventId, StringId};
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+
+use crate::fx::FxHashMap;
+
+pub(super) struct ChromeTrace {
+    path: PathBuf,
+    start: Instant,
+    events: Mutex<Vec<TraceEvent>>,
+    /// The label and arguments of each event ID created from a label and arguments,
+    /// as those cannot be looked up from the `StringId` of the event ID.
+    labels_and_args: Mutex<FxHashMap<StringId, (StringId, SmallVec<[StringId; 2]>)>>,
+    /// The event IDs which the (virtual) query invocation IDs have been mapped to.
+    query_event_ids: Mutex<FxHashMap<StringId, StringId>>,
+}
+
+struct TraceEvent {
+    event_kind: StringId,
+    event_id: StringId,
+    thread_id: u32,
+    start: Duration,
+    phase: Phase,
+}
+
+enum Phase {
+    /// An interval event with the given duration.
+    Complete(Duration),
+    /// An event which happened at a single point in time.
+    Instant,
+    /// An integer value, like the size of an artifact.
+    Counter(u64),
+}
+
+impl ChromeTrace {
+    pub(super) fn new(path: PathBuf) -> ChromeTrace {
+        ChromeTrace {
+            path,
+            start: Instant::now(),
+            events: Default::default(),
+            labels_and_args: Default::default(),
+            query_event_ids: Default::default(),
+        }
+    }
+
+    pub(super) fn start_interval(
+        &self,
+        event_kind: StringId,
+        event_id: EventId,
+        thread_id: u32,
+    ) -> IntervalGuard<'_> {
+        IntervalGuard {
+            trace: self,
+            event_kind,
+            event_id: event_id.to_string_id(),
+            thread_id,
+            start: self.start.elapsed(),
+        }
+    }
+
+    pub(super) fn record_instant(&self, event_kind: StringId, event_id: EventId, thread_id: u32) {
+        self.record(event_kind, event_id.to_string_id(), thread_id, Phase::Instant);
+    }
+
+    pub(super) fn record_integer(
+        &self,
+        event_kind: StringId,
+        event_id: EventId,
+        thread_id: u32,
+        value: u64,
+    ) {
+        self.record(event_kind, event_id.to_string_id(), thread_id, Phase::Counter(value));
+    }
+
+    fn record(&self, event_kind: StringId, event_id: StringId, thread_id: u32, phase: Phase) {
+        let start = self.start.elapsed();
+        self.events.lock().push(TraceEvent { event_kind, event_id, thread_id, start, phase });
+    }
+
+    pub(super) fn record_label_and_args(
+        &self,
+        event_id: EventId,
+        label: StringId,
+        args: &[StringId],
+    ) {
+        self.labels_and_args.lock().insert(event_id.to_string_id(), (label, args.into()));
+    }
+
+    pub(super) fn map_query_invocation_id(&self, from: StringId, to: StringId) {
+        self.query_event_ids.lock().insert(from, to);
+    }
+
+    /// Writes the recorded events to the trace file, using `strings` to look up the
+    /// names of events and `event_kinds` to look up their categories.
+    pub(super) fn write(
+        &self,
+        strings: &FxHashMap<StringId, &str>,
+        event_kinds: &[(StringId, &str)],
+    ) -> io::Result<()> {
+        let events = self.events.lock();
+        let labels_and_args = self.labels_and_args.lock();
+        let query_event_ids = self.query_event_ids.lock();
+        let pid = process::id();
+
+        let mut out = String::from("{\"traceEvents\":[");
+        for (i, event) in events.iter().enumerate() {
+            let category = event_kinds
+                .iter()
+                .find(|&&(kind, _)| kind == event.event_kind)
+                .map_or("Unknown", |&(_, name)| name);
+            let event_id = query_event_ids.get(&event.event_id).unwrap_or(&event.event_id);
+            let (name, args): (&str, SmallVec<[&str; 2]>) =
+                if let Some(&name) = strings.get(event_id) {
+                    (name, SmallVec::new())
+                } else if let Some((label, args)) = labels_and_args.get(event_id) {
+                    let name = strings.get(label).copied().unwrap_or(category);
+                    (name, args.iter().filter_map(|arg| strings.get(arg).copied()).collect())
+                } else {
+                    (category, SmallVec::new())
+                };
+
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("\n{\"name\":");
+            write_json_string(&mut out, name);
+            out.push_str(",\"cat\":");
+            write_json_string(&mut out, category);
+            let ts = micros(event.start);
+            match event.phase {
+                Phase::Complete(dur) => {
+                    write!(out, ",\"ph\":\"X\",\"ts\":{ts},\"dur\":{}", micros(dur)).unwrap();
+                    if !args.is_empty() {
+                        out.push_str(",\"args\":{");
+                        for (i, arg) in args.iter().enumerate() {
+                            if i > 0 {
+                                out.push(',');
+                            }
+                            write!(out, "\"arg{i}\":").unwrap();
+                            write_json_string(&mut out, arg);
+                        }
+                        out.push('}');
+                    }
+                }
+                Phase::Instant => {
+                    write!(out, ",\"ph\":\"i\",\"s\":\"t\",\"ts\":{ts}").unwrap();
+                }
+                Phase::Counter(value) => {
+                    // Counters are grouped by their name, with one series per argument, e.g.
+                    // one series per object file for the "object_file" artifact sizes.
+                    write!(out, ",\"ph\":\"C\",\"ts\":{ts},\"args\":{{").unwrap();
+                    write_json_string(&mut out, args.first().copied().unwrap_or("value"));
+                    write!(out, ":{value}}}").unwrap();
+                }
+            }
+            write!(out, ",\"pid\":{pid},\"tid\":{}}}", event.thread_id).unwrap();
+        }
+        out.push_str("\n],\"displayTimeUnit\":\"ms\"}\n");
+
+        fs::write(&self.path, out)
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Records an interval event once dropped.
+pub(super) struct IntervalGuard<'a> {
+    trace: &'a ChromeTrace,
+    event_kind: StringId,
+    event_id: StringId,
+    thread_id: u32,
+    start: Duration,
+}
+
+impl IntervalGuard<'_> {
+    pub(super) fn finish_with_override_event_id(mut self, event_id: EventId) {
+        self.event_id = event_id.to_string_id();
+    }
+}
+
+impl Drop for IntervalGuard<'_> {
+    fn drop(&mut self) {
+        let duration = self.trace.start.elapsed() - self.start;
+        self.trace.events.lock().push(TraceEvent {
+            event_kind: self.event_kind,
+            event_id: self.event_id,
+            thread_id: self.thread_id,
+            start: self.start,
+            phase: Phase::Complete(duration),
+        });
+    }
+}
+
+/// Timestamps and durations of trace events are in microseconds.
+fn micros(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1000.0
+}
+
+pub(super) fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
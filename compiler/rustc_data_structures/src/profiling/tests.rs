@@ -1,4 +1,5 @@
 use super::JsonTimePassesEntry;
+use super::chrome_trace::write_json_string;
 
 #[test]
 fn with_rss() {
@@ -17,3 +18,11 @@ fn no_rss() {
         r#"{"pass":"typeck","time":56.1,"rss_start":null,"rss_end":null}"#
     )
 }
+
+#[test]
+fn chrome_trace_string_escaping() {
+    let mut out = String::new();
+    write_json_string(&mut out, "typeck(\"a\\b\")\n\u{1}");
+
+    assert_eq!(out, r#""typeck(\"a\\b\")\n\u0001""#)
+}
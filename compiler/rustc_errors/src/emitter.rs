@@ -33,7 +33,7 @@
     Annotation, AnnotationColumn, AnnotationType, Line, MultilineAnnotation, Style, StyledString,
 };
 use crate::styled_buffer::StyledBuffer;
-use crate::timings::TimingRecord;
+use crate::timings::{ItemTimingRecord, TimingRecord};
 use crate::translation::{Translator, to_fluent_args};
 use crate::{
     CodeSuggestion, DiagInner, DiagMessage, ErrCode, Level, MultiSpan, Subdiag,
@@ -186,6 +186,10 @@ fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
     /// Currently only supported for the JSON format.
     fn emit_timing_section(&mut self, _record: TimingRecord, _event: TimingEvent) {}
 
+    /// Emit the time spent on a single item within a timing section.
+    /// Currently only supported for the JSON format.
+    fn emit_item_timing(&mut self, _record: ItemTimingRecord) {}
+
     /// Emit a report about future breakage.
     /// Currently only supported for the JSON format.
     fn emit_future_breakage_report(&mut self, _diags: Vec<DiagInner>, _registry: &Registry) {}
@@ -31,7 +31,7 @@
     TimingEvent, should_show_source_code,
 };
 use crate::registry::Registry;
-use crate::timings::{TimingRecord, TimingSection};
+use crate::timings::{ItemTimingRecord, TimingRecord};
 use crate::translation::{Translator, to_fluent_args};
 use crate::{CodeSuggestion, MultiSpan, SpanLabel, Subdiag, Suggestions, TerminalUrl};
 
@@ -101,6 +101,7 @@ enum EmitTyped<'a> {
     Diagnostic(Diagnostic),
     Artifact(ArtifactNotification<'a>),
     SectionTiming(SectionTimestamp<'a>),
+    ItemTiming(ItemTiming<'a>),
     FutureIncompat(FutureIncompatReport<'a>),
     UnusedExtern(UnusedExterns<'a>),
 }
@@ -127,10 +128,7 @@ fn emit_timing_section(&mut self, record: TimingRecord, event: TimingEvent) {
             TimingEvent::Start => "start",
             TimingEvent::End => "end",
         };
-        let name = match record.section {
-            TimingSection::Linking => "link",
-            TimingSection::Codegen => "codegen",
-        };
+        let name = record.section.name();
         let data = SectionTimestamp { name, event, timestamp: record.timestamp };
         let result = self.emit(EmitTyped::SectionTiming(data));
         if let Err(e) = result {
@@ -138,6 +136,18 @@ fn emit_timing_section(&mut self, record: TimingRecord, event: TimingEvent) {
         }
     }
 
+    fn emit_item_timing(&mut self, record: ItemTimingRecord) {
+        let data = ItemTiming {
+            section: record.section.name(),
+            item: &record.item,
+            duration: record.duration,
+        };
+        let result = self.emit(EmitTyped::ItemTiming(data));
+        if let Err(e) = result {
+            panic!("failed to print item timing: {e:?}");
+        }
+    }
+
     fn emit_future_breakage_report(&mut self, diags: Vec<crate::DiagInner>, registry: &Registry) {
         let data: Vec<FutureBreakageItem<'_>> = diags
             .into_iter()
@@ -280,6 +290,16 @@ struct SectionTimestamp<'a> {
     timestamp: u128,
 }
 
+#[derive(Serialize)]
+struct ItemTiming<'a> {
+    /// Name of the section the item belongs to
+    section: &'a str,
+    /// Path of the item
+    item: &'a str,
+    /// Time spent on the item, in microseconds.
+    duration: u128,
+}
+
 #[derive(Serialize)]
 struct FutureBreakageItem<'a> {
     // Always EmitTyped::Diagnostic, but we want to make sure it gets serialized
@@ -76,7 +76,7 @@
 
 use crate::emitter::TimingEvent;
 use crate::registry::Registry;
-use crate::timings::TimingRecord;
+use crate::timings::{ItemTimingRecord, TimingRecord};
 
 pub mod annotate_snippet_emitter_writer;
 pub mod codes;
@@ -88,6 +88,7 @@
 mod lock;
 pub mod markdown;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -1138,6 +1139,10 @@ pub fn emit_timing_section_end(&self, record: TimingRecord) {
         self.inner.borrow_mut().emitter.emit_timing_section(record, TimingEvent::End);
     }
 
+    pub fn emit_item_timing(&self, record: ItemTimingRecord) {
+        self.inner.borrow_mut().emitter.emit_item_timing(record);
+    }
+
     pub fn emit_future_breakage_report(&self) {
         let inner = &mut *self.inner.borrow_mut();
         let diags = std::mem::take(&mut inner.future_breakage_diagnostics);
@@ -0,0 +1,487 @@
+//! A SARIF emitter for errors.
+//!
+//! Unlike the JSON emitter, which prints every diagnostic as soon as it is emitted, this collects
+//! the diagnostics and writes them as a single [SARIF 2.1.0] log when the emitter is dropped, so
+//! that code-scanning services can ingest the output directly.
+//!
+//! Every diagnostic with a span or a code becomes a `result`: error codes and lint names become
+//! `rules`, primary spans become `locations`, secondary spans and child diagnostics become
+//! `relatedLocations` and structured suggestions become `fixes`. The remaining diagnostics, such
+//! as the "aborting due to previous errors" summary, are about the compilation as a whole and are
+//! reported as tool execution notifications instead.
+//!
+//! [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::error::Report;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use derive_setters::Setters;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::IntoDynSyncSend;
+use rustc_error_messages::FluentArgs;
+use rustc_lint_defs::Applicability;
+use rustc_span::Span;
+use rustc_span::source_map::SourceMap;
+use serde::Serialize;
+
+use crate::diagnostic::IsLint;
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{Translator, to_fluent_args};
+use crate::{CodeSuggestion, DiagInner, DiagMessage, Level, MultiSpan, Suggestions};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Setters)]
+pub struct SarifEmitter {
+    #[setters(skip)]
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    #[setters(skip)]
+    sm: Option<Arc<SourceMap>>,
+    #[setters(skip)]
+    translator: Translator,
+    /// The name of the tool the log is attributed to, e.g. `rustc` or `rustdoc`.
+    tool_name: &'static str,
+    #[setters(skip)]
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    #[setters(skip)]
+    results: Vec<SarifResult>,
+    #[setters(skip)]
+    notifications: Vec<Notification>,
+    /// Whether a fatal error or an ICE ended the compilation early.
+    #[setters(skip)]
+    aborted: bool,
+}
+
+impl SarifEmitter {
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        sm: Option<Arc<SourceMap>>,
+        translator: Translator,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            sm,
+            translator,
+            tool_name: "rustc",
+            rules: FxIndexMap::default(),
+            results: Vec::new(),
+            notifications: Vec::new(),
+            aborted: false,
+        }
+    }
+
+    fn translate(&self, message: &DiagMessage, args: &FluentArgs<'_>) -> String {
+        self.translator.translate_message(message, args).map_err(Report::new).unwrap().to_string()
+    }
+
+    /// Returns the index of the rule for the diagnostic's error code or lint name, if it has one.
+    fn rule_index(&mut self, diag: &DiagInner, registry: &Registry) -> Option<usize> {
+        let rule = if let Some(code) = diag.code {
+            ReportingDescriptor {
+                id: code.to_string(),
+                help_uri: registry
+                    .try_find_description(code)
+                    .is_ok()
+                    .then(|| format!("https://doc.rust-lang.org/error_codes/{code}.html")),
+            }
+        } else if let Some(IsLint { name, .. }) = &diag.is_lint {
+            ReportingDescriptor { id: name.clone(), help_uri: None }
+        } else {
+            return None;
+        };
+        let (index, _) = self.rules.insert_full(rule.id.clone(), rule);
+        Some(index)
+    }
+
+    fn physical_location(&self, span: Span) -> Option<PhysicalLocation> {
+        let sm = self.sm.as_deref()?;
+        if span.is_dummy() {
+            return None;
+        }
+        let start = sm.lookup_char_pos(span.lo());
+        let end = sm.lookup_char_pos(span.hi());
+        let byte_offset = start.file.original_relative_byte_pos(span.lo()).0;
+        Some(PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: artifact_uri(&sm.filename_for_diagnostics(&start.file.name).to_string()),
+            },
+            region: Region {
+                start_line: start.line,
+                start_column: start.col.0 + 1,
+                end_line: end.line,
+                end_column: end.col.0 + 1,
+                byte_offset,
+                byte_length: start.file.original_relative_byte_pos(span.hi()).0 - byte_offset,
+            },
+        })
+    }
+
+    /// Converts the spans of `msp` to locations, using `message` for the spans without a label.
+    fn locations(
+        &self,
+        msp: &MultiSpan,
+        message: Option<&str>,
+        args: &FluentArgs<'_>,
+    ) -> impl Iterator<Item = (bool, Location)> {
+        msp.span_labels().into_iter().filter_map(move |label| {
+            let physical_location = self.physical_location(label.span)?;
+            let message = match &label.label {
+                Some(label) => Some(self.translate(label, args)),
+                None => message.map(str::to_owned),
+            };
+            Some((
+                label.is_primary,
+                Location {
+                    id: None,
+                    physical_location: Some(physical_location),
+                    message: message.map(Message::new),
+                },
+            ))
+        })
+    }
+
+    fn fixes(&self, suggestions: &[CodeSuggestion], args: &FluentArgs<'_>) -> Vec<Fix> {
+        let mut fixes = Vec::new();
+        for suggestion in suggestions {
+            let description = self.translate(&suggestion.msg, args);
+            // Every substitution is an alternative way of applying the suggestion.
+            for substitution in &suggestion.substitutions {
+                let mut changes = FxIndexMap::<String, Vec<Replacement>>::default();
+                for part in &substitution.parts {
+                    let Some(location) = self.physical_location(part.span) else { continue };
+                    changes.entry(location.artifact_location.uri).or_default().push(Replacement {
+                        deleted_region: location.region,
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    });
+                }
+                if changes.is_empty() {
+                    continue;
+                }
+                fixes.push(Fix {
+                    description: Message::new(description.clone()),
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                });
+            }
+        }
+        fixes
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: self.tool_name,
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                invocations: [Invocation {
+                    execution_successful: !self.aborted,
+                    tool_execution_notifications: &self.notifications,
+                }],
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        serde_json::to_writer_pretty(&mut *self.dst, &log)?;
+        self.dst.write_all(b"\n")?;
+        self.dst.flush()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner, registry: &Registry) {
+        if matches!(diag.level, Level::Bug | Level::Fatal) {
+            self.aborted = true;
+        }
+
+        let args = to_fluent_args(diag.args.iter());
+        let message = self.translator.translate_messages(&diag.messages, &args).to_string();
+        let level = sarif_level(diag.level);
+        let rule_index = self.rule_index(&diag, registry);
+        if rule_index.is_none() && diag.span.is_dummy() {
+            self.notifications.push(Notification { level, message: Message::new(message) });
+            return;
+        }
+
+        let mut locations = Vec::new();
+        let mut related_locations = Vec::new();
+        for (is_primary, location) in self.locations(&diag.span, None, &args) {
+            let locations = if is_primary { &mut locations } else { &mut related_locations };
+            locations.push(location);
+        }
+        for child in &diag.children {
+            let child_message = format!(
+                "{}: {}",
+                child.level.to_str(),
+                self.translator.translate_messages(&child.messages, &args)
+            );
+            let start = related_locations.len();
+            related_locations.extend(
+                self.locations(&child.span, Some(&child_message), &args).map(|(_, loc)| loc),
+            );
+            if related_locations.len() == start {
+                related_locations.push(Location {
+                    id: None,
+                    physical_location: None,
+                    message: Some(Message::new(child_message)),
+                });
+            }
+        }
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+
+        let suggestions: &[CodeSuggestion] = match &diag.suggestions {
+            Suggestions::Enabled(suggestions) => suggestions,
+            Suggestions::Sealed(suggestions) => suggestions,
+            Suggestions::Disabled => &[],
+        };
+        let fixes = self.fixes(suggestions, &args);
+
+        self.results.push(SarifResult {
+            rule_id: rule_index.map(|index| self.rules[index].id.clone()),
+            rule_index,
+            level,
+            message: Message::new(message),
+            locations,
+            related_locations,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        self.sm.as_deref()
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // The rules already link to the explanations of the error codes.
+        false
+    }
+
+    fn translator(&self) -> &Translator {
+        &self.translator
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        // Like the other emitters, print nothing if there is nothing to report. This also keeps
+        // the early diagnostic contexts from printing logs of their own.
+        if self.results.is_empty() && self.notifications.is_empty() {
+            return;
+        }
+        if let Err(e) = self.write_log()
+            && !std::thread::panicking()
+        {
+            panic!("failed to print diagnostics: {e:?}");
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error | Level::DelayedBug => "error",
+        Level::ForceWarning | Level::Warning => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp | Level::FailureNote => {
+            "note"
+        }
+        Level::Allow | Level::Expect => "none",
+    }
+}
+
+/// Turns a file name as it is shown in diagnostics into the `uri` of a SARIF artifact location.
+///
+/// Relative paths stay relative references, so that they are resolved against the directory the
+/// compiler was invoked from, while absolute paths become `file` URIs.
+fn artifact_uri(file_name: &str) -> String {
+    let path = file_name.replace('\\', "/");
+    let mut uri = String::new();
+    if Path::new(file_name).is_absolute() {
+        uri.push_str(if path.starts_with('/') { "file://" } else { "file:///" });
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => write!(uri, "%{byte:02X}").unwrap(),
+        }
+    }
+    uri
+}
+
+// The following data types are provided just for serialisation. They are the subset of the
+// SARIF 2.1.0 object model that rustc fills in.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    invocations: [Invocation<'a>; 1],
+    /// Columns count characters, like the columns rustc prints.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Invocation<'a> {
+    execution_successful: bool,
+    tool_execution_notifications: &'a [Notification],
+}
+
+/// A rule, i.e. an error code or a lint.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    /// The documentation of the error code, if it has any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Notification {
+    level: &'static str,
+    message: Message,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+impl Message {
+    fn new(text: String) -> Message {
+        Message { text }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    /// Identifies related locations within their result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<PhysicalLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    /// 1-based and exclusive, character offset.
+    end_column: usize,
+    /// Offset from the start of the file, in bytes.
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+/// Property bag carrying the rustc-specific details of a fix.
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
@@ -0,0 +1,140 @@
+use std::sync::Mutex;
+
+use rustc_span::BytePos;
+use rustc_span::source_map::FilePathMapping;
+use serde_json::{Value, json};
+
+use super::*;
+use crate::{DiagCtxt, E0308};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` with a `DiagCtxt` that emits SARIF for a file `test.rs` containing `code`, and
+/// returns the log it printed.
+fn emit_sarif(code: &str, f: impl FnOnce(&DiagCtxt)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let emitter =
+            SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(sm), translator);
+        let dcx = DiagCtxt::new(Box::new(emitter));
+        f(&dcx);
+        drop(dcx);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn result() {
+    let log = emit_sarif("let x: u32 = \"five\";\n", |dcx| {
+        dcx.handle()
+            .struct_span_err(span(13, 19), "mismatched types")
+            .with_code(E0308)
+            .with_span_label(span(13, 19), "expected `u32`, found `&str`")
+            .with_span_label(span(7, 10), "expected due to this")
+            .with_note("the types must match")
+            .with_span_suggestion(span(13, 19), "use a number", "5", Applicability::MaybeIncorrect)
+            .emit();
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(run["tool"]["driver"]["rules"], json!([{ "id": "E0308" }]));
+    assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0308");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "mismatched types");
+    assert_eq!(
+        result["locations"],
+        json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": "test.rs" },
+                "region": {
+                    "startLine": 1,
+                    "startColumn": 14,
+                    "endLine": 1,
+                    "endColumn": 20,
+                    "byteOffset": 13,
+                    "byteLength": 6,
+                },
+            },
+            "message": { "text": "expected `u32`, found `&str`" },
+        }])
+    );
+    assert_eq!(result["relatedLocations"][0]["id"], 0);
+    assert_eq!(result["relatedLocations"][0]["message"]["text"], "expected due to this");
+    assert_eq!(
+        result["relatedLocations"][1],
+        json!({ "id": 1, "message": { "text": "note: the types must match" } })
+    );
+    assert_eq!(
+        result["fixes"],
+        json!([{
+            "description": { "text": "use a number" },
+            "artifactChanges": [{
+                "artifactLocation": { "uri": "test.rs" },
+                "replacements": [{
+                    "deletedRegion": {
+                        "startLine": 1,
+                        "startColumn": 14,
+                        "endLine": 1,
+                        "endColumn": 20,
+                        "byteOffset": 13,
+                        "byteLength": 6,
+                    },
+                    "insertedContent": { "text": "5" },
+                }],
+            }],
+            "properties": { "applicability": "MaybeIncorrect" },
+        }])
+    );
+}
+
+#[test]
+fn notification() {
+    let log = emit_sarif("", |dcx| {
+        dcx.handle().err("aborting due to 1 previous error");
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(run["results"], json!([]));
+    assert_eq!(
+        run["invocations"][0]["toolExecutionNotifications"],
+        json!([{ "level": "error", "message": { "text": "aborting due to 1 previous error" } }])
+    );
+}
+
+#[test]
+fn uris() {
+    assert_eq!(artifact_uri("src/main.rs"), "src/main.rs");
+    assert_eq!(artifact_uri(r"src\my module.rs"), "src/my%20module.rs");
+    #[cfg(unix)]
+    assert_eq!(artifact_uri("/home/me/lib.rs"), "file:///home/me/lib.rs");
+    #[cfg(windows)]
+    assert_eq!(artifact_uri(r"C:\me\lib.rs"), "file:///C:/me/lib.rs");
+}
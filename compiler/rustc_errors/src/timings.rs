@@ -1,6 +1,6 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
 
 use crate::DiagCtxtHandle;
@@ -8,12 +8,40 @@
 /// A high-level section of the compilation process.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TimingSection {
+    /// Time spent parsing the crate root and its out-of-line modules.
+    Parsing,
+    /// Time spent expanding macros, including the early name resolution they require.
+    MacroExpansion,
+    /// Time spent on late name resolution.
+    NameResolution,
+    /// Time spent type checking item signatures and bodies.
+    TypeChecking,
+    /// Time spent borrow checking MIR bodies.
+    BorrowChecking,
+    /// Time spent encoding and writing crate metadata.
+    MetadataEncoding,
     /// Time spent doing codegen.
     Codegen,
     /// Time spent linking.
     Linking,
 }
 
+impl TimingSection {
+    /// Name of the section, as emitted in the `--json=timings` messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            TimingSection::Parsing => "parse",
+            TimingSection::MacroExpansion => "expand",
+            TimingSection::NameResolution => "resolve",
+            TimingSection::TypeChecking => "typeck",
+            TimingSection::BorrowChecking => "borrowck",
+            TimingSection::MetadataEncoding => "metadata",
+            TimingSection::Codegen => "codegen",
+            TimingSection::Linking => "link",
+        }
+    }
+}
+
 /// Section with attached timestamp
 #[derive(Copy, Clone, Debug)]
 pub struct TimingRecord {
@@ -36,6 +64,19 @@ pub fn timestamp(&self) -> u128 {
     }
 }
 
+/// Time spent on a single item (e.g. the body of a function) within a section.
+#[derive(Clone, Debug)]
+pub struct ItemTimingRecord {
+    pub section: TimingSection,
+    /// Path of the item, as printed by the compiler.
+    pub item: String,
+    /// Microseconds spent on the item.
+    pub duration: u128,
+}
+
+/// How many of the slowest items are reported for each section.
+const MAX_REPORTED_ITEMS: usize = 10;
+
 /// Manages emission of start/end section timings, enabled through `--json=timings`.
 pub struct TimingSectionHandler {
     /// Time when the compilation session started.
@@ -43,12 +84,18 @@ pub struct TimingSectionHandler {
     origin: Option<Instant>,
     /// Sanity check to ensure that we open and close sections correctly.
     opened_sections: Lock<FxHashSet<TimingSection>>,
+    /// The slowest items seen so far in each section, sorted from slowest to fastest.
+    slowest_items: Lock<FxHashMap<TimingSection, Vec<ItemTimingRecord>>>,
 }
 
 impl TimingSectionHandler {
     pub fn new(enabled: bool) -> Self {
         let origin = if enabled { Some(Instant::now()) } else { None };
-        Self { origin, opened_sections: Lock::new(FxHashSet::default()) }
+        Self {
+            origin,
+            opened_sections: Lock::new(FxHashSet::default()),
+            slowest_items: Lock::new(FxHashMap::default()),
+        }
     }
 
     /// Returns a RAII guard that will immediately emit a start the provided section, and then emit
@@ -91,6 +138,60 @@ pub fn end_section(&self, diag_ctxt: DiagCtxtHandle<'_>, section: TimingSection)
         }
     }
 
+    /// Runs `f`, recording how long it took as part of `section`.
+    ///
+    /// Only the slowest items of each section are kept, and `item` is only invoked to describe
+    /// the item if it is one of them. They are emitted by [`Self::emit_slowest_items`].
+    pub fn time_item<R>(
+        &self,
+        section: TimingSection,
+        item: impl FnOnce() -> String,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        if !self.is_enabled() {
+            return f();
+        }
+
+        let start = Instant::now();
+        let result = f();
+        self.record_item(section, start.elapsed(), item);
+        result
+    }
+
+    fn record_item(
+        &self,
+        section: TimingSection,
+        elapsed: Duration,
+        item: impl FnOnce() -> String,
+    ) {
+        let duration = elapsed.as_micros();
+        let is_slow_enough = |items: &[ItemTimingRecord]| {
+            items.len() < MAX_REPORTED_ITEMS
+                || items.last().is_some_and(|fastest| fastest.duration < duration)
+        };
+        if !self.slowest_items.borrow().get(&section).is_none_or(|items| is_slow_enough(items)) {
+            return;
+        }
+
+        // Describe the item without holding the lock, as doing so may run queries.
+        let record = ItemTimingRecord { section, item: item(), duration };
+        let mut slowest_items = self.slowest_items.borrow_mut();
+        let items = slowest_items.entry(section).or_default();
+        if is_slow_enough(items) {
+            let index = items.partition_point(|record| record.duration >= duration);
+            items.insert(index, record);
+            items.truncate(MAX_REPORTED_ITEMS);
+        }
+    }
+
+    /// Emit the slowest items recorded through [`Self::time_item`] for the provided section.
+    pub fn emit_slowest_items(&self, diag_ctxt: DiagCtxtHandle<'_>, section: TimingSection) {
+        let items = self.slowest_items.borrow_mut().remove(&section).unwrap_or_default();
+        for record in items {
+            diag_ctxt.emit_item_timing(record);
+        }
+    }
+
     fn is_enabled(&self) -> bool {
         self.origin.is_some()
     }
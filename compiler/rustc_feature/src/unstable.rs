@@ -213,6 +213,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (internal, custom_mir, "1.65.0", None),
     /// Outputs useful `assert!` messages
     (unstable, generic_assert, "1.63.0", None),
+    /// Outputs the values of the sub-expressions in `assert!` messages, requires `generic_assert`
+    (unstable, generic_assert_sub_expressions, "CURRENT_RUSTC_VERSION", None),
     /// Allows using the #[rustc_intrinsic] attribute.
     (internal, intrinsics, "1.0.0", None),
     /// Allows using `#[lang = ".."]` attribute for linking items to special compiler logic.
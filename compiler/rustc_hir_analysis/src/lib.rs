@@ -91,6 +91,7 @@
 
 pub use errors::NoVariantNamed;
 use rustc_abi::ExternAbi;
+use rustc_errors::timings::TimingSection;
 use rustc_hir::def::DefKind;
 use rustc_hir::lints::DelayedLint;
 use rustc_hir::{self as hir};
@@ -252,7 +253,11 @@ pub fn check_crate(tcx: TyCtxt<'_>) {
         }
         // Skip `AnonConst`s because we feed their `type_of`.
         if !matches!(def_kind, DefKind::AnonConst) {
-            tcx.ensure_ok().typeck(item_def_id);
+            tcx.sess.timings.time_item(
+                TimingSection::TypeChecking,
+                || tcx.def_path_str(item_def_id),
+                || tcx.ensure_ok().typeck(item_def_id),
+            );
         }
         // Ensure we generate the new `DefId` before finishing `check_crate`.
         // Afterwards we freeze the list of `DefId`s.
@@ -282,6 +282,18 @@ pub(crate) fn demand_coerce_diag(
         let expr_ty = self.resolve_vars_if_possible(checked_ty);
         let mut err =
             self.err_ctxt().report_mismatched_types(&cause, self.param_env, expected, expr_ty, e);
+        // `checked_ty` was already resolved when `expr` was checked, so for a local explain the
+        // variable it was declared with instead.
+        let unresolved_ty = if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = expr.kind
+            && let Res::Local(hir_id) = path.res
+        {
+            self.local_ty(expr.span, hir_id)
+        } else {
+            checked_ty
+        };
+        if unresolved_ty != expr_ty {
+            self.err_ctxt().note_inference_history(&mut err, &[unresolved_ty]);
+        }
 
         self.emit_coerce_suggestions(&mut err, expr, expr_ty, expected, expected_ty_expr, Some(e));
 
@@ -85,6 +85,12 @@ incremental_repeated_depnode_label = dep-node label `{$label}` is repeated
 incremental_session_gc_failed =
     failed to garbage collect incremental compilation session directory `{$path}`: {$err}
 
+incremental_shared_cache_export =
+    failed to add the incremental compilation session directory `{$path}` to the shared cache: {$err}
+
+incremental_shared_cache_import =
+    failed to import incremental compilation results from the shared cache at `{$path}`: {$err}
+
 incremental_unchecked_clean = found unchecked `#[rustc_clean]` attribute
 
 incremental_undefined_clean_dirty_assertions =
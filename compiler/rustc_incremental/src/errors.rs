@@ -241,6 +241,20 @@ pub(crate) struct FinalizedGcFailed<'a> {
     pub err: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(incremental_shared_cache_export)]
+pub(crate) struct SharedCacheExport<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(incremental_shared_cache_import)]
+pub(crate) struct SharedCacheImport<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(incremental_session_gc_failed)]
 pub(crate) struct SessionGcFailed<'a> {
@@ -122,6 +122,7 @@
 use rustc_span::Symbol;
 use tracing::debug;
 
+use super::shared_cache;
 use crate::errors;
 
 #[cfg(test)]
@@ -259,11 +260,16 @@ pub(crate) fn prepare_session_directory(sess: &Session, crate_name: Symbol) {
         let source_directory = find_source_directory(&crate_dir, &source_directories_already_tried);
 
         let Some(source_directory) = source_directory else {
-            // There's nowhere to copy from, we're done
-            debug!(
-                "no source directory found. Continuing with empty session \
-                    directory."
-            );
+            // There's nowhere to copy from locally, but the shared cache may
+            // have results from another checkout or machine.
+            if shared_cache::import_session_directory(sess, &session_dir) {
+                debug!("imported session directory from the shared cache");
+            } else {
+                debug!(
+                    "no source directory found. Continuing with empty session \
+                        directory."
+                );
+            }
 
             sess.init_incr_comp_session(session_dir, directory_lock);
             return;
@@ -359,6 +365,8 @@ pub fn finalize_session_directory(sess: &Session, svh: Option<Svh>) {
         Ok(_) => {
             debug!("finalize_session_directory() - directory renamed successfully");
 
+            shared_cache::export_session_directory(sess, &new_path);
+
             // This unlocks the directory
             sess.finalize_incr_comp_session(new_path);
         }
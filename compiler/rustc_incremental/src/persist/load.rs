@@ -18,7 +18,7 @@
 use super::data::*;
 use super::fs::*;
 use super::save::build_dep_graph;
-use super::{file_format, work_product};
+use super::{file_format, shared_cache, work_product};
 use crate::errors;
 
 #[derive(Debug)]
@@ -107,7 +107,7 @@ fn load_dep_graph(
     // Calling `sess.incr_comp_session_dir()` will panic if `sess.opts.incremental.is_none()`.
     // Fortunately, we just checked that this isn't the case.
     let path = dep_graph_path(sess);
-    let expected_hash = sess.opts.dep_tracking_hash(false);
+    let expected_hash = shared_cache::options_hash(sess);
 
     let mut prev_work_products = UnordMap::default();
 
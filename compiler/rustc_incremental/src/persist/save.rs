@@ -14,7 +14,7 @@
 
 use super::data::*;
 use super::fs::*;
-use super::{dirty_clean, file_format, work_product};
+use super::{dirty_clean, file_format, shared_cache, work_product};
 use crate::assert_dep_graph::assert_dep_graph;
 use crate::errors;
 
@@ -166,7 +166,7 @@ pub(crate) fn build_dep_graph(
     file_format::write_file_header(&mut encoder, sess);
 
     // First encode the commandline arguments hash
-    sess.opts.dep_tracking_hash(false).encode(&mut encoder);
+    shared_cache::options_hash(sess).encode(&mut encoder);
 
     Some(DepGraph::new(sess, prev_graph, prev_work_products, encoder))
 }
@@ -0,0 +1,221 @@
+//! A content-addressed store that lets compilation sessions of different
+//! incremental compilation directories share their results. It is enabled with
+//! `-Zincremental-shared-cache=<dir>`.
+//!
+//! Session directories (see the [`fs`](super::fs) module) belong to a single
+//! incremental compilation directory, so a fresh checkout of a crate or another
+//! machine cannot reuse them. The shared store complements them:
+//!
+//! 1. When a session directory is finalized, each of its files is added to the
+//!    store, and a manifest listing these files is published for the crate.
+//! 2. When a session starts and there is no finalized session directory for the
+//!    crate to copy from, the files listed in the crate's manifest are copied
+//!    from the store into the new session directory. The session then loads
+//!    them like the files of a previous local session.
+//!
+//! The unit of reuse is a whole session: a new session imports all the files of
+//! the crate's most recently exported session, or none of them. Work products
+//! and query results are not keyed individually, they stay inside the files of
+//! the session directory and are validated by its dependency graph as usual.
+//! Storing the files by their contents only means that the files sessions have
+//! in common are stored once.
+//!
+//! The store has the following layout:
+//!
+//! - `objects/{hash}`: the contents of a file from a session directory, named
+//!   after the stable hash of these contents. Objects are never modified once
+//!   they are written, so sessions of any crate can share them.
+//! - `manifests/{crate-dir-name}-{options-hash}`: the files of the most recently
+//!   exported session of a crate, compiled with a given set of options. Each line
+//!   contains the hash of a file, followed by its name in the session directory.
+//!
+//! None of these names depend on the location of the sources or of the
+//! incremental compilation directory. Neither does the options hash that the
+//! dependency graph of a session is checked against when the store is in use:
+//! the working directory only affects it if it ends up in debuginfo, so
+//! checkouts built with debuginfo should remap it with `--remap-path-prefix` to
+//! share their results. Other results may still contain paths, in spans for
+//! example, and the imported results that depend on them are invalidated by the
+//! dependency graph, like they would be if the sources were moved.
+//!
+//! Files are always copied rather than hard-linked, so that the contents of an
+//! object cannot change after it is written, even if a file of a session is
+//! modified in place. They are written under a temporary name and then renamed,
+//! so any number of compiler processes can use the same store concurrently.
+//! The store is never garbage collected by the compiler, and since its hashes
+//! are not cryptographic, it should only be shared between trusted machines.
+
+use std::fs as std_fs;
+use std::hash::Hasher;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use rand::{RngCore, rng};
+use rustc_data_structures::base_n::{CASE_INSENSITIVE, ToBaseN};
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hashes::{Hash64, Hash128};
+use rustc_session::Session;
+use tracing::debug;
+
+use crate::errors;
+
+const OBJECTS_DIR: &str = "objects";
+const MANIFESTS_DIR: &str = "manifests";
+
+/// Seeds `session_dir` with the files of the crate's manifest in the shared
+/// store, if there is one.
+///
+/// Returns whether any file was imported. If the import fails, `session_dir`
+/// is left empty.
+pub(crate) fn import_session_directory(sess: &Session, session_dir: &Path) -> bool {
+    let Some(store) = &sess.opts.unstable_opts.incremental_shared_cache else { return false };
+    let manifest_path = manifest_path(sess, store, session_dir);
+    debug!("import_session_directory: manifest = {}", manifest_path.display());
+
+    let manifest = match std_fs::read_to_string(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) if err.kind() == ErrorKind::NotFound => return false,
+        Err(err) => {
+            sess.dcx().emit_warn(errors::SharedCacheImport { path: &manifest_path, err });
+            return false;
+        }
+    };
+
+    let mut imported = 0;
+    for line in manifest.lines() {
+        let result = parse_manifest_line(line).and_then(|(hash, file_name)| {
+            std_fs::copy(object_path(store, hash), session_dir.join(file_name)).map(|_| ())
+        });
+        if let Err(err) = result {
+            sess.dcx().emit_warn(errors::SharedCacheImport { path: &manifest_path, err });
+            // Don't leave a partial session behind, it would fail to load.
+            if let Ok(entries) = session_dir.read_dir() {
+                for entry in entries.flatten() {
+                    let _ = std_fs::remove_file(entry.path());
+                }
+            }
+            return false;
+        }
+        imported += 1;
+    }
+
+    if sess.opts.unstable_opts.incremental_info {
+        eprintln!("[incremental] shared cache: {imported} files imported");
+    }
+    imported > 0
+}
+
+/// Adds the files of the finalized `session_dir` to the shared store, and
+/// publishes them as the crate's manifest.
+pub(crate) fn export_session_directory(sess: &Session, session_dir: &Path) {
+    let Some(store) = &sess.opts.unstable_opts.incremental_shared_cache else { return };
+    let _timer = sess.timer("incr_comp_export_shared_cache");
+
+    if let Err(err) = try_export_session_directory(sess, store, session_dir) {
+        sess.dcx().emit_warn(errors::SharedCacheExport { path: session_dir, err });
+    }
+}
+
+fn try_export_session_directory(
+    sess: &Session,
+    store: &Path,
+    session_dir: &Path,
+) -> io::Result<()> {
+    std_fs::create_dir_all(store.join(OBJECTS_DIR))?;
+    std_fs::create_dir_all(store.join(MANIFESTS_DIR))?;
+
+    let mut files = Vec::new();
+    for entry in session_dir.read_dir()? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let Some(file_name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        files.push((file_name, entry.path()));
+    }
+    // Keep manifests stable, so that they only change when the files do.
+    files.sort();
+
+    let mut manifest = String::new();
+    let mut added = 0;
+    for (file_name, path) in files {
+        let hash = hash_file(&path)?;
+        let object_path = object_path(store, &hash);
+        if !object_path.exists() {
+            publish(&object_path, |tmp_path| std_fs::copy(&path, tmp_path).map(|_| ()))?;
+            added += 1;
+        }
+        manifest.push_str(&format!("{hash} {file_name}\n"));
+    }
+    publish(&manifest_path(sess, store, session_dir), |tmp_path| {
+        std_fs::write(tmp_path, manifest.as_bytes())
+    })?;
+
+    if sess.opts.unstable_opts.incremental_info {
+        eprintln!("[incremental] shared cache: {added} files added");
+    }
+    Ok(())
+}
+
+/// Returns the hash of the options that must match for the dependency graph
+/// and work products of a previous session to be loaded.
+///
+/// With a shared store, sessions must be loadable from other checkouts, so
+/// the hash doesn't depend on the location of the sources then.
+pub(crate) fn options_hash(sess: &Session) -> Hash64 {
+    if sess.opts.unstable_opts.incremental_shared_cache.is_some() {
+        sess.opts.path_independent_dep_tracking_hash()
+    } else {
+        sess.opts.dep_tracking_hash(false)
+    }
+}
+
+/// Returns the path of the manifest for the crate of `session_dir`.
+///
+/// The name of the crate directory only depends on the crate name and its
+/// `StableCrateId`, and the options hash is the one that must match for the
+/// dependency graph to be loaded, so manifests for different options don't
+/// overwrite each other.
+fn manifest_path(sess: &Session, store: &Path, session_dir: &Path) -> PathBuf {
+    let crate_dir_name = session_dir.parent().unwrap().file_name().unwrap().to_string_lossy();
+    let options_hash = options_hash(sess).as_u64().to_base_fixed_len(CASE_INSENSITIVE);
+    store.join(MANIFESTS_DIR).join(format!("{crate_dir_name}-{options_hash}"))
+}
+
+fn object_path(store: &Path, hash: &str) -> PathBuf {
+    store.join(OBJECTS_DIR).join(hash)
+}
+
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = StableHasher::new();
+    hasher.write(&std_fs::read(path)?);
+    let hash: Hash128 = hasher.finish();
+    Ok(hash.as_u128().to_base_fixed_len(CASE_INSENSITIVE).to_string())
+}
+
+fn parse_manifest_line(line: &str) -> io::Result<(&str, &str)> {
+    let invalid = || io::Error::new(ErrorKind::InvalidData, format!("malformed entry `{line}`"));
+    let (hash, file_name) = line.split_once(' ').ok_or_else(invalid)?;
+    // Both are only ever file names, anything else has not been written by us.
+    let is_file_name =
+        |name: &str| !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\']);
+    if !is_file_name(hash) || !is_file_name(file_name) {
+        return Err(invalid());
+    }
+    Ok((hash, file_name))
+}
+
+/// Creates the file at `path` by writing it under a temporary name with `write`,
+/// and then renaming it, so that readers never observe a partially written file.
+fn publish(path: &Path, write: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap().to_owned();
+    tmp_name.push(format!(".{}.tmp", rng().next_u32().to_base_fixed_len(CASE_INSENSITIVE)));
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = write(&tmp_path).and_then(|()| std_fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = std_fs::remove_file(&tmp_path);
+    }
+    result
+}
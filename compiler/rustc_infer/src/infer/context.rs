@@ -181,6 +181,7 @@ fn enter_forall<T: TypeFoldable<TyCtxt<'tcx>>, U>(
 
     fn equate_ty_vids_raw(&self, a: ty::TyVid, b: ty::TyVid) {
         self.inner.borrow_mut().type_variables().equate(a, b);
+        self.record_inference_step(a, Ty::new_var(self.tcx, b), DUMMY_SP);
     }
 
     fn equate_int_vids_raw(&self, a: ty::IntVid, b: ty::IntVid) {
@@ -165,6 +165,10 @@ pub struct InferCtxtInner<'tcx> {
 
     /// Caches for opaque type inference.
     opaque_type_storage: OpaqueTypeStorage<'tcx>,
+
+    /// `-Zexplain-inference`: every instantiation or unification of a type variable, in the
+    /// order in which they happened.
+    inference_steps: Vec<InferenceStep<'tcx>>,
 }
 
 impl<'tcx> InferCtxtInner<'tcx> {
@@ -182,6 +186,7 @@ fn new() -> InferCtxtInner<'tcx> {
             region_assumptions: Default::default(),
             hir_typeck_potentially_region_dependent_goals: Default::default(),
             opaque_type_storage: Default::default(),
+            inference_steps: Default::default(),
         }
     }
 
@@ -324,9 +329,25 @@ pub struct InferCtxt<'tcx> {
 
     next_trait_solver: bool,
 
+    /// `-Zexplain-inference`: Whether to record the [`InferenceStep`]s of this inference
+    /// context. Only enabled during HIR typeck.
+    explain_inference: bool,
+
     pub obligation_inspector: Cell<Option<ObligationInspector<'tcx>>>,
 }
 
+/// `-Zexplain-inference`: a single step of type inference, i.e. the type variable `vid`
+/// being instantiated with, or unified with, `value`.
+#[derive(Clone, Copy, Debug)]
+pub struct InferenceStep<'tcx> {
+    pub vid: TyVid,
+    /// The value `vid` was related to, as it was known at the time. This is another type
+    /// variable if two variables were unified.
+    pub value: Ty<'tcx>,
+    /// Where the relation which caused this step came from. May be dummy.
+    pub span: Span,
+}
+
 /// See the `error_reporting` module for more details.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, TypeFoldable, TypeVisitable)]
 pub enum ValuePairs<'tcx> {
@@ -625,6 +646,7 @@ pub fn build(&mut self, typing_mode: TypingMode<'tcx>) -> InferCtxt<'tcx> {
             tainted_by_errors: Cell::new(None),
             universe: Cell::new(ty::UniverseIndex::ROOT),
             next_trait_solver,
+            explain_inference: in_hir_typeck && tcx.sess.opts.unstable_opts.explain_inference,
             obligation_inspector: Cell::new(None),
         }
     }
@@ -1125,6 +1147,63 @@ pub fn root_var(&self, var: ty::TyVid) -> ty::TyVid {
         self.inner.borrow_mut().type_variables().root_var(var)
     }
 
+    /// `-Zexplain-inference`: records that `vid` was instantiated with, or unified with,
+    /// `value` because of a relation at `span`.
+    pub(crate) fn record_inference_step(&self, vid: ty::TyVid, value: Ty<'tcx>, span: Span) {
+        if self.explain_inference {
+            let mut inner = self.inner.borrow_mut();
+            inner.undo_log.push(UndoLog::PushInferenceStep);
+            inner.inference_steps.push(InferenceStep { vid, value, span });
+        }
+    }
+
+    /// `-Zexplain-inference`: returns the steps which determined the type variables in `tys`,
+    /// transitively following the variables they were related to, in the order in which they
+    /// happened. Always empty if the flag is not set.
+    pub fn inference_history(&self, tys: &[Ty<'tcx>]) -> Vec<InferenceStep<'tcx>> {
+        fn add_vars<'tcx>(
+            type_variables: &mut type_variable::TypeVariableTable<'_, 'tcx>,
+            ty: Ty<'tcx>,
+            relevant: &mut FxHashSet<TyVid>,
+        ) {
+            for arg in ty.walk() {
+                if let Some(ty) = arg.as_type()
+                    && let ty::Infer(ty::TyVar(vid)) = *ty.kind()
+                {
+                    relevant.insert(type_variables.root_var(vid));
+                }
+            }
+        }
+
+        if !self.explain_inference {
+            return vec![];
+        }
+        let mut inner = self.inner.borrow_mut();
+        let steps = inner.inference_steps.clone();
+        let mut type_variables = inner.type_variables();
+        let mut relevant = FxHashSet::default();
+        for &ty in tys {
+            add_vars(&mut type_variables, ty, &mut relevant);
+        }
+
+        // Relating a variable may make earlier steps relevant, so iterate to a fixpoint.
+        let mut included = vec![false; steps.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (step, included) in std::iter::zip(&steps, &mut included) {
+                if !*included && relevant.contains(&type_variables.root_var(step.vid)) {
+                    *included = true;
+                    changed = true;
+                    add_vars(&mut type_variables, step.value, &mut relevant);
+                }
+            }
+        }
+        std::iter::zip(steps, included)
+            .filter_map(|(step, included)| included.then_some(step))
+            .collect()
+    }
+
     pub fn root_const_var(&self, var: ty::ConstVid) -> ty::ConstVid {
         self.inner.borrow_mut().const_unification_table().find(var).vid
     }
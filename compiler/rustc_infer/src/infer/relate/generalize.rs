@@ -85,6 +85,7 @@ pub fn instantiate_ty_var<R: PredicateEmittingRelation<InferCtxt<'tcx>>>(
         } else {
             self.inner.borrow_mut().type_variables().instantiate(target_vid, generalized_ty);
         }
+        self.record_inference_step(target_vid, generalized_ty, relation.span());
 
         // See the comment on `Generalization::has_unconstrained_ty_var`.
         if has_unconstrained_ty_var {
@@ -161,6 +161,7 @@ fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
                     }
                     ty::Invariant => {
                         infcx.inner.borrow_mut().type_variables().equate(a_id, b_id);
+                        infcx.record_inference_step(a_id, b, self.span());
                     }
                     ty::Bivariant => {
                         unreachable!("Expected bivariance to be handled in relate_with_variance")
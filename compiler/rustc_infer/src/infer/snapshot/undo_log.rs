@@ -30,6 +30,7 @@ pub(crate) enum UndoLog<'tcx> {
     PushTypeOutlivesConstraint,
     PushRegionAssumption,
     PushHirTypeckPotentiallyRegionDependentGoal,
+    PushInferenceStep,
 }
 
 macro_rules! impl_from {
@@ -87,6 +88,10 @@ fn reverse(&mut self, undo: UndoLog<'tcx>) {
                 let popped = self.hir_typeck_potentially_region_dependent_goals.pop();
                 assert_matches!(popped, Some(_), "pushed goal but could not pop it");
             }
+            UndoLog::PushInferenceStep => {
+                let popped = self.inference_steps.pop();
+                assert_matches!(popped, Some(_), "pushed inference step but could not pop it");
+            }
         }
     }
 }
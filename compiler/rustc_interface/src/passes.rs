@@ -48,6 +48,7 @@
 use crate::{errors, limits, proc_macro_decls, util};
 
 pub fn parse<'a>(sess: &'a Session) -> ast::Crate {
+    let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::Parsing);
     let mut krate = sess
         .time("parse_crate", || {
             let mut parser = unwrap_or_emit_fatal(match &sess.io.input {
@@ -155,6 +156,8 @@ fn configure_and_expand(
 
     // Expand all macros
     krate = sess.time("macro_expand_crate", || {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::MacroExpansion);
+
         // Windows dlls do not have rpaths, so they don't know how to find their
         // dependencies. It's up to us to tell the system where to find all the
         // dependent dlls. Note that this uses cfg!(windows) as opposed to
@@ -292,7 +295,10 @@ fn configure_and_expand(
 
     // Done with macro expansion!
 
-    resolver.resolve_crate(&krate);
+    {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::NameResolution);
+        resolver.resolve_crate(&krate);
+    }
 
     CStore::from_tcx(tcx).report_incompatible_target_modifiers(tcx, &krate);
     CStore::from_tcx(tcx).report_incompatible_async_drop_feature(tcx, &krate);
@@ -1068,7 +1074,12 @@ fn run_required_analyses(tcx: TyCtxt<'_>) {
         );
     });
 
-    rustc_hir_analysis::check_crate(tcx);
+    {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::TypeChecking);
+        rustc_hir_analysis::check_crate(tcx);
+    }
+    sess.timings.emit_slowest_items(sess.dcx(), TimingSection::TypeChecking);
+
     // Freeze definitions as we don't add new ones at this point.
     // We need to wait until now since we synthesize a by-move body
     // for all coroutine-closures.
@@ -1077,11 +1088,18 @@ fn run_required_analyses(tcx: TyCtxt<'_>) {
     tcx.untracked().definitions.freeze();
 
     sess.time("MIR_borrow_checking", || {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::BorrowChecking);
         tcx.par_hir_body_owners(|def_id| {
             if !tcx.is_typeck_child(def_id.to_def_id()) {
                 // Child unsafety and borrowck happens together with the parent
-                tcx.ensure_ok().check_unsafety(def_id);
-                tcx.ensure_ok().mir_borrowck(def_id)
+                sess.timings.time_item(
+                    TimingSection::BorrowChecking,
+                    || tcx.def_path_str(def_id),
+                    || {
+                        tcx.ensure_ok().check_unsafety(def_id);
+                        tcx.ensure_ok().mir_borrowck(def_id)
+                    },
+                );
             }
             tcx.ensure_ok().has_ffi_unwind_calls(def_id);
 
@@ -1109,6 +1127,8 @@ fn run_required_analyses(tcx: TyCtxt<'_>) {
         });
     });
 
+    sess.timings.emit_slowest_items(sess.dcx(), TimingSection::BorrowChecking);
+
     sess.time("layout_testing", || layout_test::test_layout(tcx));
     sess.time("abi_testing", || abi_test::test_abi(tcx));
 
@@ -1220,7 +1240,11 @@ pub(crate) fn start_codegen<'tcx>(
 
     info!("Pre-codegen\n{:?}", tcx.debug_stats());
 
-    let metadata = rustc_metadata::fs::encode_and_write_metadata(tcx);
+    let metadata = {
+        let _timing =
+            tcx.sess.timings.section_guard(tcx.sess.dcx(), TimingSection::MetadataEncoding);
+        rustc_metadata::fs::encode_and_write_metadata(tcx)
+    };
 
     let codegen = tcx.sess.time("codegen_crate", move || codegen_backend.codegen_crate(tcx));
 
@@ -5,7 +5,7 @@
 use std::sync::atomic::AtomicBool;
 
 use rustc_abi::Align;
-use rustc_data_structures::profiling::TimePassesFormat;
+use rustc_data_structures::profiling::{SelfProfileFormat, TimePassesFormat};
 use rustc_errors::emitter::HumanReadableErrorType;
 use rustc_errors::{ColorConfig, registry};
 use rustc_session::config::{
@@ -14,9 +14,9 @@
     Externs, FmtDebug, FunctionReturn, InliningThreshold, Input, InstrumentCoverage,
     InstrumentXRay, LinkSelfContained, LinkerPluginLto, LocationDetail, LtoCli, MirIncludeSpans,
     NextSolverConfig, Offload, OomStrategy, Options, OutFileName, OutputType, OutputTypes,
-    PAuthKey, PacRet, Passes, PatchableFunctionEntry, Polonius, ProcMacroExecutionStrategy, Strip,
-    SwitchWithOptPath, SymbolManglingVersion, WasiExecModel, build_configuration,
-    build_session_options, rustc_optgroups,
+    PAuthKey, PacRet, Passes, PatchableFunctionEntry, Polonius, ProcMacroExecutionStrategy,
+    SizeReportFormat, Strip, SwitchWithOptPath, SymbolManglingVersion, TrackedFile, WasiExecModel,
+    build_configuration, build_session_options, rustc_optgroups,
 };
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
@@ -701,11 +701,13 @@ macro_rules! untracked {
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
+    untracked!(explain_inference, true);
     untracked!(future_incompat_test, true);
     untracked!(identify_regions, true);
     untracked!(incremental_info, true);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
+    untracked!(json_call_graph, true);
     untracked!(link_native_libraries, false);
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
@@ -713,6 +715,7 @@ macro_rules! untracked {
     untracked!(macro_stats, true);
     untracked!(meta_stats, true);
     untracked!(mir_include_spans, MirIncludeSpans::On);
+    untracked!(next_solver_dump, Some("proof-trees".into()));
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
     untracked!(no_leak_check, true);
@@ -731,7 +734,9 @@ macro_rules! untracked {
     untracked!(query_dep_graph, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_format, SelfProfileFormat::Chrome);
     untracked!(shell_argfiles, true);
+    untracked!(size_report, Some(SizeReportFormat::Json));
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
@@ -773,6 +778,7 @@ macro_rules! tracked {
             pac_ret: Some(PacRet { leaf: true, pc: true, key: PAuthKey::B })
         })
     );
+    tracked!(cgu_profile, Some(TrackedFile::new(PathBuf::from("abc"))));
     tracked!(codegen_backend, Some("abc".to_string()));
     tracked!(
         coverage_options,
@@ -823,6 +829,7 @@ macro_rules! tracked {
     tracked!(mir_emit_retag, true);
     tracked!(mir_enable_passes, vec![("DestProp".to_string(), false)]);
     tracked!(mir_opt_level, Some(4));
+    tracked!(mir_outline_cold_paths, true);
     tracked!(mir_preserve_ub, true);
     tracked!(move_size_limit, Some(4096));
     tracked!(mutable_noalias, false);
@@ -899,6 +906,23 @@ macro_rules! tracked_no_crate_hash {
     tracked_no_crate_hash!(verbose_internals, true);
 }
 
+#[test]
+fn test_tracked_file_contents_tracking_hash() {
+    // Editing a file like the profile of `-Z cgu-profile` changes the hash, even
+    // though its path stays the same.
+    let path = std::env::temp_dir().join(format!("rustc-tracked-file-{}", std::process::id()));
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+
+    std::fs::write(&path, "main;hot 100\n").unwrap();
+    v1.unstable_opts.cgu_profile = Some(TrackedFile::new(path.clone()));
+    std::fs::write(&path, "main;cold 100\n").unwrap();
+    v2.unstable_opts.cgu_profile = Some(TrackedFile::new(path.clone()));
+    std::fs::remove_file(&path).unwrap();
+
+    assert_different_hash(&v1, &v2);
+}
+
 #[test]
 fn test_edition_parsing() {
     // test default edition
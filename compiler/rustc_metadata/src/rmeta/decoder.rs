@@ -785,6 +785,7 @@ pub(crate) fn list_crate_metadata(
                         root.hash(),
                         root.stable_crate_id
                     )?;
+                    writeln!(out, "interface_hash {}", root.header.interface_hash)?;
                     writeln!(out, "proc_macro {:?}", root.proc_macro_data.is_some())?;
                     writeln!(out, "triple {}", root.header.triple.tuple())?;
                     writeln!(out, "edition {}", root.edition)?;
@@ -817,13 +818,20 @@ pub(crate) fn list_crate_metadata(
                     let dylib_dependency_formats =
                         root.dylib_dependency_formats.decode(self).collect::<Vec<_>>();
                     for (i, dep) in root.crate_deps.decode(self).enumerate() {
-                        let CrateDep { name, extra_filename, hash, host_hash, kind, is_private } =
-                            dep;
+                        let CrateDep {
+                            name,
+                            extra_filename,
+                            hash,
+                            interface_hash,
+                            host_hash,
+                            kind,
+                            is_private,
+                        } = dep;
                         let number = i + 1;
 
                         writeln!(
                             out,
-                            "{number} {name}{extra_filename} hash {hash} host_hash {host_hash:?} kind {kind:?} {privacy}{linkage}",
+                            "{number} {name}{extra_filename} hash {hash} interface_hash {interface_hash} host_hash {host_hash:?} kind {kind:?} {privacy}{linkage}",
                             privacy = if is_private { "private" } else { "public" },
                             linkage = if dylib_dependency_formats.is_empty() {
                                 String::new()
@@ -374,6 +374,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     native_libraries => { cdata.get_native_libraries(tcx.sess).collect() }
     foreign_modules => { cdata.get_foreign_modules(tcx.sess).map(|m| (m.def_id, m)).collect() }
     crate_hash => { cdata.root.header.hash }
+    interface_hash => { cdata.root.header.interface_hash }
     crate_host_hash => { cdata.host_hash }
     crate_name => { cdata.root.header.name }
     num_extern_def_ids => { cdata.num_def_ids() }
@@ -723,6 +723,7 @@ macro_rules! stat {
                     name: tcx.crate_name(LOCAL_CRATE),
                     triple: tcx.sess.opts.target_triple.clone(),
                     hash: tcx.crate_hash(LOCAL_CRATE),
+                    interface_hash: tcx.interface_hash(LOCAL_CRATE),
                     is_proc_macro_crate: proc_macro_data.is_some(),
                     is_stub: false,
                 },
@@ -1011,7 +1012,7 @@ fn should_encode_expn_that_defined(def_kind: DefKind) -> bool {
     }
 }
 
-fn should_encode_visibility(def_kind: DefKind) -> bool {
+pub(super) fn should_encode_visibility(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Mod
         | DefKind::Struct
@@ -1048,7 +1049,7 @@ fn should_encode_visibility(def_kind: DefKind) -> bool {
     }
 }
 
-fn should_encode_stability(def_kind: DefKind) -> bool {
+pub(super) fn should_encode_stability(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Mod
         | DefKind::Ctor(..)
@@ -1104,7 +1105,7 @@ fn should_encode_stability(def_kind: DefKind) -> bool {
 /// const fn f() -> usize { 0 }
 /// pub struct S { pub a: [usize; f()] }
 /// ```
-fn should_encode_mir(
+pub(super) fn should_encode_mir(
     tcx: TyCtxt<'_>,
     reachable_set: &LocalDefIdSet,
     def_id: LocalDefId,
@@ -1141,7 +1142,11 @@ fn should_encode_mir(
     }
 }
 
-fn should_encode_variances<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, def_kind: DefKind) -> bool {
+pub(super) fn should_encode_variances<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    def_kind: DefKind,
+) -> bool {
     match def_kind {
         DefKind::Struct
         | DefKind::Union
@@ -1180,7 +1185,7 @@ fn should_encode_variances<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, def_kind: Def
     }
 }
 
-fn should_encode_generics(def_kind: DefKind) -> bool {
+pub(super) fn should_encode_generics(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Struct
         | DefKind::Union
@@ -1216,7 +1221,7 @@ fn should_encode_generics(def_kind: DefKind) -> bool {
     }
 }
 
-fn should_encode_type(tcx: TyCtxt<'_>, def_id: LocalDefId, def_kind: DefKind) -> bool {
+pub(super) fn should_encode_type(tcx: TyCtxt<'_>, def_id: LocalDefId, def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Struct
         | DefKind::Union
@@ -1277,7 +1282,7 @@ fn should_encode_type(tcx: TyCtxt<'_>, def_id: LocalDefId, def_kind: DefKind) ->
     }
 }
 
-fn should_encode_fn_sig(def_kind: DefKind) -> bool {
+pub(super) fn should_encode_fn_sig(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Fn | DefKind::AssocFn | DefKind::Ctor(_, CtorKind::Fn) => true,
 
@@ -1313,7 +1318,7 @@ fn should_encode_fn_sig(def_kind: DefKind) -> bool {
     }
 }
 
-fn should_encode_constness(def_kind: DefKind) -> bool {
+pub(super) fn should_encode_constness(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Fn | DefKind::AssocFn | DefKind::Closure | DefKind::Ctor(_, CtorKind::Fn) => true,
 
@@ -2024,6 +2029,7 @@ fn encode_crate_deps(&mut self) -> LazyArray<CrateDep> {
                 let dep = CrateDep {
                     name: self.tcx.crate_name(cnum),
                     hash: self.tcx.crate_hash(cnum),
+                    interface_hash: self.tcx.interface_hash(cnum),
                     host_hash: self.tcx.crate_host_hash(cnum),
                     kind: self.tcx.dep_kind(cnum),
                     extra_filename: self.tcx.extra_filename(cnum).clone(),
@@ -2375,6 +2381,7 @@ pub fn encode_metadata(tcx: TyCtxt<'_>, path: &Path, ref_path: Option<&Path>) {
                 name: tcx.crate_name(LOCAL_CRATE),
                 triple: tcx.sess.opts.target_triple.clone(),
                 hash: tcx.crate_hash(LOCAL_CRATE),
+                interface_hash: tcx.interface_hash(LOCAL_CRATE),
                 is_proc_macro_crate: false,
                 is_stub: true,
             });
@@ -2514,6 +2521,7 @@ fn encode_root_position(mut file: &File, pos: usize) -> Result<(), std::io::Erro
 
 pub(crate) fn provide(providers: &mut Providers) {
     *providers = Providers {
+        interface_hash: interface_hash::interface_hash,
         doc_link_resolutions: |tcx, def_id| {
             tcx.resolutions(())
                 .doc_link_resolutions
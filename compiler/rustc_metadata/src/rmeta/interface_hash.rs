@@ -0,0 +1,326 @@
+//! The interface hash of a crate.
+//!
+//! Unlike the crate hash, which changes whenever anything about the crate changes, the interface
+//! hash only covers what downstream crates can observe of it: the items reachable from its public
+//! API with their signatures, generics and predicates, the fields of the types they mention (which
+//! determine their layout), all trait impls, exported macros and re-exports, and the MIR which is
+//! encoded for inlining, monomorphization and const evaluation, including its spans. It also
+//! covers the symbols exported by the crate and the indices of its definitions and expansions, as
+//! the metadata of downstream crates refers to those.
+//!
+//! Build systems can use it to avoid rebuilding dependent crates after changes to private items,
+//! and read it with `-Z ls=root`; it is not available as a `--print` request, as those are
+//! answered before the crate is compiled. Downstream crates also record the interface hash of
+//! their dependencies, see `CrateDep`, but only for information: the crate loader still matches
+//! dependencies on their crate hash.
+
+use std::hash::Hash;
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_data_structures::svh::Svh;
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefPathHash, LOCAL_CRATE, LocalDefId, StableCrateId};
+use rustc_middle::mir::Body;
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::query::LocalCrate;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_query_system::ich::StableHashingContext;
+use rustc_session::config::{CrateType, OptLevel};
+use rustc_span::Span;
+use rustc_span::hygiene::local_expn_hashes;
+use rustc_span::source_map::SourceMap;
+
+use super::encoder::{
+    should_encode_constness, should_encode_fn_sig, should_encode_generics, should_encode_mir,
+    should_encode_stability, should_encode_type, should_encode_variances, should_encode_visibility,
+};
+
+pub(super) fn interface_hash(tcx: TyCtxt<'_>, _: LocalCrate) -> Svh {
+    let mut worklist: Vec<_> = tcx
+        .hir_crate_items(())
+        .definitions()
+        .filter(|&def_id| is_observable(tcx, def_id))
+        .collect();
+    let mut visited = FxHashSet::default();
+    let mut hashes = Vec::new();
+    let upstream_crates = upstream_crates(tcx);
+
+    let interface_hash = tcx.with_stable_hashing_context(|mut hcx| {
+        // Where an item is defined is only observable through its MIR and macros, which hash the
+        // positions of their spans themselves.
+        hcx.while_hashing_spans(false, |hcx| {
+            while let Some(def_id) = worklist.pop() {
+                if visited.insert(def_id) {
+                    let hash = hash_item(tcx, hcx, def_id, &mut worklist);
+                    hashes.push((tcx.def_path_hash(def_id.to_def_id()), hash));
+                }
+            }
+        });
+        hash_encoded_mir(tcx, &mut hcx, &mut hashes);
+        // An item may have both its own hash and the hash of its MIR.
+        hashes.sort_unstable();
+
+        let mut stable_hasher = StableHasher::new();
+        hashes.hash_stable(&mut hcx, &mut stable_hasher);
+        hash_indices(tcx, &mut stable_hasher);
+        if !tcx.crate_types().contains(&CrateType::ProcMacro) {
+            tcx.exported_non_generic_symbols(LOCAL_CRATE).hash_stable(&mut hcx, &mut stable_hasher);
+            tcx.exported_generic_symbols(LOCAL_CRATE).hash_stable(&mut hcx, &mut stable_hasher);
+        }
+        upstream_crates.hash_stable(&mut hcx, &mut stable_hasher);
+        tcx.stable_crate_id(LOCAL_CRATE).hash_stable(&mut hcx, &mut stable_hasher);
+        tcx.sess.opts.dep_tracking_hash(true).hash_stable(&mut hcx, &mut stable_hasher);
+        stable_hasher.finish()
+    });
+
+    Svh::new(interface_hash)
+}
+
+/// Downstream crates refer to the definitions and expansions of this crate by their index, so
+/// those must not change either. This makes the interface hash change when a definition or a
+/// macro invocation is added anywhere, even to the body of a private function.
+fn hash_indices(tcx: TyCtxt<'_>, hasher: &mut StableHasher) {
+    for def_id in tcx.iter_local_def_id() {
+        tcx.def_path_hash(def_id.to_def_id()).hash(hasher);
+    }
+    local_expn_hashes().hash(hasher);
+}
+
+/// Hashes where `span` is in its source file. Downstream crates encode spans pointing into the
+/// source files of this crate as byte offsets into them, and the lines and columns hashed for
+/// spans by `HashStable` stay the same when, e.g., an earlier line gets longer.
+fn hash_span_position(source_map: &SourceMap, span: Span, hasher: &mut StableHasher) {
+    let span = span.data_untracked();
+    if span.is_dummy() {
+        return;
+    }
+    let file = source_map.lookup_source_file(span.lo);
+    file.stable_id.hash(hasher);
+    (span.lo - file.start_pos).0.hash(hasher);
+    (span.hi - span.lo).0.hash(hasher);
+}
+
+struct SpanPositionHasher<'a> {
+    source_map: &'a SourceMap,
+    hasher: &'a mut StableHasher,
+}
+
+impl<'tcx> Visitor<'tcx> for SpanPositionHasher<'_> {
+    fn visit_span(&mut self, span: Span) {
+        hash_span_position(self.source_map, span, self.hasher);
+    }
+}
+
+/// The interface of an upstream crate may be observable through this crate, e.g. through the
+/// layout of a type containing one of its types.
+fn upstream_crates(tcx: TyCtxt<'_>) -> Vec<(StableCrateId, Svh)> {
+    let mut upstream_crates: Vec<_> = tcx
+        .crates(())
+        .iter()
+        .map(|&cnum| (tcx.stable_crate_id(cnum), tcx.interface_hash(cnum)))
+        .collect();
+    upstream_crates.sort_unstable_by_key(|&(stable_crate_id, _)| stable_crate_id);
+    upstream_crates
+}
+
+/// Whether downstream crates can observe `def_id`, either through the public API of this crate
+/// or because inlinable code refers to it.
+///
+/// Items only mentioned by the types of other observable items, like the types of private fields,
+/// are added as they are found by `hash_item`.
+fn is_observable(tcx: TyCtxt<'_>, def_id: LocalDefId) -> bool {
+    if tcx.effective_visibilities(()).is_reachable(def_id)
+        || tcx.reachable_set(()).contains(&def_id)
+    {
+        return true;
+    }
+    match tcx.def_kind(def_id) {
+        // Trait impls apply in downstream crates regardless of visibility.
+        DefKind::Impl { of_trait } => of_trait,
+        DefKind::AssocFn | DefKind::AssocConst | DefKind::AssocTy => {
+            matches!(tcx.def_kind(tcx.local_parent(def_id)), DefKind::Impl { of_trait: true })
+        }
+        DefKind::Macro(_) => tcx.visibility(def_id).is_public(),
+        _ => false,
+    }
+}
+
+/// Hashes everything about `def_id` which is encoded in the crate metadata and which downstream
+/// crates can observe, except for its MIR. Local types and opaque types mentioned by it are added
+/// to the `worklist`.
+fn hash_item<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    hcx: &mut StableHashingContext<'_>,
+    def_id: LocalDefId,
+    worklist: &mut Vec<LocalDefId>,
+) -> Fingerprint {
+    let mut hasher = StableHasher::new();
+    let def_kind = tcx.def_kind(def_id);
+    def_kind.hash_stable(hcx, &mut hasher);
+
+    if should_encode_visibility(def_kind) {
+        tcx.visibility(def_id).hash_stable(hcx, &mut hasher);
+    }
+    if should_encode_stability(def_kind) {
+        tcx.lookup_stability(def_id).hash_stable(hcx, &mut hasher);
+        tcx.lookup_const_stability(def_id).hash_stable(hcx, &mut hasher);
+        tcx.lookup_deprecation_entry(def_id).hash_stable(hcx, &mut hasher);
+    }
+    if should_encode_variances(tcx, def_id.to_def_id(), def_kind) {
+        tcx.variances_of(def_id).hash_stable(hcx, &mut hasher);
+    }
+    if should_encode_generics(def_kind) {
+        tcx.generics_of(def_id).hash_stable(hcx, &mut hasher);
+        tcx.explicit_predicates_of(def_id).hash_stable(hcx, &mut hasher);
+        tcx.inferred_outlives_of(def_id).hash_stable(hcx, &mut hasher);
+    }
+    if should_encode_type(tcx, def_id, def_kind) {
+        let ty = tcx.type_of(def_id);
+        ty.hash_stable(hcx, &mut hasher);
+        add_local_types(ty.instantiate_identity(), worklist);
+    }
+    if should_encode_fn_sig(def_kind) {
+        let fn_sig = tcx.fn_sig(def_id);
+        fn_sig.hash_stable(hcx, &mut hasher);
+        for &ty in fn_sig.instantiate_identity().skip_binder().inputs_and_output {
+            add_local_types(ty, worklist);
+        }
+    }
+    if should_encode_constness(def_kind) {
+        tcx.constness(def_id).hash_stable(hcx, &mut hasher);
+    }
+    if def_kind.has_codegen_attrs() {
+        tcx.codegen_fn_attrs(def_id).hash_stable(hcx, &mut hasher);
+    }
+
+    match def_kind {
+        DefKind::Struct | DefKind::Enum | DefKind::Union => {
+            let adt_def = tcx.adt_def(def_id);
+            adt_def.hash_stable(hcx, &mut hasher);
+            // Private fields are observable through the layout of the type.
+            for field in adt_def.all_fields() {
+                let ty = tcx.type_of(field.did);
+                ty.hash_stable(hcx, &mut hasher);
+                add_local_types(ty.instantiate_identity(), worklist);
+            }
+        }
+        DefKind::Trait => {
+            tcx.trait_def(def_id).hash_stable(hcx, &mut hasher);
+            tcx.explicit_super_predicates_of(def_id).hash_stable(hcx, &mut hasher);
+            tcx.explicit_implied_predicates_of(def_id).hash_stable(hcx, &mut hasher);
+        }
+        DefKind::Impl { of_trait: true } => {
+            tcx.impl_trait_header(def_id).hash_stable(hcx, &mut hasher);
+        }
+        DefKind::AssocFn | DefKind::AssocConst => {
+            tcx.associated_item(def_id).hash_stable(hcx, &mut hasher);
+        }
+        DefKind::AssocTy => {
+            tcx.associated_item(def_id).hash_stable(hcx, &mut hasher);
+            tcx.explicit_item_bounds(def_id).hash_stable(hcx, &mut hasher);
+        }
+        DefKind::OpaqueTy => {
+            tcx.explicit_item_bounds(def_id).hash_stable(hcx, &mut hasher);
+        }
+        DefKind::Mod => {
+            // Private items and imports are not observable.
+            let children: Vec<_> = tcx
+                .module_children_local(def_id)
+                .iter()
+                .filter(|child| child.vis.is_public())
+                .collect();
+            children.hash_stable(hcx, &mut hasher);
+        }
+        DefKind::Macro(_) => {
+            if let hir::ItemKind::Macro(_, macro_def, _) = tcx.hir_expect_item(def_id).kind {
+                macro_def.hash_stable(hcx, &mut hasher);
+            }
+            // The tokens of the macro keep their spans in the crates it is expanded in, which
+            // changes with the layout of its source code even if the tokens stay the same.
+            let source_map = tcx.sess.source_map();
+            let span = tcx.source_span(def_id);
+            hash_span_position(source_map, span, &mut hasher);
+            source_map.span_to_snippet(span).ok().hash(&mut hasher);
+        }
+        // The values of immutable statics can be read during const evaluation.
+        DefKind::Static { mutability: hir::Mutability::Not, nested: false, .. }
+            if !tcx.is_foreign_item(def_id) =>
+        {
+            tcx.eval_static_initializer(def_id).ok().hash_stable(hcx, &mut hasher);
+        }
+        _ => {}
+    }
+
+    hasher.finish()
+}
+
+/// Adds the local types and opaque types in `ty` to the `worklist`, so that the fields or hidden
+/// types they are defined by are hashed as well.
+fn add_local_types<'tcx>(ty: Ty<'tcx>, worklist: &mut Vec<LocalDefId>) {
+    for arg in ty.walk() {
+        let ty::GenericArgKind::Type(ty) = arg.kind() else { continue };
+        let def_id = match *ty.kind() {
+            ty::Adt(adt_def, _) => adt_def.did(),
+            ty::Alias(ty::Opaque, alias_ty) => alias_ty.def_id,
+            _ => continue,
+        };
+        if let Some(def_id) = def_id.as_local() {
+            worklist.push(def_id);
+        }
+    }
+}
+
+/// Hashes the MIR which is encoded in the crate metadata, see `should_encode_mir`, including its
+/// spans.
+fn hash_encoded_mir(
+    tcx: TyCtxt<'_>,
+    hcx: &mut StableHashingContext<'_>,
+    hashes: &mut Vec<(DefPathHash, Fingerprint)>,
+) {
+    if tcx.crate_types().contains(&CrateType::ProcMacro) {
+        return;
+    }
+
+    let reachable_set = tcx.reachable_set(());
+    // Calls to functions in other crates are annotated with their deduced parameter attributes.
+    let hash_deduced_param_attrs = tcx.sess.opts.output_types.should_codegen()
+        && tcx.sess.opts.optimize != OptLevel::No
+        && tcx.sess.opts.incremental.is_none();
+
+    for &def_id in tcx.mir_keys(()) {
+        let (encode_const, encode_opt) = should_encode_mir(tcx, reachable_set, def_id);
+        let is_reachable_fn = matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && reachable_set.contains(&def_id);
+        if !encode_const && !encode_opt && !(hash_deduced_param_attrs && is_reachable_fn) {
+            continue;
+        }
+
+        let mut hasher = StableHasher::new();
+        let mut hash_body = |hcx: &mut StableHashingContext<'_>, body: &Body<'_>| {
+            hcx.while_hashing_spans(true, |hcx| body.hash_stable(hcx, &mut hasher));
+            SpanPositionHasher { source_map: tcx.sess.source_map(), hasher: &mut hasher }
+                .visit_body(body);
+        };
+        if encode_opt {
+            hash_body(hcx, tcx.optimized_mir(def_id));
+        }
+        if encode_const {
+            hash_body(hcx, tcx.mir_for_ctfe(def_id));
+        }
+        if encode_const || encode_opt {
+            for promoted in tcx.promoted_mir(def_id) {
+                hash_body(hcx, promoted);
+            }
+        }
+        if encode_opt {
+            tcx.cross_crate_inlinable(def_id).hash_stable(hcx, &mut hasher);
+        }
+        if hash_deduced_param_attrs && is_reachable_fn {
+            tcx.deduced_param_attrs(def_id).hash_stable(hcx, &mut hasher);
+        }
+        hashes.push((tcx.def_path_hash(def_id.to_def_id()), hasher.finish()));
+    }
+}
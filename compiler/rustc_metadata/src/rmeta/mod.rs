@@ -8,8 +8,10 @@
 pub use encoder::{EncodedMetadata, encode_metadata, rendered_const};
 pub(crate) use parameterized::ParameterizedOverTcx;
 use rustc_abi::{FieldIdx, ReprOptions, VariantIdx};
+use rustc_ast as ast;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::svh::Svh;
+use rustc_hir as hir;
 use rustc_hir::attrs::StrippedCfgItem;
 use rustc_hir::def::{CtorKind, DefKind, DocLinkResMap};
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, DefIndex, DefPathHash, StableCrateId};
@@ -39,13 +41,13 @@
 use rustc_span::{self, ExpnData, ExpnHash, ExpnId, Ident, Span, Symbol};
 use rustc_target::spec::{PanicStrategy, TargetTuple};
 use table::TableBuilder;
-use {rustc_ast as ast, rustc_hir as hir};
 
 use crate::creader::CrateMetadataRef;
 
 mod decoder;
 mod def_path_hash_map;
 mod encoder;
+mod interface_hash;
 mod parameterized;
 mod table;
 
@@ -56,7 +58,7 @@ pub(crate) fn rustc_version(cfg_version: &'static str) -> String {
 /// Metadata encoding version.
 /// N.B., increment this if you change the format of metadata such that
 /// the rustc version can't be found to compare with `rustc_version()`.
-const METADATA_VERSION: u8 = 10;
+const METADATA_VERSION: u8 = 11;
 
 /// Metadata header which includes `METADATA_VERSION`.
 ///
@@ -203,6 +205,9 @@ pub(crate) struct ProcMacroData {
 pub(crate) struct CrateHeader {
     pub(crate) triple: TargetTuple,
     pub(crate) hash: Svh,
+    /// The hash of everything downstream crates can observe of this crate,
+    /// see the `interface_hash` query.
+    pub(crate) interface_hash: Svh,
     pub(crate) name: Symbol,
     /// Whether this is the header for a proc-macro crate.
     ///
@@ -325,6 +330,10 @@ fn decode_from_cdata(self, cdata: CrateMetadataRef<'_>) -> DefId {
 pub(crate) struct CrateDep {
     pub name: Symbol,
     pub hash: Svh,
+    /// The interface hash of the dependency. Unlike `hash`, it is informational only: crates
+    /// found for the dependency are matched on their crate hash.
+    pub interface_hash: Svh,
+    /// The crate hash of the host proc macro crate, for `-Z dual-proc-macros`.
     pub host_hash: Option<Svh>,
     pub kind: CrateDepKind,
     pub extra_filename: String,
@@ -102,6 +102,10 @@ fn clone(&self) -> Self { *self }
     /// Ensure the given scalar is valid for the given type.
     /// This checks non-recursive runtime validity.
     hook validate_scalar_in_layout(scalar: crate::ty::ScalarInt, ty: Ty<'tcx>) -> bool;
+
+    /// Checks the types in `body` like MIR validation does, and returns the failures.
+    /// This is used to check MIR bodies that are built outside of the compiler.
+    hook validate_mir_types(body: &mir::Body<'tcx>) -> Vec<(mir::Location, String)>;
 }
 
 #[cold]
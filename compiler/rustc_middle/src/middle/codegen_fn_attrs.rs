@@ -17,6 +17,18 @@ pub fn codegen_instance_attrs(
     ) -> Cow<'tcx, CodegenFnAttrs> {
         let mut attrs = Cow::Borrowed(self.codegen_fn_attrs(instance_kind.def_id()));
 
+        // Outlined cold paths are internal functions, which only share the attributes their code
+        // depends on with the function they come from.
+        if let InstanceKind::OutlinedColdPath(..) = instance_kind {
+            let mut outlined = CodegenFnAttrs::new();
+            outlined.flags = CodegenFnAttrFlags::COLD;
+            outlined.inline = InlineAttr::Never;
+            outlined.target_features = attrs.target_features.clone();
+            outlined.no_sanitize = attrs.no_sanitize;
+            outlined.instruction_set = attrs.instruction_set;
+            return Cow::Owned(outlined);
+        }
+
         // Drop the `#[naked]` attribute on non-item `InstanceKind`s, like the shims that
         // are generated for indirect function calls.
         if !matches!(instance_kind, InstanceKind::Item(_)) {
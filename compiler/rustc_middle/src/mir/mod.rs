@@ -279,6 +279,10 @@ pub struct Body<'tcx> {
     /// information.
     pub coroutine: Option<Box<CoroutineInfo<'tcx>>>,
 
+    /// The bodies of cold paths which the `OutlineColdPaths` pass moved out of this body, which
+    /// are the bodies of the `InstanceKind::OutlinedColdPath` instances of this function.
+    pub outlined_cold_paths: Vec<Body<'tcx>>,
+
     /// Declarations of locals.
     ///
     /// The first local is the return value pointer, followed by `arg_count`
@@ -405,6 +409,7 @@ pub fn new(
             basic_blocks: BasicBlocks::new(basic_blocks),
             source_scopes,
             coroutine,
+            outlined_cold_paths: Vec::new(),
             local_decls,
             user_type_annotations,
             arg_count,
@@ -436,6 +441,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             basic_blocks: BasicBlocks::new(basic_blocks),
             source_scopes: IndexVec::new(),
             coroutine: None,
+            outlined_cold_paths: Vec::new(),
             local_decls: IndexVec::new(),
             user_type_annotations: IndexVec::new(),
             arg_count: 0,
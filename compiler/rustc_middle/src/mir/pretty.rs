@@ -290,6 +290,7 @@ fn dump_path<'tcx>(
             }));
             s
         }
+        ty::InstanceKind::OutlinedColdPath(_, index) => format!(".cold{index}"),
         _ => String::new(),
     };
 
@@ -2008,6 +2008,15 @@
         separate_provide_extern
     }
 
+    /// Gets the hash of the interface of a crate: unlike the `crate_hash`, it only covers what
+    /// downstream crates can observe, so it does not change when only private items change.
+    query interface_hash(_: CrateNum) -> Svh {
+        // Accesses untracked data
+        eval_always
+        desc { "computing the interface hash of a crate" }
+        separate_provide_extern
+    }
+
     /// Gets the hash for the host proc macro. Used to support -Z dual-proc-macro.
     query crate_host_hash(_: CrateNum) -> Option<Svh> {
         eval_always
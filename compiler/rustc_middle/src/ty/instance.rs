@@ -149,6 +149,13 @@ pub enum InstanceKind<'tcx> {
     /// Proxy shim for async drop of future (def_id, proxy_cor_ty, impl_cor_ty)
     FutureDropPollShim(DefId, Ty<'tcx>, Ty<'tcx>),
 
+    /// A cold path of the optimized MIR of a non-generic function, which the `OutlineColdPaths`
+    /// MIR pass moved into a separate function.
+    ///
+    /// The `DefId` is for the function, the `usize` is the index of the outlined body in the
+    /// `outlined_cold_paths` of its optimized MIR.
+    OutlinedColdPath(DefId, usize),
+
     /// `core::ptr::drop_in_place::<T>`.
     ///
     /// The `DefId` is for `core::ptr::drop_in_place`.
@@ -250,6 +257,7 @@ pub fn def_id(self) -> DefId {
             | InstanceKind::Virtual(def_id, _)
             | InstanceKind::Intrinsic(def_id)
             | InstanceKind::ThreadLocalShim(def_id)
+            | InstanceKind::OutlinedColdPath(def_id, _)
             | InstanceKind::ClosureOnceShim { call_once: def_id, track_caller: _ }
             | ty::InstanceKind::ConstructCoroutineInClosureShim {
                 coroutine_closure_def_id: def_id,
@@ -282,7 +290,8 @@ pub fn def_id_if_not_guaranteed_local_codegen(self) -> Option<DefId> {
             | ty::InstanceKind::ConstructCoroutineInClosureShim { .. }
             | InstanceKind::DropGlue(..)
             | InstanceKind::CloneShim(..)
-            | InstanceKind::FnPtrAddrShim(..) => None,
+            | InstanceKind::FnPtrAddrShim(..)
+            | InstanceKind::OutlinedColdPath(..) => None,
         }
     }
 
@@ -309,6 +318,8 @@ pub fn requires_inline(&self, tcx: TyCtxt<'tcx>) -> bool {
             ty::InstanceKind::FutureDropPollShim(_, _, _) => return false,
             ty::InstanceKind::AsyncDropGlue(_, _) => return false,
             ty::InstanceKind::ThreadLocalShim(_) => return false,
+            // Cold paths are outlined so that they are not inlined.
+            ty::InstanceKind::OutlinedColdPath(..) => return false,
             _ => return true,
         };
         matches!(
@@ -348,6 +359,7 @@ pub fn has_polymorphic_mir_body(&self) -> bool {
             | InstanceKind::DropGlue(..)
             | InstanceKind::Item(_)
             | InstanceKind::Intrinsic(..)
+            | InstanceKind::OutlinedColdPath(..)
             | InstanceKind::ReifyShim(..)
             | InstanceKind::Virtual(..)
             | InstanceKind::VTableShim(..) => true,
@@ -1710,6 +1710,9 @@ pub fn instance_mir(self, instance: ty::InstanceKind<'tcx>) -> &'tcx Body<'tcx>
             | ty::InstanceKind::FnPtrAddrShim(..)
             | ty::InstanceKind::AsyncDropGlueCtorShim(..)
             | ty::InstanceKind::AsyncDropGlue(..) => self.mir_shims(instance),
+            ty::InstanceKind::OutlinedColdPath(def_id, index) => {
+                &self.optimized_mir(def_id).outlined_cold_paths[index]
+            }
         }
     }
 
@@ -329,6 +329,9 @@ fn print(&self, cx: &mut P) -> Result<(), PrintError> {
                 cx.write_str(" - shim(reify-vtable)")?
             }
             ty::InstanceKind::ThreadLocalShim(_) => cx.write_str(" - shim(tls)")?,
+            ty::InstanceKind::OutlinedColdPath(_, index) => {
+                cx.write_str(&format!(" - shim(cold#{index})"))?
+            }
             ty::InstanceKind::Intrinsic(_) => cx.write_str(" - intrinsic")?,
             ty::InstanceKind::Virtual(_, num) => cx.write_str(&format!(" - virtual#{num}"))?,
             ty::InstanceKind::FnPtrShim(_, ty) => cx.write_str(&format!(" - shim({ty})"))?,
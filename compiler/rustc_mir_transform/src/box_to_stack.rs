@@ -0,0 +1,493 @@
+//! Replaces `Box` allocations which never escape the function with locals.
+//!
+//! A box is either allocated by a call to `Box::new`, or by an `exchange_malloc` call whose result
+//! is turned into a box by a `ShallowInitBox` statement, which is what the `box_new` intrinsic
+//! becomes. Like after `ElaborateBoxDerefs`, the contents of the box are only accessed through
+//! pointers which are transmuted from the box:
+//!
+//! ```ignore (MIR)
+//! _2 = alloc::alloc::exchange_malloc(const 4_usize, const 4_usize) -> [return: bb1, ...];
+//! _3 = ShallowInitBox(move _2, i32);
+//! _4 = copy ((_3.0: Unique<i32>).0: NonNull<i32>) as *const i32 (Transmute);
+//! (*_4) = const 42_i32;
+//! _5 = move _3;
+//! _6 = copy ((_5.0: Unique<i32>).0: NonNull<i32>) as *const i32 (Transmute);
+//! _1 = copy (*_6);
+//! drop(_5) -> [return: bb2, ...];
+//! ```
+//!
+//! If the box is only moved between locals, dereferenced and dropped, it cannot be observed by
+//! any other code: it is not stored, returned, borrowed or passed to another function, only its
+//! contents may be. In that case the allocation is removed, the contents are accessed through a
+//! new local instead, and dropping the box only drops that local:
+//!
+//! ```ignore (MIR)
+//! goto -> bb1;
+//! _7 = const 42_i32;
+//! _1 = copy _7;
+//! goto -> bb2;
+//! ```
+//!
+//! A call to `Box::new` is replaced in the same way, with the argument of the call moved into the
+//! new local.
+//!
+//! As there is only one local for all allocations of the same call, no box holding an earlier
+//! allocation of it may be live when it allocates again, e.g. in a loop.
+//!
+//! The inliner does not inline `Box::new` and `exchange_malloc` when this pass is enabled, so that
+//! the allocations can be recognized.
+
+use rustc_abi::FieldIdx;
+use rustc_hir::def_id::DefId;
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::*;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{Ty, TyCtxt};
+use rustc_mir_dataflow::Analysis;
+use rustc_mir_dataflow::impls::MaybeLiveLocals;
+use tracing::{debug, instrument};
+
+/// Larger allocations are kept on the heap, so that they cannot overflow the stack.
+const MAX_STACK_ALLOCATION_SIZE: u64 = 4096;
+
+pub(super) struct BoxToStack;
+
+impl<'tcx> crate::MirPass<'tcx> for BoxToStack {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 3
+    }
+
+    #[instrument(level = "debug", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+
+        // The locals of coroutines which are live across suspension points have already been
+        // moved into the coroutine, so we cannot add new ones.
+        if body.coroutine.is_some() {
+            return;
+        }
+        let lang_items = tcx.lang_items();
+        let mut allocations =
+            find_allocations(body, lang_items.box_new_fn(), lang_items.exchange_malloc_fn());
+        if allocations.allocations.is_empty() {
+            return;
+        }
+        allocations.find_escaping(body);
+        allocations.check_size(tcx, body);
+        allocations.check_liveness(tcx, body);
+        debug!(?allocations.allocations);
+        allocations.replace(tcx, body);
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+rustc_index::newtype_index! {
+    #[debug_format = "a{}"]
+    struct AllocationIndex {}
+}
+
+#[derive(Debug)]
+struct Allocation<'tcx> {
+    /// The type of the contents of the box.
+    ty: Ty<'tcx>,
+    /// The block ending in the `Box::new` or `exchange_malloc` call.
+    call: BasicBlock,
+    /// The argument of the `Box::new` call.
+    value: Option<Operand<'tcx>>,
+    /// The `ShallowInitBox` statement, moves of the box between locals and the transmutes of the
+    /// box to pointers to its contents, which are all removed.
+    statements: Vec<Location>,
+    escapes: bool,
+    /// The local replacing the contents of the box.
+    replacement: Option<Local>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Role {
+    None,
+    /// The result of the `exchange_malloc` call.
+    Pointer(AllocationIndex),
+    /// A local holding the box, or the result of the `Box::new` call.
+    Box(AllocationIndex),
+    /// A pointer to the contents of the box, transmuted from the box.
+    Contents(AllocationIndex),
+}
+
+struct Allocations<'tcx> {
+    allocations: IndexVec<AllocationIndex, Allocation<'tcx>>,
+    roles: IndexVec<Local, Role>,
+}
+
+fn find_allocations<'tcx>(
+    body: &Body<'tcx>,
+    box_new: Option<DefId>,
+    exchange_malloc: Option<DefId>,
+) -> Allocations<'tcx> {
+    let mut allocations = Allocations {
+        allocations: IndexVec::new(),
+        roles: IndexVec::from_elem(Role::None, &body.local_decls),
+    };
+
+    let mut calls = IndexVec::from_elem(None, &body.local_decls);
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        let TerminatorKind::Call { ref func, ref args, destination, target: Some(_), .. } =
+            data.terminator().kind
+        else {
+            continue;
+        };
+        let Some((def_id, generic_args)) = func.const_fn_def() else { continue };
+        let Some(local) = destination.as_local() else { continue };
+        if Some(def_id) == exchange_malloc {
+            calls[local] = Some(block);
+        } else if Some(def_id) == box_new
+            && let [value] = &args[..]
+        {
+            let index = allocations.allocations.push(Allocation {
+                ty: generic_args.type_at(0),
+                call: block,
+                value: Some(value.node.clone()),
+                statements: Vec::new(),
+                escapes: false,
+                replacement: None,
+            });
+            allocations.assign_role(local, Role::Box(index));
+        }
+    }
+
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            if let StatementKind::Assign(box (place, Rvalue::ShallowInitBox(ref operand, ty))) =
+                statement.kind
+                && let Some(local) = place.as_local()
+                && let Operand::Move(pointer) = operand
+                && let Some(pointer) = pointer.as_local()
+                && let Some(call) = calls[pointer]
+            {
+                let location = Location { block, statement_index };
+                let index = allocations.allocations.push(Allocation {
+                    ty,
+                    call,
+                    value: None,
+                    statements: vec![location],
+                    escapes: false,
+                    replacement: None,
+                });
+                allocations.assign_role(local, Role::Box(index));
+                allocations.assign_role(pointer, Role::Pointer(index));
+            }
+        }
+    }
+    if allocations.allocations.is_empty() {
+        return allocations;
+    }
+
+    // Follow the box through moves and transmutes, which may appear in any order in the body.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else {
+                    continue;
+                };
+                let Some(local) = place.as_local() else { continue };
+                let (source, role): (Place<'_>, fn(_) -> _) = match *rvalue {
+                    Rvalue::Use(Operand::Move(source)) if source.projection.is_empty() => {
+                        (source, Role::Box)
+                    }
+                    Rvalue::Cast(CastKind::Transmute, Operand::Copy(source), _)
+                        if is_box_pointer(source) =>
+                    {
+                        (source, Role::Contents)
+                    }
+                    _ => continue,
+                };
+                let Role::Box(index) = allocations.roles[source.local] else { continue };
+                let role = role(index);
+                debug!(?source, ?local, ?role);
+                let location = Location { block, statement_index };
+                let statements = &mut allocations.allocations[index].statements;
+                if !statements.contains(&location) {
+                    statements.push(location);
+                    allocations.assign_role(local, role);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    // The return place and the arguments are observable by the caller.
+    for local in body.args_iter().chain([RETURN_PLACE]) {
+        allocations.escape(allocations.roles[local]);
+    }
+
+    allocations
+}
+
+/// Whether `place` is the pointer inside a box, `((box.0: Unique<T>).0: NonNull<T>)`.
+fn is_box_pointer(place: Place<'_>) -> bool {
+    matches!(
+        place.projection[..],
+        [PlaceElem::Field(FieldIdx::ZERO, _), PlaceElem::Field(FieldIdx::ZERO, _)]
+    )
+}
+
+impl<'tcx> Allocations<'tcx> {
+    /// Assigns a role to `local`. A local cannot be part of two allocations, or have two roles.
+    fn assign_role(&mut self, local: Local, role: Role) {
+        match self.roles[local] {
+            Role::None => self.roles[local] = role,
+            current if current == role => {}
+            current => {
+                self.escape(current);
+                self.escape(role);
+            }
+        }
+    }
+
+    fn escape(&mut self, role: Role) {
+        if let Role::Pointer(index) | Role::Box(index) | Role::Contents(index) = role {
+            self.allocations[index].escapes = true;
+        }
+    }
+
+    fn find_escaping(&mut self, body: &Body<'tcx>) {
+        let mut visitor = EscapeVisitor { allocations: self, escaping: Vec::new() };
+        visitor.visit_body(body);
+        for index in std::mem::take(&mut visitor.escaping) {
+            self.allocations[index].escapes = true;
+        }
+    }
+
+    fn check_size(&mut self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) {
+        let typing_env = body.typing_env(tcx);
+        for allocation in self.allocations.iter_mut().filter(|allocation| !allocation.escapes) {
+            let size = tcx.layout_of(typing_env.as_query_input(allocation.ty)).map(|l| l.size);
+            if !size.is_ok_and(|size| size.bytes() <= MAX_STACK_ALLOCATION_SIZE) {
+                debug!(?allocation.ty, ?size, "too large");
+                allocation.escapes = true;
+            }
+        }
+    }
+
+    /// As all allocations of a `ShallowInitBox` statement share the same local, a box holding an
+    /// earlier allocation must not be live when it allocates again.
+    fn check_liveness(&mut self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) {
+        if self.allocations.iter().all(|allocation| allocation.escapes) {
+            return;
+        }
+        let mut live = MaybeLiveLocals
+            .iterate_to_fixpoint(tcx, body, Some("BoxToStack"))
+            .into_results_cursor(body);
+        for (index, allocation) in self.allocations.iter_mut_enumerated() {
+            if allocation.escapes {
+                continue;
+            }
+            live.seek_to_block_end(allocation.call);
+            if live.get().iter().any(|local| self.roles[local] == Role::Box(index)) {
+                debug!(?index, "live at allocation");
+                allocation.escapes = true;
+            }
+        }
+    }
+
+    fn role(&self, local: Local) -> Option<(AllocationIndex, Role)> {
+        // Locals added by this pass have no role.
+        let role = *self.roles.get(local)?;
+        let (Role::Pointer(index) | Role::Box(index) | Role::Contents(index)) = role else {
+            return None;
+        };
+        let allocation = &self.allocations[index];
+        if allocation.escapes { None } else { Some((index, role)) }
+    }
+
+    fn replace(mut self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let typing_env = body.typing_env(tcx);
+        let mut changed = false;
+        for allocation in self.allocations.iter_mut().filter(|allocation| !allocation.escapes) {
+            let data = &mut body.basic_blocks.as_mut()[allocation.call];
+            let source_info = data.terminator().source_info;
+            let TerminatorKind::Call { target: Some(target), .. } = data.terminator().kind else {
+                unreachable!()
+            };
+            data.terminator_mut().kind = TerminatorKind::Goto { target };
+            let replacement =
+                body.local_decls.push(LocalDecl::new(allocation.ty, source_info.span));
+            allocation.replacement = Some(replacement);
+            if let Some(value) = allocation.value.take() {
+                body.basic_blocks.as_mut()[allocation.call].statements.push(Statement::new(
+                    source_info,
+                    StatementKind::Assign(Box::new((replacement.into(), Rvalue::Use(value)))),
+                ));
+            }
+            for &location in &allocation.statements {
+                body.basic_blocks.as_mut()[location.block].statements[location.statement_index]
+                    .make_nop();
+            }
+            changed = true;
+        }
+        if !changed {
+            return;
+        }
+
+        for data in body.basic_blocks.as_mut_preserves_cfg() {
+            let terminator = data.terminator_mut();
+            if let TerminatorKind::Drop { place, target, .. } = terminator.kind
+                && let Some((index, Role::Box(_))) = self.role(place.local)
+            {
+                let allocation = &self.allocations[index];
+                if allocation.ty.needs_drop(tcx, typing_env) {
+                    let TerminatorKind::Drop { place, .. } = &mut terminator.kind else {
+                        unreachable!()
+                    };
+                    *place = allocation.replacement.unwrap().into();
+                } else {
+                    terminator.kind = TerminatorKind::Goto { target };
+                }
+            }
+        }
+
+        ReplacementVisitor { tcx, allocations: &self }.visit_body_preserves_cfg(body);
+    }
+
+    /// The place replacing `place`, a place in the contents of a box.
+    fn replace_place(&self, tcx: TyCtxt<'tcx>, place: Place<'tcx>) -> Option<Place<'tcx>> {
+        let (index, role) = self.role(place.local)?;
+        let projection = match (role, &place.projection[..]) {
+            (Role::Contents(_), [PlaceElem::Deref, projection @ ..]) => projection,
+            // Debuginfo of the box itself, which can only access the contents.
+            (Role::Box(_), []) => &[],
+            (
+                Role::Box(_),
+                [
+                    PlaceElem::Field(FieldIdx::ZERO, _),
+                    PlaceElem::Field(FieldIdx::ZERO, _),
+                    PlaceElem::Field(FieldIdx::ZERO, _),
+                    PlaceElem::Deref,
+                    projection @ ..,
+                ],
+            ) => projection,
+            _ => return None,
+        };
+        let local = self.allocations[index].replacement?;
+        Some(Place { local, projection: tcx.mk_place_elems(projection) })
+    }
+}
+
+struct EscapeVisitor<'a, 'tcx> {
+    allocations: &'a Allocations<'tcx>,
+    escaping: Vec<AllocationIndex>,
+}
+
+impl<'tcx> Visitor<'tcx> for EscapeVisitor<'_, 'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        let (Role::Pointer(index) | Role::Box(index) | Role::Contents(index)) =
+            self.allocations.roles[place.local]
+        else {
+            return;
+        };
+        let allocation = &self.allocations.allocations[index];
+        if context.is_storage_marker() {
+            return;
+        }
+        let allowed = match self.allocations.roles[place.local] {
+            Role::Pointer(_) => {
+                place.projection.is_empty()
+                    && match context {
+                        // Only the terminator of a block can be a call.
+                        PlaceContext::MutatingUse(MutatingUseContext::Call) => {
+                            location.block == allocation.call
+                        }
+                        PlaceContext::NonMutatingUse(NonMutatingUseContext::Move) => {
+                            location == allocation.statements[0]
+                        }
+                        _ => false,
+                    }
+            }
+            Role::Box(_) => match context {
+                PlaceContext::MutatingUse(MutatingUseContext::Call) => {
+                    place.projection.is_empty()
+                        && allocation.value.is_some()
+                        && location.block == allocation.call
+                }
+                PlaceContext::MutatingUse(MutatingUseContext::Drop) => place.projection.is_empty(),
+                PlaceContext::MutatingUse(MutatingUseContext::Store)
+                | PlaceContext::NonMutatingUse(
+                    NonMutatingUseContext::Move | NonMutatingUseContext::Copy,
+                ) => allocation.statements.contains(&location),
+                _ => false,
+            },
+            Role::Contents(_) => {
+                place.is_indirect_first_projection()
+                    || (context == PlaceContext::MutatingUse(MutatingUseContext::Store)
+                        && allocation.statements.contains(&location))
+            }
+            Role::None => unreachable!(),
+        };
+        if !allowed {
+            debug!(?place, ?context, ?location, "escapes");
+            self.escaping.push(index);
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        let VarDebugInfoContents::Place(place) = var_debug_info.value else { return };
+        let (Role::Pointer(index) | Role::Box(index) | Role::Contents(index)) =
+            self.allocations.roles[place.local]
+        else {
+            return;
+        };
+        let representable = var_debug_info.composite.is_none()
+            && match (self.allocations.roles[place.local], &place.projection[..]) {
+                (Role::Contents(_), [PlaceElem::Deref, ..]) => true,
+                (Role::Box(_), []) => true,
+                (
+                    Role::Box(_),
+                    [
+                        PlaceElem::Field(FieldIdx::ZERO, _),
+                        PlaceElem::Field(FieldIdx::ZERO, _),
+                        PlaceElem::Field(FieldIdx::ZERO, _),
+                        PlaceElem::Deref,
+                        ..,
+                    ],
+                ) => true,
+                _ => false,
+            };
+        if !representable {
+            debug!(?var_debug_info, "cannot be represented");
+            self.escaping.push(index);
+        }
+    }
+}
+
+struct ReplacementVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    allocations: &'a Allocations<'tcx>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ReplacementVisitor<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        // The storage markers of the box are kept, as they are not those of its contents.
+        if !context.is_storage_marker()
+            && let Some(replacement) = self.allocations.replace_place(self.tcx, *place)
+        {
+            *place = replacement;
+        }
+        self.super_place(place, context, location)
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &mut VarDebugInfo<'tcx>) {
+        if let VarDebugInfoContents::Place(place) = &mut var_debug_info.value
+            && let Some(replacement) = self.allocations.replace_place(self.tcx, *place)
+        {
+            *place = replacement;
+        }
+    }
+}
@@ -5,6 +5,7 @@
 use std::ops::{Range, RangeFrom};
 
 use rustc_abi::{ExternAbi, FieldIdx};
+use rustc_hir::LangItem;
 use rustc_hir::attrs::{InlineAttr, OptimizeAttr};
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
@@ -19,11 +20,12 @@
 use rustc_span::source_map::Spanned;
 use tracing::{debug, instrument, trace, trace_span};
 
+use crate::box_to_stack::BoxToStack;
 use crate::cost_checker::{CostChecker, is_call_like};
 use crate::deref_separator::deref_finder;
 use crate::simplify::simplify_cfg;
 use crate::validate::validate_types;
-use crate::{check_inline, util};
+use crate::{check_inline, pass_manager as pm, util};
 
 pub(crate) mod cycle;
 
@@ -287,6 +289,11 @@ struct NormalInliner<'tcx> {
     /// Indicates that the caller is #[inline] and just calls another function,
     /// and thus we can inline less into it as it'll be inlined itself.
     caller_is_inline_forwarder: bool,
+    /// Indicates that `BoxToStack` runs after inlining, which needs to see the calls to `Box::new`
+    /// and `exchange_malloc` to find the allocations of boxes. This also keeps the calls of boxes
+    /// which escape, but inlining them would not allow any other MIR optimization: the allocation
+    /// itself is an opaque call to the allocator, and LLVM still inlines both functions.
+    keep_box_allocations: bool,
 }
 
 impl<'tcx> NormalInliner<'tcx> {
@@ -311,6 +318,7 @@ fn new(tcx: TyCtxt<'tcx>, def_id: DefId, body: &Body<'tcx>) -> Self {
                 codegen_fn_attrs.inline,
                 InlineAttr::Hint | InlineAttr::Always | InlineAttr::Force { .. }
             ) && body_is_forwarder(body),
+            keep_box_allocations: pm::should_run_pass(tcx, &BoxToStack, pm::Optimizations::Allowed),
         }
     }
 
@@ -334,8 +342,12 @@ fn changed(self) -> bool {
         self.changed
     }
 
-    fn should_inline_for_callee(&self, _: DefId) -> bool {
-        true
+    fn should_inline_for_callee(&self, def_id: DefId) -> bool {
+        !(self.keep_box_allocations
+            && matches!(
+                self.tcx.as_lang_item(def_id),
+                Some(LangItem::BoxNew | LangItem::ExchangeMalloc)
+            ))
     }
 
     fn check_codegen_attributes_extra(
@@ -762,6 +774,9 @@ fn check_mir_is_available<'tcx, I: Inliner<'tcx>>(
         | InstanceKind::CloneShim(..)
         | InstanceKind::ThreadLocalShim(..)
         | InstanceKind::FnPtrAddrShim(..) => return Ok(()),
+
+        // Cold paths are outlined so that they are not inlined again.
+        InstanceKind::OutlinedColdPath(..) => return Err("outlined cold path"),
     }
 
     if inliner.tcx().is_constructor(callee_def_id) {
@@ -114,6 +114,7 @@ macro_rules! declare_passes {
     mod add_moves_for_packed_drops : AddMovesForPackedDrops;
     mod add_retag : AddRetag;
     mod add_subtyping_projections : Subtyper;
+    mod box_to_stack : BoxToStack;
     mod check_inline : CheckForceInline;
     mod check_call_recursion : CheckCallRecursion, CheckDropRecursion;
     mod check_alignment : CheckAlignment;
@@ -155,6 +156,7 @@ mod dead_store_elimination : DeadStoreElimination {
     mod mentioned_items : MentionedItems;
     mod multiple_return_terminators : MultipleReturnTerminators;
     mod nrvo : RenameReturnPlace;
+    mod outline_cold_paths : OutlineColdPaths;
     mod post_drop_elaboration : CheckLiveDrops;
     mod prettify : ReorderBasicBlocks, ReorderLocals;
     mod promote_consts : PromoteTemps;
@@ -205,6 +207,8 @@ pub fn provide(providers: &mut Providers) {
     ffi_unwind_calls::provide(providers);
     shim::provide(providers);
     cross_crate_inline::provide(providers);
+    providers.hooks.validate_mir_types =
+        |tcx, body| validate::validate_types(tcx, body.typing_env(tcx), body, body);
     providers.queries = query::Providers {
         mir_keys,
         mir_built,
@@ -683,6 +687,9 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             // Code from other crates may have storage markers, so this needs to happen after
             // inlining.
             &remove_storage_markers::RemoveStorageMarkers,
+            // Inlining may reveal boxes which never escape the function, whose allocations can be
+            // replaced with locals before they are split up by SROA.
+            &box_to_stack::BoxToStack,
             // Inlining and instantiation may introduce ZST and useless drops.
             &remove_zsts::RemoveZsts,
             &remove_unneeded_drops::RemoveUnneededDrops,
@@ -713,6 +720,8 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &dest_prop::DestinationPropagation,
             &o1(simplify_branches::SimplifyConstCondition::Final),
             &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
+            // After constant conditions are simplified, which can make more paths cold.
+            &outline_cold_paths::OutlineColdPaths,
             &o1(simplify::SimplifyCfg::Final),
             // After the last SimplifyCfg, because this wants one-block functions.
             &strip_debuginfo::StripDebugInfo,
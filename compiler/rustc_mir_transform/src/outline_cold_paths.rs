@@ -0,0 +1,523 @@
+//! Moves cold paths, which can only end in a diverging call, out of functions.
+//!
+//! Panicking paths, e.g. the formatting of a panic message, often make up a large part of a
+//! function although they are executed at most once. Keeping them in the function makes it larger
+//! and less likely to be inlined, and spreads its hot code over more cache lines.
+//!
+//! A block is cold if it ends in a diverging call or is unreachable, or if all of its successors
+//! are cold. The cold blocks reachable from a cold block which has a predecessor that is not cold
+//! form a cold path. A cold block which is reachable from several such paths starts a path of its
+//! own, which the other paths call, so that no block is copied into several functions. Each path
+//! is moved into a separate `#[cold]` function, an `InstanceKind::OutlinedColdPath` instance of the
+//! function, which takes the locals which are live at the start of the path as arguments:
+//!
+//! ```ignore (MIR)
+//! bb1: {
+//!     _5 = &_1;
+//!     _4 = core::fmt::rt::Argument::<'_>::new_display::<u32>(copy _5) -> [return: bb2, ...];
+//! }
+//! bb2: {
+//!     ...
+//!     _3 = core::panicking::panic_fmt(move _6) -> unwind continue;
+//! }
+//! ```
+//!
+//! becomes
+//!
+//! ```ignore (MIR)
+//! bb1: {
+//!     _9 = const {foo as fn(u32) -> !}(move _1) -> unwind continue;
+//! }
+//! ```
+//!
+//! The outlined function is called through a constant function pointer, as it has no type of
+//! its own. The pointer is printed like one to the function itself. The body of the outlined
+//! function is stored in `Body::outlined_cold_paths` of the function it comes from.
+
+use rustc_abi::ExternAbi;
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_index::IndexVec;
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::mir::interpret::{CTFE_ALLOC_SALT, Pointer, Scalar};
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, GenericArgs, Instance, InstanceKind, Ty, TyCtxt};
+use rustc_mir_dataflow::Analysis;
+use rustc_mir_dataflow::impls::MaybeLiveLocals;
+use rustc_span::source_map::Spanned;
+use tracing::{debug, instrument};
+
+use crate::cost_checker::CostChecker;
+use crate::required_consts::RequiredConstsVisitor;
+use crate::{add_call_guards, mentioned_items, pass_manager as pm, simplify};
+
+/// Cheaper paths are kept, as they would not get much smaller than the call replacing them.
+const MIN_OUTLINED_COST: usize = 40;
+
+pub(super) struct OutlineColdPaths;
+
+impl<'tcx> crate::MirPass<'tcx> for OutlineColdPaths {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.opts.unstable_opts.mir_outline_cold_paths
+    }
+
+    #[instrument(level = "debug", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let def_id = body.source.def_id();
+        debug!(?def_id);
+
+        if body.source.promoted.is_some()
+            || !matches!(body.source.instance, InstanceKind::Item(_))
+            || body.coroutine.is_some()
+            // Coverage counters belong to the function they are declared in.
+            || body.function_coverage_info.is_some()
+            // CFI checks calls through function pointers against the type of the callee, which is
+            // the type of the function the paths are outlined from.
+            || tcx.sess.is_sanitizer_cfi_enabled()
+            || tcx.sess.is_sanitizer_kcfi_enabled()
+        {
+            return;
+        }
+        match tcx.def_kind(def_id) {
+            DefKind::Fn | DefKind::AssocFn => {
+                if tcx.fn_sig(def_id).skip_binder().c_variadic() {
+                    return;
+                }
+            }
+            DefKind::Closure => {}
+            _ => return,
+        }
+        // The outlined paths are called through constant function pointers, which cannot depend
+        // on generic parameters. The caller location of `#[track_caller]` functions is an
+        // implicit argument which cannot be passed on.
+        if tcx.generics_of(def_id).requires_monomorphization(tcx)
+            || body.source.instance.requires_caller_location(tcx)
+        {
+            return;
+        }
+
+        let cold = cold_blocks(body);
+        if cold.is_empty() {
+            return;
+        }
+
+        let paths = find_cold_paths(tcx, body, &cold);
+        if paths.is_empty() {
+            return;
+        }
+
+        let mut outlined = Vec::with_capacity(paths.len());
+        for (index, path) in paths.iter().enumerate() {
+            debug!(?path);
+            outlined.push(outline(tcx, body, &paths, index));
+        }
+
+        for (index, path) in paths.iter().enumerate() {
+            let destination = body.local_decls.push(LocalDecl::new(tcx.types.never, body.span));
+            let call = call_outlined(
+                tcx,
+                body,
+                &paths,
+                index,
+                path.arguments.iter().copied(),
+                destination,
+            );
+            body.basic_blocks_mut()[path.entry] = BasicBlockData::new(Some(call), false);
+        }
+
+        simplify::remove_dead_blocks(body);
+        body.outlined_cold_paths = outlined;
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+struct ColdPath {
+    /// The first block of the path, which is the first one of the outlined body.
+    entry: BasicBlock,
+    blocks: Vec<BasicBlock>,
+    /// The entries of the paths which this path continues into, and whose outlined bodies are
+    /// called from the outlined body of this path.
+    callees: Vec<BasicBlock>,
+    /// The locals which are live at the start of the path, in the order of the arguments of the
+    /// outlined body.
+    arguments: Vec<Local>,
+    /// The other locals used in the path.
+    locals: Vec<Local>,
+    /// The source info of the first diverging call of the path.
+    source_info: SourceInfo,
+    /// How the path unwinds, which is either `Continue` or `Unreachable`.
+    unwind: UnwindAction,
+}
+
+/// Returns the blocks from which every path ends in a diverging call or an `unreachable`
+/// terminator without unwinding into a cleanup block.
+fn cold_blocks(body: &Body<'_>) -> DenseBitSet<BasicBlock> {
+    let mut cold = DenseBitSet::new_empty(body.basic_blocks.len());
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // In postorder, most successors are decided before their predecessors.
+        for (bb, data) in traversal::postorder(body) {
+            if cold.contains(bb) || data.is_cleanup {
+                continue;
+            }
+            let terminator = data.terminator();
+            if let Some(UnwindAction::Cleanup(_)) = terminator.unwind() {
+                continue;
+            }
+            let is_cold = match terminator.kind {
+                TerminatorKind::Call { target: None, .. } | TerminatorKind::Unreachable => true,
+                TerminatorKind::Goto { .. }
+                | TerminatorKind::SwitchInt { .. }
+                | TerminatorKind::Call { .. }
+                | TerminatorKind::Assert { .. }
+                | TerminatorKind::Drop { .. } => {
+                    terminator.successors().all(|succ| cold.contains(succ))
+                }
+                _ => false,
+            };
+            if is_cold {
+                cold.insert(bb);
+                changed = true;
+            }
+        }
+    }
+    cold
+}
+
+fn find_cold_paths<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    cold: &DenseBitSet<BasicBlock>,
+) -> Vec<ColdPath> {
+    let predecessors = body.basic_blocks.predecessors();
+    let mut entries = DenseBitSet::new_empty(body.basic_blocks.len());
+    for bb in cold.iter() {
+        if bb != START_BLOCK && predecessors[bb].iter().any(|&pred| !cold.contains(pred)) {
+            entries.insert(bb);
+        }
+    }
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    // A block which is reachable from several entries without passing another entry becomes an
+    // entry itself, so that it is only outlined once. As the successors of cold blocks are cold,
+    // and a block is only cold once all of its successors are, the cold blocks have no cycles.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut owners = IndexVec::from_elem(None, &body.basic_blocks);
+        let mut shared = Vec::new();
+        for entry in entries.iter() {
+            let mut stack = vec![entry];
+            while let Some(bb) = stack.pop() {
+                for succ in body.basic_blocks[bb].terminator().successors() {
+                    if entries.contains(succ) {
+                        continue;
+                    }
+                    match owners[succ] {
+                        None => {
+                            owners[succ] = Some(entry);
+                            stack.push(succ);
+                        }
+                        Some(owner) if owner == entry => {}
+                        Some(_) => shared.push(succ),
+                    }
+                }
+            }
+        }
+        for bb in shared {
+            changed |= entries.insert(bb);
+        }
+    }
+
+    let typing_env = body.typing_env(tcx);
+    let mut borrows = BorrowCollector { borrows: Vec::new() };
+    borrows.visit_body(body);
+    let mut liveness = MaybeLiveLocals
+        .iterate_to_fixpoint(tcx, body, Some("OutlineColdPaths"))
+        .into_results_cursor(body);
+
+    let mut paths = Vec::new();
+    'entries: for entry in entries.iter() {
+        let mut in_path = DenseBitSet::new_empty(body.basic_blocks.len());
+        let mut blocks = vec![entry];
+        let mut callees = Vec::new();
+        in_path.insert(entry);
+        let mut i = 0;
+        while i < blocks.len() {
+            for succ in body.basic_blocks[blocks[i]].terminator().successors() {
+                if entries.contains(succ) {
+                    if !callees.contains(&succ) {
+                        callees.push(succ);
+                    }
+                } else if in_path.insert(succ) {
+                    blocks.push(succ);
+                }
+            }
+            i += 1;
+        }
+        // The blocks of this path and of all paths it calls, directly or not.
+        let mut reachable = in_path.clone();
+        let mut stack = callees.clone();
+        while let Some(bb) = stack.pop() {
+            if reachable.insert(bb) {
+                stack.extend(body.basic_blocks[bb].terminator().successors());
+            }
+        }
+
+        let Some(source_info) = blocks.iter().copied().chain(reachable.iter()).find_map(|bb| {
+            let terminator = body.basic_blocks[bb].terminator();
+            matches!(terminator.kind, TerminatorKind::Call { target: None, .. })
+                .then_some(terminator.source_info)
+        }) else {
+            continue;
+        };
+
+        let mut checker = CostChecker::new(tcx, typing_env, None, body);
+        let mut used = LocalCollector { locals: DenseBitSet::new_empty(body.local_decls.len()) };
+        for &bb in &blocks {
+            checker.visit_basic_block_data(bb, &body.basic_blocks[bb]);
+            used.visit_basic_block_data(bb, &body.basic_blocks[bb]);
+        }
+        if checker.cost() < MIN_OUTLINED_COST {
+            debug!(?entry, cost = checker.cost(), "too cheap to outline");
+            continue;
+        }
+        // The outlined body returns `!`.
+        if used.locals.contains(RETURN_PLACE) {
+            continue;
+        }
+        // The arguments of the paths this path calls are passed on.
+        for &callee in &callees {
+            liveness.seek_to_block_start(callee);
+            used.locals.union(liveness.get());
+        }
+
+        liveness.seek_to_block_start(entry);
+        let mut arguments = Vec::new();
+        let mut locals = Vec::new();
+        for local in used.locals.iter() {
+            if !body.local_decls[local].ty.is_sized(tcx, typing_env) {
+                continue 'entries;
+            }
+            if liveness.get().contains(local) {
+                // A live local is moved into the outlined body, so there must be no pointers to
+                // it.
+                if borrows
+                    .borrows
+                    .iter()
+                    .any(|&(bb, borrowed)| borrowed == local && !reachable.contains(bb))
+                {
+                    continue 'entries;
+                }
+                arguments.push(local);
+            } else {
+                locals.push(local);
+            }
+        }
+
+        let unwind = if reachable.iter().any(|bb| {
+            matches!(body.basic_blocks[bb].terminator().unwind(), Some(UnwindAction::Continue))
+        }) {
+            UnwindAction::Continue
+        } else {
+            UnwindAction::Unreachable
+        };
+
+        paths.push(ColdPath { entry, blocks, callees, arguments, locals, source_info, unwind });
+    }
+
+    // A path can only be outlined if the paths it calls are.
+    let mut changed = true;
+    while changed {
+        let len = paths.len();
+        let outlined: Vec<BasicBlock> = paths.iter().map(|path| path.entry).collect();
+        paths.retain(|path| path.callees.iter().all(|callee| outlined.contains(callee)));
+        changed = paths.len() != len;
+    }
+    paths
+}
+
+/// Builds the call of the outlined body of `paths[index]`, which passes `arguments`, the arguments
+/// of the path or the locals replacing them.
+fn call_outlined<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    paths: &[ColdPath],
+    index: usize,
+    arguments: impl Iterator<Item = Local>,
+    destination: Local,
+) -> Terminator<'tcx> {
+    let path = &paths[index];
+    let def_id = body.source.def_id();
+    let arg_tys =
+        tcx.mk_type_list_from_iter(path.arguments.iter().map(|&local| body.local_decls[local].ty));
+    let fn_ptr_ty = Ty::new_fn_ptr(
+        tcx,
+        ty::Binder::dummy(tcx.mk_fn_sig(
+            arg_tys,
+            tcx.types.never,
+            false,
+            hir::Safety::Safe,
+            ExternAbi::Rust,
+        )),
+    );
+    let args = tcx.erase_regions(GenericArgs::identity_for_item(tcx, def_id));
+    let instance = Instance { def: InstanceKind::OutlinedColdPath(def_id, index), args };
+    let alloc_id = tcx.reserve_and_set_fn_alloc(instance, CTFE_ALLOC_SALT);
+    let func = Operand::Constant(Box::new(ConstOperand {
+        span: path.source_info.span,
+        user_ty: None,
+        const_: Const::Val(
+            ConstValue::Scalar(Scalar::from_pointer(Pointer::from(alloc_id), &tcx)),
+            fn_ptr_ty,
+        ),
+    }));
+    let args = arguments
+        .map(|local| Spanned { node: Operand::Move(local.into()), span: path.source_info.span })
+        .collect();
+    Terminator {
+        source_info: path.source_info,
+        kind: TerminatorKind::Call {
+            func,
+            args,
+            destination: destination.into(),
+            target: None,
+            unwind: path.unwind,
+            call_source: CallSource::Misc,
+            fn_span: path.source_info.span,
+        },
+    }
+}
+
+/// Builds the body of the `index`th outlined cold path of `body`.
+fn outline<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    paths: &[ColdPath],
+    index: usize,
+) -> Body<'tcx> {
+    let path = &paths[index];
+    let mut local_decls = IndexVec::with_capacity(1 + path.arguments.len() + path.locals.len());
+    local_decls.push(LocalDecl::new(tcx.types.never, body.span));
+    let mut locals = IndexVec::from_elem(None, &body.local_decls);
+    for &local in path.arguments.iter().chain(&path.locals) {
+        locals[local] = Some(local_decls.push(body.local_decls[local].clone()));
+    }
+    let mut blocks = IndexVec::from_elem(None, &body.basic_blocks);
+    for (new, &bb) in path.blocks.iter().chain(&path.callees).enumerate() {
+        blocks[bb] = Some(BasicBlock::from_usize(new));
+    }
+
+    let mut renamer = Renamer { tcx, locals: &locals, blocks: &blocks };
+    let mut basic_blocks: IndexVec<BasicBlock, _> =
+        path.blocks.iter().map(|&bb| body.basic_blocks[bb].clone()).collect();
+    for (bb, data) in basic_blocks.iter_mut_enumerated() {
+        renamer.visit_basic_block_data(bb, data);
+    }
+    // The paths this path continues into are called instead.
+    for &callee in &path.callees {
+        let callee = paths.iter().position(|path| path.entry == callee).unwrap();
+        let destination = local_decls.push(LocalDecl::new(tcx.types.never, body.span));
+        let arguments = paths[callee].arguments.iter().map(|&local| locals[local].unwrap());
+        let call = call_outlined(tcx, body, paths, callee, arguments, destination);
+        basic_blocks.push(BasicBlockData::new(Some(call), false));
+    }
+    // The locals which are not arguments may have been storage-live in the original body.
+    let source_info = SourceInfo::outermost(body.span);
+    basic_blocks[START_BLOCK].statements.splice(
+        0..0,
+        path.locals.iter().map(|&local| {
+            Statement::new(source_info, StatementKind::StorageLive(locals[local].unwrap()))
+        }),
+    );
+
+    let var_debug_info = body
+        .var_debug_info
+        .iter()
+        .filter(|info| match info.value {
+            VarDebugInfoContents::Place(place) => locals[place.local].is_some(),
+            VarDebugInfoContents::Const(_) => false,
+        })
+        .map(|info| {
+            let mut info = info.clone();
+            info.argument_index = None;
+            renamer.visit_var_debug_info(&mut info);
+            info
+        })
+        .collect();
+
+    let mut outlined = Body::new(
+        MirSource::from_instance(InstanceKind::OutlinedColdPath(body.source.def_id(), index)),
+        basic_blocks,
+        body.source_scopes.clone(),
+        local_decls,
+        IndexVec::new(),
+        path.arguments.len(),
+        var_debug_info,
+        body.span,
+        None,
+        None,
+    );
+    outlined.phase = body.phase;
+    RequiredConstsVisitor::compute_required_consts(&mut outlined);
+    pm::run_passes(
+        tcx,
+        &mut outlined,
+        &[&mentioned_items::MentionedItems, &add_call_guards::CriticalCallEdges],
+        Some(MirPhase::Runtime(RuntimePhase::Optimized)),
+        pm::Optimizations::Allowed,
+    );
+    outlined
+}
+
+struct LocalCollector {
+    locals: DenseBitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalCollector {
+    fn visit_local(&mut self, local: Local, _context: PlaceContext, _location: Location) {
+        self.locals.insert(local);
+    }
+}
+
+/// Collects the locals which are borrowed directly, together with the block borrowing them.
+struct BorrowCollector {
+    borrows: Vec<(BasicBlock, Local)>,
+}
+
+impl<'tcx> Visitor<'tcx> for BorrowCollector {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if !place.is_indirect() && (context.is_borrow() || context.is_address_of()) {
+            self.borrows.push((location.block, place.local));
+        }
+    }
+}
+
+struct Renamer<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    locals: &'a IndexVec<Local, Option<Local>>,
+    blocks: &'a IndexVec<BasicBlock, Option<BasicBlock>>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for Renamer<'a, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _context: PlaceContext, _location: Location) {
+        *local = self.locals[*local].unwrap();
+    }
+
+    fn visit_terminator(&mut self, terminator: &mut Terminator<'tcx>, location: Location) {
+        terminator.successors_mut(|bb| *bb = self.blocks[*bb].unwrap());
+        self.super_terminator(terminator, location);
+    }
+}
@@ -219,6 +219,9 @@ fn make_shim<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::InstanceKind<'tcx>) -> Body<
         ty::InstanceKind::Intrinsic(_) => {
             bug!("creating shims from intrinsics ({:?}) is unsupported", instance)
         }
+        ty::InstanceKind::OutlinedColdPath(..) => {
+            bug!("{:?} is built by the `OutlineColdPaths` pass", instance)
+        }
     };
     debug!("make_shim({:?}) = untransformed {:?}", instance, result);
 
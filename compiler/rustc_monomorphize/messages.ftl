@@ -39,6 +39,12 @@ monomorphize_abi_required_target_feature =
 monomorphize_couldnt_dump_mono_stats =
     unexpected error occurred while dumping monomorphization stats: {$error}
 
+monomorphize_couldnt_read_cgu_profile =
+    couldn't read the codegen unit profile `{$path}`: {$error}
+
+monomorphize_couldnt_write_cgu_profile_report =
+    couldn't write the codegen unit profile report `{$path}`: {$error}
+
 monomorphize_encountered_error_while_instantiating =
     the above error was encountered while instantiating `{$kind} {$instance}`
 
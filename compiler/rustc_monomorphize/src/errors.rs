@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use rustc_macros::{Diagnostic, LintDiagnostic};
 use rustc_middle::ty::{Instance, Ty};
 use rustc_span::{Span, Symbol};
@@ -46,6 +48,20 @@ pub(crate) struct CouldntDumpMonoStats {
     pub error: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(monomorphize_couldnt_read_cgu_profile)]
+pub(crate) struct CouldntReadCguProfile<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(monomorphize_couldnt_write_cgu_profile_report)]
+pub(crate) struct CouldntWriteCguProfileReport<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_encountered_error_while_instantiating)]
 pub(crate) struct EncounteredErrorWhileInstantiating<'tcx> {
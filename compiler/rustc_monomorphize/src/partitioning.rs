@@ -93,6 +93,7 @@
 //! inlining, even when they are not marked `#[inline]`.
 
 mod autodiff;
+mod profile;
 
 use std::cmp;
 use std::collections::hash_map::Entry;
@@ -163,6 +164,18 @@ fn partition<'tcx, I>(
         placed
     };
 
+    // Move hot items next to their callers or callees, if we were given a
+    // profile. This happens before merging, which only moves whole CGUs.
+    let profile = profile::load_profile(tcx);
+    let profile_moves = if let Some(profile) = &profile {
+        let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_apply_profile");
+        let moves = profile::co_locate_hot_items(cx, profile, &mut codegen_units);
+        debug_dump(tcx, "PROFILE", &codegen_units);
+        moves
+    } else {
+        Vec::new()
+    };
+
     // Merge until we don't exceed the max CGU count.
     // `merge_codegen_units` is responsible for updating the CGU size
     // estimates.
@@ -195,6 +208,10 @@ fn partition<'tcx, I>(
         bug!("unsorted CGUs:\n{names}");
     }
 
+    if let Some(path) = &tcx.sess.opts.unstable_opts.cgu_profile_report {
+        profile::write_report(tcx, profile.as_ref(), &profile_moves, &codegen_units, path);
+    }
+
     codegen_units
 }
 
@@ -305,21 +322,23 @@ fn place_mono_items<'tcx, I>(cx: &PartitioningCx<'_, 'tcx>, mono_items: I) -> Pl
         cgu.compute_size_estimate();
     }
 
-    return PlacedMonoItems { codegen_units, internalization_candidates };
-
-    fn get_reachable_inlined_items<'tcx>(
-        tcx: TyCtxt<'tcx>,
-        item: MonoItem<'tcx>,
-        usage_map: &UsageMap<'tcx>,
-        visited: &mut FxIndexSet<MonoItem<'tcx>>,
-    ) {
-        usage_map.for_each_inlined_used_item(tcx, item, |inlined_item| {
-            let is_new = visited.insert(inlined_item);
-            if is_new {
-                get_reachable_inlined_items(tcx, inlined_item, usage_map, visited);
-            }
-        });
-    }
+    PlacedMonoItems { codegen_units, internalization_candidates }
+}
+
+/// Collects all inlined items that are reachable from `item` without going via
+/// another root item.
+fn get_reachable_inlined_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    item: MonoItem<'tcx>,
+    usage_map: &UsageMap<'tcx>,
+    visited: &mut FxIndexSet<MonoItem<'tcx>>,
+) {
+    usage_map.for_each_inlined_used_item(tcx, item, |inlined_item| {
+        let is_new = visited.insert(inlined_item);
+        if is_new {
+            get_reachable_inlined_items(tcx, inlined_item, usage_map, visited);
+        }
+    });
 }
 
 // This function requires the CGUs to be sorted by name on input, and ensures
@@ -629,7 +648,8 @@ fn characteristic_def_id_of_mono_item<'tcx>(
     match mono_item {
         MonoItem::Fn(instance) => {
             let def_id = match instance.def {
-                ty::InstanceKind::Item(def) => def,
+                // Outlined cold paths are placed next to the function they come from.
+                ty::InstanceKind::Item(def) | ty::InstanceKind::OutlinedColdPath(def, _) => def,
                 ty::InstanceKind::VTableShim(..)
                 | ty::InstanceKind::ReifyShim(..)
                 | ty::InstanceKind::FnPtrShim(..)
@@ -814,7 +834,8 @@ fn mono_item_visibility<'tcx>(
         | InstanceKind::ConstructCoroutineInClosureShim { .. }
         | InstanceKind::DropGlue(..)
         | InstanceKind::CloneShim(..)
-        | InstanceKind::FnPtrAddrShim(..) => return Visibility::Hidden,
+        | InstanceKind::FnPtrAddrShim(..)
+        | InstanceKind::OutlinedColdPath(..) => return Visibility::Hidden,
     };
 
     // Both the `start_fn` lang item and `main` itself should not be exported,
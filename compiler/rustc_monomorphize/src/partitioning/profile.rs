@@ -0,0 +1,296 @@
+//! Profile-guided placement of mono items, enabled with `-Zcgu-profile=<file>`.
+//!
+//! The profile is a call-frequency profile in the "folded stacks" format that
+//! is produced by most sampling profilers and consumed by flamegraph tools:
+//! each line contains a call stack, from the outermost frame to the innermost
+//! one, separated by `;`, followed by a space and the number of samples of that
+//! stack. Every pair of adjacent frames is a caller/callee edge, weighted by
+//! the number of samples. Frames can be named with their symbol name or with
+//! their demangled path without its hash (e.g. `my_crate::parse::token`), and
+//! frames that don't name a root mono item of the current crate are ignored.
+//!
+//! The edges are visited from the hottest to the coldest one, and if the
+//! caller and the callee were placed in different codegen units, one of them
+//! is moved into the codegen unit of the other. An item is only moved if it
+//! wasn't co-located with a hotter caller or callee before, so hot call chains
+//! end up in the same codegen unit. Merging codegen units afterwards only ever
+//! moves whole codegen units, so it keeps these items together.
+//!
+//! With `-Zcgu-profile-report=<file>`, a report of the resulting partitioning
+//! is written to the given file.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
+use rustc_middle::mir::mono::{CodegenUnit, Linkage, MonoItem, MonoItemData, Visibility};
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_span::Symbol;
+use tracing::debug;
+
+use super::{PartitioningCx, get_reachable_inlined_items};
+use crate::errors::{CouldntReadCguProfile, CouldntWriteCguProfileReport};
+
+/// The caller/callee edges of a profile, with their total weight, in the order
+/// in which they first appear in the profile.
+pub(super) struct CguProfile {
+    edges: FxIndexMap<(String, String), u64>,
+}
+
+/// An item that was moved to another codegen unit because of the profile.
+pub(super) struct ProfileMove<'tcx> {
+    item: MonoItem<'tcx>,
+    from: Symbol,
+    to: Symbol,
+    weight: u64,
+}
+
+/// Loads the profile given with `-Zcgu-profile`, if any.
+pub(super) fn load_profile(tcx: TyCtxt<'_>) -> Option<CguProfile> {
+    let path = &tcx.sess.opts.unstable_opts.cgu_profile.as_ref()?.path;
+    match std::fs::read_to_string(path).map_err(|err| err.to_string()).and_then(parse_profile) {
+        Ok(profile) => Some(profile),
+        Err(error) => tcx.dcx().emit_fatal(CouldntReadCguProfile { path, error }),
+    }
+}
+
+fn parse_profile(contents: String) -> Result<CguProfile, String> {
+    let mut edges = FxIndexMap::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let malformed = || format!("malformed stack on line {}: `{line}`", index + 1);
+        let (stack, count) = line.rsplit_once(' ').ok_or_else(malformed)?;
+        let count: u64 = count.parse().map_err(|_| malformed())?;
+        let frames = split_frames(stack);
+        for pair in frames.windows(2) {
+            let (caller, callee) = (pair[0].trim(), pair[1].trim());
+            if caller != callee {
+                *edges.entry((caller.to_owned(), callee.to_owned())).or_default() += count;
+            }
+        }
+    }
+    Ok(CguProfile { edges })
+}
+
+/// Splits a folded stack into its frames. A `;` followed by a space is part of
+/// a frame, as in array types like `[u8; 4]`.
+fn split_frames(stack: &str) -> Vec<&str> {
+    let mut frames = Vec::new();
+    let mut start = 0;
+    for (i, _) in stack.match_indices(';') {
+        if !stack[i + 1..].starts_with(' ') {
+            frames.push(&stack[start..i]);
+            start = i + 1;
+        }
+    }
+    frames.push(&stack[start..]);
+    frames
+}
+
+/// The root items of `codegen_units`, by the names they can have in a profile.
+/// Names that are shared by several items are ambiguous, and map to `None`.
+fn root_items_by_name<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[CodegenUnit<'tcx>],
+) -> FxHashMap<String, Option<MonoItem<'tcx>>> {
+    let mut items = FxHashMap::default();
+    let mut insert = |name: String, item| {
+        items.entry(name).and_modify(|entry| *entry = None).or_insert(Some(item));
+    };
+    for cgu in codegen_units {
+        for (&item, data) in cgu.items() {
+            if data.inlined {
+                continue;
+            }
+            let symbol_name = item.symbol_name(tcx).name;
+            insert(format!("{:#}", rustc_demangle::demangle(symbol_name)), item);
+            insert(symbol_name.to_owned(), item);
+        }
+    }
+    items
+}
+
+/// Moves the root items of hot caller/callee pairs of `profile` into the same
+/// codegen unit, and returns the moves that were made. Codegen units that end
+/// up empty are removed, so `codegen_units` stays sorted by name.
+pub(super) fn co_locate_hot_items<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    profile: &CguProfile,
+    codegen_units: &mut Vec<CodegenUnit<'tcx>>,
+) -> Vec<ProfileMove<'tcx>> {
+    let tcx = cx.tcx;
+    let items_by_name = root_items_by_name(tcx, codegen_units);
+    let mut cgu_of_item: FxHashMap<MonoItem<'tcx>, usize> = FxHashMap::default();
+    for (i, cgu) in codegen_units.iter().enumerate() {
+        cgu_of_item.extend(
+            cgu.items().iter().filter(|(_, data)| !data.inlined).map(|(&item, _)| (item, i)),
+        );
+    }
+
+    let mut edges: Vec<_> = profile.edges.iter().collect();
+    // This sort is stable, so edges of the same weight are visited in the order
+    // of the profile.
+    edges.sort_by_key(|&(_, &weight)| std::cmp::Reverse(weight));
+
+    let mut placed = FxHashSet::default();
+    let mut moves = Vec::new();
+    let mut touched = FxIndexSet::default();
+    for ((caller, callee), &weight) in edges {
+        let (Some(&Some(caller)), Some(&Some(callee))) =
+            (items_by_name.get(caller), items_by_name.get(callee))
+        else {
+            continue;
+        };
+        if caller == callee {
+            continue;
+        }
+        let (caller_cgu, callee_cgu) = (cgu_of_item[&caller], cgu_of_item[&callee]);
+        let (item, from, to) = if caller_cgu == callee_cgu {
+            placed.extend([caller, callee]);
+            continue;
+        } else if !placed.contains(&callee) {
+            (callee, callee_cgu, caller_cgu)
+        } else if !placed.contains(&caller) {
+            (caller, caller_cgu, callee_cgu)
+        } else {
+            continue;
+        };
+        placed.extend([caller, callee]);
+
+        debug!(
+            "co_locate_hot_items: moving {item} from {} to {} (weight {weight})",
+            codegen_units[from].name(),
+            codegen_units[to].name()
+        );
+        let data = codegen_units[from].items_mut().shift_remove(&item).unwrap();
+        codegen_units[to].items_mut().insert(item, data);
+        cgu_of_item.insert(item, to);
+        touched.extend([from, to]);
+        moves.push(ProfileMove {
+            item,
+            from: codegen_units[from].name(),
+            to: codegen_units[to].name(),
+            weight,
+        });
+    }
+
+    for i in touched {
+        recompute_inlined_items(cx, &mut codegen_units[i]);
+        codegen_units[i].compute_size_estimate();
+    }
+    if codegen_units.len() > 1 {
+        codegen_units.retain(|cgu| cgu.items().values().any(|data| !data.inlined));
+    }
+    moves
+}
+
+/// Recomputes the inlined items of `cgu` from its root items after items were
+/// moved into or out of it, so that the moved items get copies of the inlined
+/// items they use, and the copies that only they used are not left behind.
+fn recompute_inlined_items<'tcx>(cx: &PartitioningCx<'_, 'tcx>, cgu: &mut CodegenUnit<'tcx>) {
+    let mut reachable_inlined_items = FxIndexSet::default();
+    for (&item, data) in cgu.items() {
+        if !data.inlined {
+            get_reachable_inlined_items(cx.tcx, item, cx.usage_map, &mut reachable_inlined_items);
+        }
+    }
+    let items = cgu.items_mut();
+    items.retain(|_, data| !data.inlined);
+    for inlined_item in reachable_inlined_items {
+        // This is a CGU-private copy.
+        items.insert(
+            inlined_item,
+            MonoItemData {
+                inlined: true,
+                linkage: Linkage::Internal,
+                visibility: Visibility::Default,
+                size_estimate: inlined_item.size_estimate(cx.tcx),
+            },
+        );
+    }
+}
+
+/// Writes the report of `-Zcgu-profile-report` for the final `codegen_units`.
+pub(super) fn write_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    profile: Option<&CguProfile>,
+    moves: &[ProfileMove<'tcx>],
+    codegen_units: &[CodegenUnit<'tcx>],
+    path: &Path,
+) {
+    if let Err(err) = try_write_report(tcx, profile, moves, codegen_units, path) {
+        tcx.dcx().emit_fatal(CouldntWriteCguProfileReport { path, error: err.to_string() });
+    }
+}
+
+fn try_write_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    profile: Option<&CguProfile>,
+    moves: &[ProfileMove<'tcx>],
+    codegen_units: &[CodegenUnit<'tcx>],
+    path: &Path,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    if let Some(profile) = profile {
+        let items_by_name = root_items_by_name(tcx, codegen_units);
+        let mut cgu_of_item = FxHashMap::default();
+        for cgu in codegen_units {
+            for (&item, data) in cgu.items() {
+                if !data.inlined {
+                    cgu_of_item.insert(item, cgu.name());
+                }
+            }
+        }
+
+        let (mut matched, mut matched_weight) = (0, 0);
+        let (mut co_located, mut co_located_weight) = (0, 0);
+        let total_weight: u64 = profile.edges.values().sum();
+        for ((caller, callee), &weight) in &profile.edges {
+            let (Some(&Some(caller)), Some(&Some(callee))) =
+                (items_by_name.get(caller), items_by_name.get(callee))
+            else {
+                continue;
+            };
+            matched += 1;
+            matched_weight += weight;
+            if cgu_of_item[&caller] == cgu_of_item[&callee] {
+                co_located += 1;
+                co_located_weight += weight;
+            }
+        }
+
+        writeln!(file, "profile edges: {} (weight {total_weight})", profile.edges.len())?;
+        writeln!(file, "matched edges: {matched} (weight {matched_weight})")?;
+        writeln!(file, "co-located edges: {co_located} (weight {co_located_weight})")?;
+        writeln!(file)?;
+        writeln!(file, "moved items: {}", moves.len())?;
+        for ProfileMove { item, from, to, weight } in moves {
+            let item = with_no_trimmed_paths!(item.to_string());
+            writeln!(file, "  {item}: {from} -> {to} (weight {weight})")?;
+        }
+        writeln!(file)?;
+    }
+
+    writeln!(file, "codegen units: {}", codegen_units.len())?;
+    for cgu in codegen_units {
+        writeln!(file, "  {} (size estimate {})", cgu.name(), cgu.size_estimate())?;
+        let mut items: Vec<_> = cgu
+            .items()
+            .iter()
+            .filter(|(_, data)| !data.inlined)
+            .map(|(item, _)| with_no_trimmed_paths!(item.to_string()))
+            .collect();
+        items.sort();
+        for item in items {
+            writeln!(file, "    {item}")?;
+        }
+    }
+
+    file.flush()
+}
@@ -1074,6 +1074,18 @@ pub(crate) fn run<F, T>(interface: &dyn CompilerInterface, f: F) -> Result<T, Er
     }
 }
 
+/// Like [`run`], but the interface may be installed while another one is already running.
+///
+/// The given interface shadows the current one until `f` returns. This is used to run user
+/// hooks from inside compiler queries, which may be executed while rustc_public is running.
+pub(crate) fn run_nested<F, T>(interface: &dyn CompilerInterface, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let ptr: *const () = (&raw const interface) as _;
+    TLV.set(&Cell::new(ptr), f)
+}
+
 /// Execute the given function with access the [`CompilerInterface`].
 ///
 /// I.e., This function will load the current interface and calls a function with it.
@@ -4,15 +4,24 @@
 //! until rustc_public's IR is complete.
 
 use std::cell::{Cell, RefCell};
+use std::iter;
+use std::sync::OnceLock;
 
+use rustc_middle::mir;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::query::queries::optimized_mir;
 use rustc_middle::ty::TyCtxt;
+use rustc_middle::util::Providers;
 use rustc_public_bridge::context::CompilerCtxt;
 use rustc_public_bridge::{Bridge, Container, Tables};
-use rustc_span::def_id::CrateNum;
+use rustc_session::Session;
+use rustc_span::def_id::{CrateNum, LocalDefId};
 use scoped_tls::scoped_thread_local;
 
-use crate::Error;
+use crate::compiler_interface::BridgeTys;
+use crate::mir::{BinOp, Body, Rvalue, StatementKind, TerminatorKind};
 use crate::unstable::{RustcInternal, Stable};
+use crate::{CrateItem, Error};
 
 pub mod pretty;
 
@@ -95,6 +104,213 @@ pub fn run<F, T>(tcx: TyCtxt<'_>, f: F) -> Result<T, Error>
     crate::compiler_interface::run(&container, || init(&container, f))
 }
 
+/// A hook that can replace the optimized MIR of the local functions.
+///
+/// The hook is invoked with the function and its optimized body, and it returns the body that
+/// should be used instead, or `None` to keep the original one.
+pub type OptimizedMirHook = fn(CrateItem, &Body) -> Option<Body>;
+
+static OPTIMIZED_MIR_HOOK: OnceLock<OptimizedMirHook> = OnceLock::new();
+
+static DEFAULT_OPTIMIZED_MIR: OnceLock<
+    for<'tcx> fn(TyCtxt<'tcx>, LocalDefId) -> optimized_mir::ProvidedValue<'tcx>,
+> = OnceLock::new();
+
+/// Install a hook that can replace the optimized MIR of the local functions before codegen.
+///
+/// This only has an effect if the compiler was started by [run!] or [run_with_tcx!], or if
+/// [override_queries] was installed with `rustc_interface::Config::override_queries`.
+/// The hook must be installed before the optimized MIR of a function is computed, which may
+/// happen as soon as the analysis is done, so it is best to install it before the compiler
+/// starts.
+///
+/// The hook runs with its own rustc_public context: items that were obtained outside of the
+/// hook must not be used inside of it, and vice versa.
+///
+/// Bodies that contain constructs that cannot be converted back from rustc_public's IR, such as
+/// inline assembly, coverage statements and coroutines, are not passed to the hook. The
+/// returned body must keep the types of the arguments and of the return value, and it cannot
+/// use those constructs either. Its types are checked like MIR validation does, and an invalid
+/// body is a fatal error.
+///
+/// # Warning
+///
+/// This function is unstable, and its behavior may change at any point.
+pub fn set_optimized_mir_hook(hook: OptimizedMirHook) -> Result<(), Error> {
+    OPTIMIZED_MIR_HOOK
+        .set(hook)
+        .map_err(|_| Error::from("an optimized MIR hook has already been installed"))
+}
+
+/// Override the queries needed by [set_optimized_mir_hook].
+///
+/// This is meant to be used as `rustc_interface::Config::override_queries` by drivers that are
+/// not started with [run!] or [run_with_tcx!].
+pub fn override_queries(_session: &Session, providers: &mut Providers) {
+    DEFAULT_OPTIMIZED_MIR.get_or_init(|| providers.optimized_mir);
+    providers.optimized_mir = hooked_optimized_mir;
+}
+
+fn hooked_optimized_mir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> optimized_mir::ProvidedValue<'tcx> {
+    let body = DEFAULT_OPTIMIZED_MIR.get().unwrap()(tcx, def_id);
+    let Some(hook) = OPTIMIZED_MIR_HOOK.get() else { return body };
+    if !can_replace(body) {
+        return body;
+    }
+
+    // This query may be executed while rustc_public is running, and the tables of the current
+    // context may be borrowed. So we use a new context that shadows it while the hook runs.
+    let container: Container<'tcx, BridgeTys> = Container {
+        tables: RefCell::new(Tables::default()),
+        cx: RefCell::new(CompilerCtxt::new(tcx)),
+    };
+    let (item, stable_body) = {
+        let mut tables = container.tables.borrow_mut();
+        let cx = container.cx.borrow();
+        (tables.crate_item(def_id.to_def_id()), body.stable(&mut tables, &cx))
+    };
+    let new_body = crate::compiler_interface::run_nested(&container, || {
+        TLV.set(&Cell::new(&container as *const _ as *const ()), || hook(item, &stable_body))
+    });
+    let Some(new_body) = new_body else { return body };
+    if let Err(msg) = validate_replacement(&stable_body, &new_body) {
+        tcx.dcx().span_fatal(
+            tcx.def_span(def_id),
+            format!("optimized MIR hook returned an invalid body: {msg}"),
+        );
+    }
+
+    let mut tables = container.tables.borrow_mut();
+    let cx = container.cx.borrow();
+    let blocks = new_body
+        .blocks
+        .iter()
+        .map(|block| {
+            (
+                block.statements.internal(&mut tables, tcx),
+                block.terminator.internal(&mut tables, tcx),
+            )
+        })
+        .collect();
+    let locals = new_body.locals().iter().map(|decl| decl.internal(&mut tables, tcx)).collect();
+    let var_debug_info = new_body.var_debug_info.internal(&mut tables, tcx);
+    let new_body = cx.replace_mir_body(body, blocks, locals, var_debug_info);
+    if let Some((location, msg)) = tcx.validate_mir_types(new_body).into_iter().next() {
+        tcx.dcx().span_fatal(
+            new_body.source_info(location).span,
+            format!("optimized MIR hook returned an invalid body: {msg} at {location:?}"),
+        );
+    }
+    new_body
+}
+
+/// Whether `body` can be converted to rustc_public's IR and back without losing information.
+fn can_replace(body: &mir::Body<'_>) -> bool {
+    struct UnsupportedFinder {
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for UnsupportedFinder {
+        fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: mir::Location) {
+            match statement.kind {
+                mir::StatementKind::FakeRead(..)
+                | mir::StatementKind::AscribeUserType(..)
+                | mir::StatementKind::Coverage(..) => self.found = true,
+                _ => self.super_statement(statement, location),
+            }
+        }
+
+        fn visit_terminator(
+            &mut self,
+            terminator: &mir::Terminator<'tcx>,
+            location: mir::Location,
+        ) {
+            match terminator.kind {
+                mir::TerminatorKind::InlineAsm { .. } | mir::TerminatorKind::TailCall { .. } => {
+                    self.found = true
+                }
+                _ => self.super_terminator(terminator, location),
+            }
+        }
+
+        fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: mir::Location) {
+            match rvalue {
+                mir::Rvalue::WrapUnsafeBinder(..) => self.found = true,
+                _ => self.super_rvalue(rvalue, location),
+            }
+        }
+
+        fn visit_projection_elem(
+            &mut self,
+            place_ref: mir::PlaceRef<'tcx>,
+            elem: mir::PlaceElem<'tcx>,
+            context: PlaceContext,
+            location: mir::Location,
+        ) {
+            match elem {
+                mir::ProjectionElem::UnwrapUnsafeBinder(..) => self.found = true,
+                _ => self.super_projection_elem(place_ref, elem, context, location),
+            }
+        }
+    }
+
+    if body.coroutine.is_some() {
+        return false;
+    }
+    let mut finder = UnsupportedFinder { found: false };
+    finder.visit_body(body);
+    !finder.found
+}
+
+/// Check that a body returned by an [OptimizedMirHook] can replace `original`.
+fn validate_replacement(original: &Body, new: &Body) -> Result<(), String> {
+    if new.ret_local().ty != original.ret_local().ty {
+        return Err(format!("the return type must stay `{}`", original.ret_local().ty));
+    }
+    if new.arg_locals().len() != original.arg_locals().len() {
+        return Err("the number of arguments must not change".to_string());
+    }
+    for (idx, (new_arg, arg)) in iter::zip(new.arg_locals(), original.arg_locals()).enumerate() {
+        if new_arg.ty != arg.ty {
+            return Err(format!("the type of argument {} must stay `{}`", idx + 1, arg.ty));
+        }
+    }
+    if new.spread_arg() != original.spread_arg() {
+        return Err("the spread argument must not change".to_string());
+    }
+    if new.blocks.is_empty() {
+        return Err("the body must have at least one basic block".to_string());
+    }
+    for block in &new.blocks {
+        for statement in &block.statements {
+            if matches!(
+                statement.kind,
+                StatementKind::FakeRead(..)
+                    | StatementKind::AscribeUserType { .. }
+                    | StatementKind::Coverage(..)
+            ) {
+                return Err(format!("unsupported statement `{:?}`", statement.kind));
+            }
+            if let StatementKind::Assign(_, Rvalue::CheckedBinaryOp(op, ..)) = &statement.kind
+                && !matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul)
+            {
+                return Err(format!("`{op:?}` cannot be used in a checked binary operation"));
+            }
+        }
+        let successors = block.terminator.successors();
+        if let Some(target) = successors.iter().find(|target| **target >= new.blocks.len()) {
+            return Err(format!("basic block `bb{target}` does not exist"));
+        }
+        if matches!(block.terminator.kind, TerminatorKind::InlineAsm { .. }) {
+            return Err("inline assembly is not supported".to_string());
+        }
+    }
+    Ok(())
+}
+
 /// Instantiate and run the compiler with the provided arguments and callback.
 ///
 /// The callback will be invoked after the compiler ran all its analyses, but before code generation.
@@ -242,6 +458,12 @@ impl<B, C, F> Callbacks for RustcPublic<B, C, F>
             C: Send,
             F: FnOnce($($crate::optional!($with_tcx TyCtxt))?) -> ControlFlow<B, C> + Send,
         {
+            /// Called before creating the compiler instance. Installs the query overrides
+            /// needed by `rustc_internal::set_optimized_mir_hook`.
+            fn config(&mut self, config: &mut interface::Config) {
+                config.override_queries = Some(rustc_internal::override_queries);
+            }
+
             /// Called after analysis. Return value instructs the compiler whether to
             /// continue the compilation afterwards (defaults to `Compilation::Continue`)
             fn after_analysis<'tcx>(
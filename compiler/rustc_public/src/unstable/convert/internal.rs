@@ -5,14 +5,23 @@
 
 // Prefer importing rustc_public over internal rustc constructs to make this file more readable.
 
+use rustc_middle::mir as rustc_mir;
 use rustc_middle::ty::{self as rustc_ty, Const as InternalConst, Ty as InternalTy};
 use rustc_public_bridge::Tables;
+use rustc_span::DUMMY_SP;
+use rustc_span::source_map::Spanned;
 
 use crate::abi::Layout;
 use crate::compiler_interface::BridgeTys;
 use crate::mir::alloc::AllocId;
 use crate::mir::mono::{Instance, MonoItem, StaticDef};
-use crate::mir::{BinOp, Mutability, Place, ProjectionElem, RawPtrKind, Safety, UnOp};
+use crate::mir::{
+    AggregateKind, AssertMessage, BinOp, BorrowKind, CastKind, ConstOperand, CoroutineDesugaring,
+    CoroutineKind, CoroutineSource, FakeBorrowKind, LocalDecl, MutBorrowKind, Mutability,
+    NonDivergingIntrinsic, NullOp, Operand, Place, PointerCoercion, ProjectionElem, RawPtrKind,
+    RetagKind, Rvalue, Safety, Statement, StatementKind, SwitchTargets, Terminator, TerminatorKind,
+    UnOp, UnwindAction, VarDebugInfo, VarDebugInfoContents,
+};
 use crate::ty::{
     Abi, AdtDef, Binder, BoundRegionKind, BoundTyKind, BoundVariableKind, ClosureKind, DynKind,
     ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig,
@@ -777,6 +786,632 @@ fn internal<'tcx>(
     }
 }
 
+impl RustcInternal for Statement {
+    type T<'tcx> = rustc_mir::Statement<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        // The stable statement doesn't keep its scope. It is recovered from the original body
+        // when the statement is part of a replaced body.
+        rustc_mir::Statement::new(
+            rustc_mir::SourceInfo::outermost(self.span.internal(tables, tcx)),
+            self.kind.internal(tables, tcx),
+        )
+    }
+}
+
+impl RustcInternal for StatementKind {
+    type T<'tcx> = rustc_mir::StatementKind<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            StatementKind::Assign(place, rvalue) => rustc_mir::StatementKind::Assign(Box::new((
+                place.internal(tables, tcx),
+                rvalue.internal(tables, tcx),
+            ))),
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                rustc_mir::StatementKind::SetDiscriminant {
+                    place: Box::new(place.internal(tables, tcx)),
+                    variant_index: variant_index.internal(tables, tcx),
+                }
+            }
+            StatementKind::Deinit(place) => {
+                rustc_mir::StatementKind::Deinit(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::StorageLive(local) => {
+                rustc_mir::StatementKind::StorageLive(rustc_mir::Local::from_usize(*local))
+            }
+            StatementKind::StorageDead(local) => {
+                rustc_mir::StatementKind::StorageDead(rustc_mir::Local::from_usize(*local))
+            }
+            StatementKind::Retag(kind, place) => rustc_mir::StatementKind::Retag(
+                kind.internal(tables, tcx),
+                Box::new(place.internal(tables, tcx)),
+            ),
+            StatementKind::PlaceMention(place) => {
+                rustc_mir::StatementKind::PlaceMention(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::Intrinsic(intrinsic) => {
+                rustc_mir::StatementKind::Intrinsic(Box::new(intrinsic.internal(tables, tcx)))
+            }
+            StatementKind::ConstEvalCounter => rustc_mir::StatementKind::ConstEvalCounter,
+            StatementKind::Nop => rustc_mir::StatementKind::Nop,
+            // These statements are only kept as opaque or lossy values in rustc_public.
+            StatementKind::FakeRead(..)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(..) => {
+                panic!("cannot convert `{self:?}` back to an internal statement")
+            }
+        }
+    }
+}
+
+impl RustcInternal for RetagKind {
+    type T<'tcx> = rustc_mir::RetagKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            RetagKind::FnEntry => rustc_mir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => rustc_mir::RetagKind::TwoPhase,
+            RetagKind::Raw => rustc_mir::RetagKind::Raw,
+            RetagKind::Default => rustc_mir::RetagKind::Default,
+        }
+    }
+}
+
+impl RustcInternal for NonDivergingIntrinsic {
+    type T<'tcx> = rustc_mir::NonDivergingIntrinsic<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            NonDivergingIntrinsic::Assume(op) => {
+                rustc_mir::NonDivergingIntrinsic::Assume(op.internal(tables, tcx))
+            }
+            NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                rustc_mir::NonDivergingIntrinsic::CopyNonOverlapping(
+                    rustc_mir::CopyNonOverlapping {
+                        src: copy.src.internal(tables, tcx),
+                        dst: copy.dst.internal(tables, tcx),
+                        count: copy.count.internal(tables, tcx),
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl RustcInternal for Rvalue {
+    type T<'tcx> = rustc_mir::Rvalue<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            Rvalue::AddressOf(kind, place) => {
+                rustc_mir::Rvalue::RawPtr(kind.internal(tables, tcx), place.internal(tables, tcx))
+            }
+            Rvalue::Aggregate(kind, operands) => rustc_mir::Rvalue::Aggregate(
+                Box::new(kind.internal(tables, tcx)),
+                operands.iter().map(|op| op.internal(tables, tcx)).collect(),
+            ),
+            Rvalue::BinaryOp(op, lhs, rhs) => rustc_mir::Rvalue::BinaryOp(
+                op.internal(tables, tcx),
+                Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+            ),
+            Rvalue::Cast(kind, op, ty) => rustc_mir::Rvalue::Cast(
+                kind.internal(tables, tcx),
+                op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::CheckedBinaryOp(op, lhs, rhs) => {
+                let Some(op) = op.internal(tables, tcx).wrapping_to_overflowing() else {
+                    panic!("`{op:?}` cannot be used in a checked binary operation")
+                };
+                rustc_mir::Rvalue::BinaryOp(
+                    op,
+                    Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+                )
+            }
+            Rvalue::CopyForDeref(place) => {
+                rustc_mir::Rvalue::CopyForDeref(place.internal(tables, tcx))
+            }
+            Rvalue::Discriminant(place) => {
+                rustc_mir::Rvalue::Discriminant(place.internal(tables, tcx))
+            }
+            Rvalue::Len(place) => rustc_mir::Rvalue::Len(place.internal(tables, tcx)),
+            Rvalue::Ref(region, kind, place) => rustc_mir::Rvalue::Ref(
+                region.internal(tables, tcx),
+                kind.internal(tables, tcx),
+                place.internal(tables, tcx),
+            ),
+            Rvalue::Repeat(op, count) => {
+                rustc_mir::Rvalue::Repeat(op.internal(tables, tcx), count.internal(tables, tcx))
+            }
+            Rvalue::ShallowInitBox(op, ty) => rustc_mir::Rvalue::ShallowInitBox(
+                op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::ThreadLocalRef(item) => {
+                rustc_mir::Rvalue::ThreadLocalRef(item.internal(tables, tcx))
+            }
+            Rvalue::NullaryOp(op, ty) => {
+                rustc_mir::Rvalue::NullaryOp(op.internal(tables, tcx), ty.internal(tables, tcx))
+            }
+            Rvalue::UnaryOp(op, operand) => {
+                rustc_mir::Rvalue::UnaryOp(op.internal(tables, tcx), operand.internal(tables, tcx))
+            }
+            Rvalue::Use(op) => rustc_mir::Rvalue::Use(op.internal(tables, tcx)),
+        }
+    }
+}
+
+impl RustcInternal for AggregateKind {
+    type T<'tcx> = rustc_mir::AggregateKind<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            AggregateKind::Array(ty) => rustc_mir::AggregateKind::Array(ty.internal(tables, tcx)),
+            AggregateKind::Tuple => rustc_mir::AggregateKind::Tuple,
+            // User type annotations are only needed by borrowck, so we drop them.
+            AggregateKind::Adt(def, variant, args, _user_ty, field) => {
+                rustc_mir::AggregateKind::Adt(
+                    def.0.internal(tables, tcx),
+                    variant.internal(tables, tcx),
+                    args.internal(tables, tcx),
+                    None,
+                    field.map(rustc_abi::FieldIdx::from_usize),
+                )
+            }
+            AggregateKind::Closure(def, args) => rustc_mir::AggregateKind::Closure(
+                def.0.internal(tables, tcx),
+                args.internal(tables, tcx),
+            ),
+            AggregateKind::Coroutine(def, args) => rustc_mir::AggregateKind::Coroutine(
+                def.0.internal(tables, tcx),
+                args.internal(tables, tcx),
+            ),
+            AggregateKind::CoroutineClosure(def, args) => {
+                rustc_mir::AggregateKind::CoroutineClosure(
+                    def.0.internal(tables, tcx),
+                    args.internal(tables, tcx),
+                )
+            }
+            AggregateKind::RawPtr(ty, mutability) => rustc_mir::AggregateKind::RawPtr(
+                ty.internal(tables, tcx),
+                mutability.internal(tables, tcx),
+            ),
+        }
+    }
+}
+
+impl RustcInternal for BorrowKind {
+    type T<'tcx> = rustc_mir::BorrowKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            BorrowKind::Shared => rustc_mir::BorrowKind::Shared,
+            BorrowKind::Fake(kind) => rustc_mir::BorrowKind::Fake(kind.internal(tables, tcx)),
+            BorrowKind::Mut { kind } => {
+                rustc_mir::BorrowKind::Mut { kind: kind.internal(tables, tcx) }
+            }
+        }
+    }
+}
+
+impl RustcInternal for MutBorrowKind {
+    type T<'tcx> = rustc_mir::MutBorrowKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            MutBorrowKind::Default => rustc_mir::MutBorrowKind::Default,
+            MutBorrowKind::TwoPhaseBorrow => rustc_mir::MutBorrowKind::TwoPhaseBorrow,
+            MutBorrowKind::ClosureCapture => rustc_mir::MutBorrowKind::ClosureCapture,
+        }
+    }
+}
+
+impl RustcInternal for FakeBorrowKind {
+    type T<'tcx> = rustc_mir::FakeBorrowKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            FakeBorrowKind::Deep => rustc_mir::FakeBorrowKind::Deep,
+            FakeBorrowKind::Shallow => rustc_mir::FakeBorrowKind::Shallow,
+        }
+    }
+}
+
+impl RustcInternal for CastKind {
+    type T<'tcx> = rustc_mir::CastKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            CastKind::PointerExposeAddress => rustc_mir::CastKind::PointerExposeProvenance,
+            CastKind::PointerWithExposedProvenance => {
+                rustc_mir::CastKind::PointerWithExposedProvenance
+            }
+            // The source of a coercion is only used for diagnostics.
+            CastKind::PointerCoercion(coercion) => rustc_mir::CastKind::PointerCoercion(
+                coercion.internal(tables, tcx),
+                rustc_mir::CoercionSource::Implicit,
+            ),
+            CastKind::IntToInt => rustc_mir::CastKind::IntToInt,
+            CastKind::FloatToInt => rustc_mir::CastKind::FloatToInt,
+            CastKind::FloatToFloat => rustc_mir::CastKind::FloatToFloat,
+            CastKind::IntToFloat => rustc_mir::CastKind::IntToFloat,
+            CastKind::PtrToPtr => rustc_mir::CastKind::PtrToPtr,
+            CastKind::FnPtrToPtr => rustc_mir::CastKind::FnPtrToPtr,
+            CastKind::Transmute => rustc_mir::CastKind::Transmute,
+        }
+    }
+}
+
+impl RustcInternal for PointerCoercion {
+    type T<'tcx> = rustc_ty::adjustment::PointerCoercion;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_ty::adjustment::PointerCoercion as InternalCoercion;
+        match self {
+            PointerCoercion::ReifyFnPointer => InternalCoercion::ReifyFnPointer,
+            PointerCoercion::UnsafeFnPointer => InternalCoercion::UnsafeFnPointer,
+            PointerCoercion::ClosureFnPointer(safety) => {
+                InternalCoercion::ClosureFnPointer(safety.internal(tables, tcx))
+            }
+            PointerCoercion::MutToConstPointer => InternalCoercion::MutToConstPointer,
+            PointerCoercion::ArrayToPointer => InternalCoercion::ArrayToPointer,
+            PointerCoercion::Unsize => InternalCoercion::Unsize,
+        }
+    }
+}
+
+impl RustcInternal for NullOp {
+    type T<'tcx> = rustc_mir::NullOp<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            NullOp::SizeOf => rustc_mir::NullOp::SizeOf,
+            NullOp::AlignOf => rustc_mir::NullOp::AlignOf,
+            NullOp::OffsetOf(indices) => {
+                let indices: Vec<_> = indices
+                    .iter()
+                    .map(|(variant, field)| {
+                        (variant.internal(tables, tcx), rustc_abi::FieldIdx::from_usize(*field))
+                    })
+                    .collect();
+                rustc_mir::NullOp::OffsetOf(tcx.mk_offset_of(&indices))
+            }
+            NullOp::UbChecks => rustc_mir::NullOp::UbChecks,
+            NullOp::ContractChecks => rustc_mir::NullOp::ContractChecks,
+        }
+    }
+}
+
+impl RustcInternal for Operand {
+    type T<'tcx> = rustc_mir::Operand<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            Operand::Copy(place) => rustc_mir::Operand::Copy(place.internal(tables, tcx)),
+            Operand::Move(place) => rustc_mir::Operand::Move(place.internal(tables, tcx)),
+            Operand::Constant(constant) => {
+                rustc_mir::Operand::Constant(Box::new(constant.internal(tables, tcx)))
+            }
+        }
+    }
+}
+
+impl RustcInternal for ConstOperand {
+    type T<'tcx> = rustc_mir::ConstOperand<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        // User type annotations are only needed by borrowck, so we drop them.
+        rustc_mir::ConstOperand {
+            span: self.span.internal(tables, tcx),
+            user_ty: None,
+            const_: self.const_.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for Terminator {
+    type T<'tcx> = rustc_mir::Terminator<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let span = self.span.internal(tables, tcx);
+        let mut kind = self.kind.internal(tables, tcx);
+        if let rustc_mir::TerminatorKind::Call { fn_span, .. } = &mut kind {
+            *fn_span = span;
+        }
+        // Like statements, the scope is recovered from the original body.
+        rustc_mir::Terminator { source_info: rustc_mir::SourceInfo::outermost(span), kind }
+    }
+}
+
+impl RustcInternal for TerminatorKind {
+    type T<'tcx> = rustc_mir::TerminatorKind<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let bb = rustc_mir::BasicBlock::from_usize;
+        match self {
+            TerminatorKind::Goto { target } => {
+                rustc_mir::TerminatorKind::Goto { target: bb(*target) }
+            }
+            TerminatorKind::SwitchInt { discr, targets } => rustc_mir::TerminatorKind::SwitchInt {
+                discr: discr.internal(tables, tcx),
+                targets: targets.internal(tables, tcx),
+            },
+            TerminatorKind::Resume => rustc_mir::TerminatorKind::UnwindResume,
+            // The reason is recovered from the body once we know which blocks are cleanup blocks.
+            TerminatorKind::Abort => {
+                rustc_mir::TerminatorKind::UnwindTerminate(rustc_mir::UnwindTerminateReason::Abi)
+            }
+            TerminatorKind::Return => rustc_mir::TerminatorKind::Return,
+            TerminatorKind::Unreachable => rustc_mir::TerminatorKind::Unreachable,
+            TerminatorKind::Drop { place, target, unwind } => rustc_mir::TerminatorKind::Drop {
+                place: place.internal(tables, tcx),
+                target: bb(*target),
+                unwind: unwind.internal(tables, tcx),
+                replace: false,
+                drop: None,
+                async_fut: None,
+            },
+            TerminatorKind::Call { func, args, destination, target, unwind } => {
+                rustc_mir::TerminatorKind::Call {
+                    func: func.internal(tables, tcx),
+                    args: args
+                        .iter()
+                        .map(|arg| Spanned { node: arg.internal(tables, tcx), span: DUMMY_SP })
+                        .collect(),
+                    destination: destination.internal(tables, tcx),
+                    target: target.map(bb),
+                    unwind: unwind.internal(tables, tcx),
+                    call_source: rustc_mir::CallSource::Normal,
+                    fn_span: DUMMY_SP,
+                }
+            }
+            TerminatorKind::Assert { cond, expected, msg, target, unwind } => {
+                rustc_mir::TerminatorKind::Assert {
+                    cond: cond.internal(tables, tcx),
+                    expected: *expected,
+                    msg: Box::new(msg.internal(tables, tcx)),
+                    target: bb(*target),
+                    unwind: unwind.internal(tables, tcx),
+                }
+            }
+            // The stable representation of inline assembly is lossy.
+            TerminatorKind::InlineAsm { .. } => {
+                panic!("cannot convert inline assembly back to an internal terminator")
+            }
+        }
+    }
+}
+
+impl RustcInternal for SwitchTargets {
+    type T<'tcx> = rustc_mir::SwitchTargets;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::SwitchTargets::new(
+            self.branches()
+                .map(|(value, target)| (value, rustc_mir::BasicBlock::from_usize(target))),
+            rustc_mir::BasicBlock::from_usize(self.otherwise()),
+        )
+    }
+}
+
+impl RustcInternal for UnwindAction {
+    type T<'tcx> = rustc_mir::UnwindAction;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            UnwindAction::Continue => rustc_mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => rustc_mir::UnwindAction::Unreachable,
+            UnwindAction::Terminate => {
+                rustc_mir::UnwindAction::Terminate(rustc_mir::UnwindTerminateReason::Abi)
+            }
+            UnwindAction::Cleanup(target) => {
+                rustc_mir::UnwindAction::Cleanup(rustc_mir::BasicBlock::from_usize(*target))
+            }
+        }
+    }
+}
+
+impl RustcInternal for AssertMessage {
+    type T<'tcx> = rustc_mir::AssertMessage<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_mir::AssertKind;
+        match self {
+            AssertMessage::BoundsCheck { len, index } => AssertKind::BoundsCheck {
+                len: len.internal(tables, tcx),
+                index: index.internal(tables, tcx),
+            },
+            AssertMessage::Overflow(op, lhs, rhs) => AssertKind::Overflow(
+                op.internal(tables, tcx),
+                lhs.internal(tables, tcx),
+                rhs.internal(tables, tcx),
+            ),
+            AssertMessage::OverflowNeg(op) => AssertKind::OverflowNeg(op.internal(tables, tcx)),
+            AssertMessage::DivisionByZero(op) => {
+                AssertKind::DivisionByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::RemainderByZero(op) => {
+                AssertKind::RemainderByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterReturn(kind) => {
+                AssertKind::ResumedAfterReturn(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterPanic(kind) => {
+                AssertKind::ResumedAfterPanic(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterDrop(kind) => {
+                AssertKind::ResumedAfterDrop(kind.internal(tables, tcx))
+            }
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                AssertKind::MisalignedPointerDereference {
+                    required: required.internal(tables, tcx),
+                    found: found.internal(tables, tcx),
+                }
+            }
+            AssertMessage::NullPointerDereference => AssertKind::NullPointerDereference,
+            AssertMessage::InvalidEnumConstruction(op) => {
+                AssertKind::InvalidEnumConstruction(op.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for CoroutineKind {
+    type T<'tcx> = rustc_hir::CoroutineKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            CoroutineKind::Desugared(desugaring, source) => {
+                let desugaring = match desugaring {
+                    CoroutineDesugaring::Async => rustc_hir::CoroutineDesugaring::Async,
+                    CoroutineDesugaring::Gen => rustc_hir::CoroutineDesugaring::Gen,
+                    CoroutineDesugaring::AsyncGen => rustc_hir::CoroutineDesugaring::AsyncGen,
+                };
+                let source = match source {
+                    CoroutineSource::Block => rustc_hir::CoroutineSource::Block,
+                    CoroutineSource::Closure => rustc_hir::CoroutineSource::Closure,
+                    CoroutineSource::Fn => rustc_hir::CoroutineSource::Fn,
+                };
+                rustc_hir::CoroutineKind::Desugared(desugaring, source)
+            }
+            CoroutineKind::Coroutine(movability) => {
+                rustc_hir::CoroutineKind::Coroutine(movability.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for LocalDecl {
+    type T<'tcx> = rustc_mir::LocalDecl<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let mut decl = rustc_mir::LocalDecl::new(
+            self.ty.internal(tables, tcx),
+            self.span.internal(tables, tcx),
+        );
+        decl.mutability = self.mutability.internal(tables, tcx);
+        decl
+    }
+}
+
+impl RustcInternal for VarDebugInfo {
+    type T<'tcx> = rustc_mir::VarDebugInfo<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::VarDebugInfo {
+            name: rustc_span::Symbol::intern(&self.name),
+            source_info: rustc_mir::SourceInfo {
+                span: self.source_info.span.internal(tables, tcx),
+                scope: rustc_mir::SourceScope::from_u32(self.source_info.scope),
+            },
+            composite: self.composite.as_ref().map(|composite| {
+                Box::new(rustc_mir::VarDebugInfoFragment {
+                    ty: composite.ty.internal(tables, tcx),
+                    projection: composite.projection.internal(tables, tcx),
+                })
+            }),
+            value: match &self.value {
+                VarDebugInfoContents::Place(place) => {
+                    rustc_mir::VarDebugInfoContents::Place(place.internal(tables, tcx))
+                }
+                VarDebugInfoContents::Const(constant) => {
+                    rustc_mir::VarDebugInfoContents::Const(constant.internal(tables, tcx))
+                }
+            },
+            argument_index: self.argument_index,
+        }
+    }
+}
+
 impl<T> RustcInternal for &T
 where
     T: RustcInternal,
@@ -962,6 +962,7 @@ fn stable<'cx>(
             | ty::InstanceKind::ClosureOnceShim { .. }
             | ty::InstanceKind::ConstructCoroutineInClosureShim { .. }
             | ty::InstanceKind::ThreadLocalShim(..)
+            | ty::InstanceKind::OutlinedColdPath(..)
             | ty::InstanceKind::DropGlue(..)
             | ty::InstanceKind::CloneShim(..)
             | ty::InstanceKind::FnPtrShim(..)
@@ -87,6 +87,13 @@ fn mk_place_elems(self, v: &[mir::PlaceElem<'tcx>]) -> &'tcx List<mir::PlaceElem
         TyCtxt::mk_place_elems(self, v)
     }
 
+    fn mk_offset_of(
+        self,
+        v: &[(rustc_abi::VariantIdx, rustc_abi::FieldIdx)],
+    ) -> &'tcx List<(rustc_abi::VariantIdx, rustc_abi::FieldIdx)> {
+        TyCtxt::mk_offset_of(self, v)
+    }
+
     fn adt_def(self, def_id: rustc_hir::def_id::DefId) -> ty::AdtDef<'tcx> {
         self.adt_def(def_id)
     }
@@ -50,6 +50,11 @@ fn mk_bound_variable_kinds_from_iter<I, T>(self, iter: I) -> T::Output
 
     fn mk_place_elems(self, v: &[mir::PlaceElem<'tcx>]) -> &'tcx List<mir::PlaceElem<'tcx>>;
 
+    fn mk_offset_of(
+        self,
+        v: &[(rustc_abi::VariantIdx, rustc_abi::FieldIdx)],
+    ) -> &'tcx List<(rustc_abi::VariantIdx, rustc_abi::FieldIdx)>;
+
     fn adt_def(self, def_id: rustc_hir::def_id::DefId) -> ty::AdtDef<'tcx>;
 }
 
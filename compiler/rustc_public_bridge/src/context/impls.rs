@@ -5,6 +5,7 @@
 use std::iter;
 
 use rustc_abi::{Endian, Layout, ReprOptions};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def::DefKind;
 use rustc_hir::{Attribute, LangItem};
 use rustc_middle::mir::interpret::{AllocId, ConstAllocation, ErrorHandled, GlobalAlloc, Scalar};
@@ -135,6 +136,99 @@ pub fn has_body(&self, def: DefId) -> bool {
         self.item_has_body(def)
     }
 
+    /// Build a copy of `body` with the given basic blocks, locals and debug info, and allocate
+    /// it in the compiler arena.
+    ///
+    /// The new blocks are expected to be converted from rustc_public's IR, which doesn't keep
+    /// source scopes nor whether a block is a cleanup block. Source scopes are recovered from
+    /// statements and terminators of `body` with the same span, and cleanup blocks are the ones
+    /// reachable from an unwind edge.
+    pub fn replace_mir_body(
+        &self,
+        body: &Body<'tcx>,
+        blocks: Vec<(Vec<mir::Statement<'tcx>>, mir::Terminator<'tcx>)>,
+        locals: Vec<mir::LocalDecl<'tcx>>,
+        mut var_debug_info: Vec<mir::VarDebugInfo<'tcx>>,
+    ) -> &'tcx Body<'tcx> {
+        let scopes: FxHashMap<Span, mir::SourceScope> = body
+            .basic_blocks
+            .iter()
+            .flat_map(|data| {
+                let terminator = data.terminator.as_ref().map(|terminator| terminator.source_info);
+                data.statements.iter().map(|statement| statement.source_info).chain(terminator)
+            })
+            .map(|source_info| (source_info.span, source_info.scope))
+            .collect();
+        let recover_scope = |source_info: &mut mir::SourceInfo| {
+            if let Some(scope) = scopes.get(&source_info.span) {
+                source_info.scope = *scope;
+            }
+        };
+
+        let mut is_cleanup = vec![false; blocks.len()];
+        let mut worklist: Vec<mir::BasicBlock> = blocks
+            .iter()
+            .filter_map(|(_, terminator)| match terminator.kind.unwind() {
+                Some(mir::UnwindAction::Cleanup(target)) => Some(*target),
+                _ => None,
+            })
+            .collect();
+        while let Some(bb) = worklist.pop() {
+            if !std::mem::replace(&mut is_cleanup[bb.as_usize()], true) {
+                worklist.extend(blocks[bb.as_usize()].1.successors());
+            }
+        }
+
+        let mut new_body = body.clone();
+        *new_body.basic_blocks_mut() = iter::zip(blocks, is_cleanup)
+            .map(|((mut statements, mut terminator), is_cleanup)| {
+                statements
+                    .iter_mut()
+                    .for_each(|statement| recover_scope(&mut statement.source_info));
+                recover_scope(&mut terminator.source_info);
+                if is_cleanup {
+                    let reason = match &mut terminator.kind {
+                        mir::TerminatorKind::UnwindTerminate(reason) => Some(reason),
+                        kind => match kind.unwind_mut() {
+                            Some(mir::UnwindAction::Terminate(reason)) => Some(reason),
+                            _ => None,
+                        },
+                    };
+                    if let Some(reason) = reason {
+                        *reason = mir::UnwindTerminateReason::InCleanup;
+                    }
+                }
+                mir::BasicBlockData::new_stmts(statements, Some(terminator), is_cleanup)
+            })
+            .collect();
+
+        // Keep the information that only the compiler knows about for locals that are unchanged.
+        new_body.local_decls = locals
+            .into_iter()
+            .enumerate()
+            .map(|(idx, decl)| match body.local_decls.get(mir::Local::from_usize(idx)) {
+                Some(original) if original.ty == decl.ty => mir::LocalDecl {
+                    mutability: decl.mutability,
+                    source_info: mir::SourceInfo {
+                        span: decl.source_info.span,
+                        ..original.source_info
+                    },
+                    ..original.clone()
+                },
+                _ => decl,
+            })
+            .collect();
+
+        for info in &mut var_debug_info {
+            if info.source_info.scope.as_usize() >= body.source_scopes.len() {
+                info.source_info.scope = mir::OUTERMOST_SOURCE_SCOPE;
+            }
+        }
+        new_body.var_debug_info = var_debug_info;
+
+        self.tcx.arena.alloc(new_body)
+    }
+
     pub fn foreign_modules(&self, crate_num: CrateNum) -> Vec<DefId> {
         self.tcx.foreign_modules(crate_num).keys().map(|mod_def_id| *mod_def_id).collect()
     }
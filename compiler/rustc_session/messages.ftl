@@ -24,6 +24,8 @@ session_expr_parentheses_needed = parentheses are required to parse this as an e
 
 session_failed_to_create_profiler = failed to create profiler: {$err}
 
+session_failed_to_write_profile = failed to write self-profile trace to `{$path}`: {$err}
+
 session_feature_diagnostic_for_issue =
     see issue #{$n} <https://github.com/rust-lang/rust/issues/{$n}> for more information
 
@@ -16,10 +16,11 @@
 
 use externs::{ExternOpt, split_extern_opt};
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
-use rustc_data_structures::stable_hasher::{StableOrd, ToStableHashKey};
+use rustc_data_structures::stable_hasher::{StableHasher, StableOrd, ToStableHashKey};
 use rustc_errors::emitter::HumanReadableErrorType;
 use rustc_errors::{ColorConfig, DiagArgValue, DiagCtxtFlags, IntoDiagArg};
 use rustc_feature::UnstableFeatures;
+use rustc_hashes::Hash64;
 use rustc_macros::{Decodable, Encodable, HashStable_Generic};
 use rustc_span::edition::{DEFAULT_EDITION, EDITION_NAME_LIST, Edition, LATEST_STABLE_EDITION};
 use rustc_span::source_map::FilePathMapping;
@@ -554,6 +555,29 @@ pub fn enabled(&self) -> bool {
     }
 }
 
+/// A file passed to an option whose contents determine the output, such as the
+/// profile of `-Z cgu-profile`. The contents are hashed when the options are
+/// parsed, so that editing the file invalidates the incremental cache even if
+/// its path stays the same.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct TrackedFile {
+    pub path: PathBuf,
+    /// The hash of the contents, or `None` if the file could not be read, in
+    /// which case reading it again reports the error.
+    contents_hash: Option<Hash64>,
+}
+
+impl TrackedFile {
+    pub fn new(path: PathBuf) -> TrackedFile {
+        let contents_hash = fs::read(&path).ok().map(|contents| {
+            let mut hasher = StableHasher::new();
+            contents.hash(&mut hasher);
+            hasher.finish()
+        });
+        TrackedFile { path, contents_hash }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, HashStable_Generic)]
 #[derive(Encodable, Decodable)]
 pub enum SymbolManglingVersion {
@@ -844,6 +868,8 @@ pub enum ErrorOutputType {
         json_rendered: HumanReadableErrorType,
         color_config: ColorConfig,
     },
+    /// A single SARIF 2.1.0 log, consumed by code-scanning services.
+    Sarif,
 }
 
 #[derive(Clone, Hash, Debug)]
@@ -1468,6 +1494,36 @@ pub fn file_path_mapping(&self) -> FilePathMapping {
         file_path_mapping(self.remap_path_prefix.clone(), &self.unstable_opts)
     }
 
+    /// Like `dep_tracking_hash(false)`, but independent of the location of the
+    /// sources, so that checkouts of a crate in different directories can share
+    /// their incremental compilation results.
+    ///
+    /// The working directory is left out unless debuginfo is enabled, as it is
+    /// then embedded in the output. Even then, if it is remapped, only the name
+    /// it is remapped to is hashed, as with `dep_tracking_hash`. The prefixes
+    /// that `--remap-path-prefix` maps from are left out entirely, as they are
+    /// usually the location of the checkout itself, while the prefixes it maps
+    /// to are hashed.
+    pub fn path_independent_dep_tracking_hash(&self) -> Hash64 {
+        let mut opts = self.clone();
+        match &mut opts.working_dir {
+            RealFileName::LocalPath(working_dir) if opts.debuginfo == DebugInfo::None => {
+                *working_dir = PathBuf::new();
+            }
+            RealFileName::LocalPath(_) => {}
+            RealFileName::Remapped { local_path, virtual_name } => {
+                *local_path = None;
+                if opts.debuginfo == DebugInfo::None {
+                    *virtual_name = PathBuf::new();
+                }
+            }
+        }
+        for (from, _) in &mut opts.remap_path_prefix {
+            *from = PathBuf::new();
+        }
+        opts.dep_tracking_hash(false)
+    }
+
     /// Returns `true` if there will be an output file generated.
     pub fn will_create_output_file(&self) -> bool {
         !self.unstable_opts.parse_crate_root_only && // The file is just being parsed
@@ -2075,11 +2131,12 @@ pub fn parse_error_format(
                 kind: HumanReadableErrorType::Unicode,
                 color_config,
             },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some(arg) => {
                 early_dcx.set_error_format(ErrorOutputType::HumanReadable { color_config, .. });
                 early_dcx.early_fatal(format!(
                     "argument for `--error-format` must be `human`, `human-annotate-rs`, \
-                    `human-unicode`, `json`, `pretty-json` or `short` (instead was `{arg}`)"
+                    `human-unicode`, `json`, `pretty-json`, `sarif` or `short` (instead was `{arg}`)"
                 ))
             }
         }
@@ -2139,6 +2196,7 @@ fn check_error_format_stability(
     }
     let format = match format {
         ErrorOutputType::Json { pretty: true, .. } => "pretty-json",
+        ErrorOutputType::Sarif => "sarif",
         ErrorOutputType::HumanReadable { kind, .. } => match kind {
             HumanReadableErrorType::AnnotateSnippet => "human-annotate-rs",
             HumanReadableErrorType::Unicode => "human-unicode",
@@ -3187,7 +3245,7 @@ pub(crate) mod dep_tracking {
         LtoCli, MirStripDebugInfo, NextSolverConfig, Offload, OomStrategy, OptLevel, OutFileName,
         OutputType, OutputTypes, PatchableFunctionEntry, Polonius, RemapPathScopeComponents,
         ResolveDocLinks, SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath,
-        SymbolManglingVersion, WasiExecModel,
+        SymbolManglingVersion, TrackedFile, WasiExecModel,
     };
     use crate::lint;
     use crate::utils::NativeLib;
@@ -3275,6 +3333,7 @@ fn hash(
         SwitchWithOptPath,
         SymbolManglingVersion,
         SymbolVisibility,
+        TrackedFile,
         RemapPathScopeComponents,
         SourceFileHashAlgorithm,
         OutFileName,
@@ -3464,6 +3523,24 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// Which format to use for `-Z size-report`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SizeReportFormat {
+    /// Human-readable tables
+    Text,
+    /// Emit structured JSON
+    Json,
+}
+
+impl SizeReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "json",
+        }
+    }
+}
+
 /// `-Z patchable-function-entry` representation - how many nops to put before and after function
 /// entry.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
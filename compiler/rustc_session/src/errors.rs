@@ -1,4 +1,6 @@
+use std::io;
 use std::num::{NonZero, ParseIntError};
+use std::path::PathBuf;
 
 use rustc_ast::token;
 use rustc_ast::util::literal::LitError;
@@ -491,6 +493,13 @@ pub(crate) struct FailedToCreateProfiler {
     pub(crate) err: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_failed_to_write_profile)]
+pub(crate) struct FailedToWriteProfile {
+    pub(crate) path: PathBuf,
+    pub(crate) err: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_soft_float_ignored)]
 #[note]
@@ -5,7 +5,7 @@
 
 use rustc_abi::Align;
 use rustc_data_structures::fx::FxIndexMap;
-use rustc_data_structures::profiling::TimePassesFormat;
+use rustc_data_structures::profiling::{SelfProfileFormat, TimePassesFormat};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_errors::{ColorConfig, LanguageIdentifier, TerminalUrl};
 use rustc_feature::UnstableFeatures;
@@ -722,6 +722,7 @@ mod desc {
     pub(crate) const parse_string_push: &str = parse_string;
     pub(crate) const parse_opt_langid: &str = "a language identifier";
     pub(crate) const parse_opt_pathbuf: &str = "a path";
+    pub(crate) const parse_opt_tracked_file: &str = parse_opt_pathbuf;
     pub(crate) const parse_list: &str = "a space-separated list of strings";
     pub(crate) const parse_list_with_polarity: &str =
         "a comma-separated list of strings, with elements beginning with + or -";
@@ -734,6 +735,7 @@ mod desc {
     pub(crate) const parse_frame_pointer: &str = "one of `true`/`yes`/`on`, `false`/`no`/`off`, or (with -Zunstable-options) `non-leaf` or `always`";
     pub(crate) const parse_threads: &str = parse_number;
     pub(crate) const parse_time_passes_format: &str = "`text` (default) or `json`";
+    pub(crate) const parse_self_profile_format: &str = "`measureme` (default) or `chrome`";
     pub(crate) const parse_passes: &str = "a space-separated list of passes, or `all`";
     pub(crate) const parse_panic_strategy: &str = "either `unwind` or `abort`";
     pub(crate) const parse_on_broken_pipe: &str = "either `kill`, `error`, or `inherit`";
@@ -754,6 +756,7 @@ mod desc {
     pub(crate) const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub(crate) const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub(crate) const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub(crate) const parse_size_report: &str = "`text` (default) or `json`";
     pub(crate) const parse_instrument_coverage: &str = parse_bool;
     pub(crate) const parse_coverage_options: &str = "`block` | `branch` | `condition`";
     pub(crate) const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
@@ -926,6 +929,16 @@ pub(crate) fn parse_opt_pathbuf(slot: &mut Option<PathBuf>, v: Option<&str>) ->
         }
     }
 
+    pub(crate) fn parse_opt_tracked_file(slot: &mut Option<TrackedFile>, v: Option<&str>) -> bool {
+        match v {
+            Some(s) => {
+                *slot = Some(TrackedFile::new(PathBuf::from(s)));
+                true
+            }
+            None => false,
+        }
+    }
+
     pub(crate) fn parse_string_push(slot: &mut Vec<String>, v: Option<&str>) -> bool {
         match v {
             Some(s) => {
@@ -1342,6 +1355,20 @@ pub(crate) fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_self_profile_format(slot: &mut SelfProfileFormat, v: Option<&str>) -> bool {
+        match v {
+            Some("measureme") => {
+                *slot = SelfProfileFormat::Measureme;
+                true
+            }
+            Some("chrome") => {
+                *slot = SelfProfileFormat::Chrome;
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&str>) -> bool {
         match v {
             None => true,
@@ -1357,6 +1384,20 @@ pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_size_report(slot: &mut Option<SizeReportFormat>, v: Option<&str>) -> bool {
+        match v {
+            None | Some("text") => {
+                *slot = Some(SizeReportFormat::Text);
+                true
+            }
+            Some("json") => {
+                *slot = Some(SizeReportFormat::Json);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
     pub(crate) fn parse_offload(slot: &mut Vec<Offload>, v: Option<&str>) -> bool {
         let Some(v) = v else {
             *slot = vec![];
@@ -2159,6 +2200,12 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "whether the stable interface is being built"),
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
+    cgu_profile: Option<TrackedFile> = (None, parse_opt_tracked_file, [TRACKED],
+        "co-locate hot callers and callees in the same codegen unit, using the call-frequency \
+        profile in the given file (in the folded stacks format)"),
+    cgu_profile_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a report of the codegen unit partitioning and of the effect of `-Z cgu-profile` \
+        to the given file"),
     check_cfg_all_expected: bool = (false, parse_bool, [UNTRACKED],
         "show all expected values in check-cfg diagnostics (default: no)"),
     checksum_hash_algorithm: Option<SourceFileHashAlgorithm> = (None, parse_cargo_src_file_hash, [TRACKED],
@@ -2240,6 +2287,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "enforce the type length limit when monomorphizing instances in codegen"),
     experimental_default_bounds: bool = (false, parse_bool, [TRACKED],
         "enable default bounds for experimental group of auto traits"),
+    explain_inference: bool = (false, parse_bool, [UNTRACKED],
+        "explain how type inference arrived at the types of \"type annotations needed\" and \
+        mismatched types errors (default: no)"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
     external_clangrt: bool = (false, parse_bool, [UNTRACKED],
@@ -2288,6 +2338,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_shared_cache: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "publish incremental compilation sessions to a content-addressed store in the given \
+        directory, and start new sessions of other checkouts and machines from them"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify extended properties for incr. comp. (default: no):
         - hashes of green query instances
@@ -2320,6 +2373,8 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
          `=skip-entry`
          `=skip-exit`
          Multiple options can be combined with commas."),
+    json_call_graph: bool = (false, parse_bool, [UNTRACKED],
+        "record the items referenced by each function body in rustdoc JSON output (default: no)"),
     layout_seed: Option<u64> = (None, parse_opt_number, [TRACKED],
         "seed layout randomization"),
     link_directives: bool = (true, parse_bool, [TRACKED],
@@ -2375,6 +2430,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mir_outline_cold_paths: bool = (false, parse_bool, [TRACKED],
+        "move paths which always end in a panic or another diverging call into separate cold \
+        functions (default: no)"),
     mir_preserve_ub: bool = (false, parse_bool, [TRACKED],
         "keep place mention statements and reads in trivial SwitchInt terminators, which are interpreted \
         e.g., by miri; implies -Zmir-opt-level=0 (default: no)"),
@@ -2388,6 +2446,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "allow crates to be namespaced by other crates (default: no)"),
     next_solver: NextSolverConfig = (NextSolverConfig::default(), parse_next_solver_config, [TRACKED],
         "enable and configure the next generation trait solver used by rustc"),
+    next_solver_dump: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the proof trees of unfulfilled obligations as JSON files into this directory \
+        (only with `-Znext-solver`)"),
     nll_facts: bool = (false, parse_bool, [UNTRACKED],
         "dump facts from NLL analysis into side files (default: no)"),
     nll_facts_dir: String = ("nll-facts".to_string(), parse_string, [UNTRACKED],
@@ -2534,6 +2595,10 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_format: SelfProfileFormat = (SelfProfileFormat::Measureme,
+        parse_self_profile_format, [UNTRACKED],
+        "the format to use for -Z self-profile: `measureme` (default) writes the raw event data, \
+        `chrome` additionally writes the events as Chrome trace-event JSON"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     shell_argfiles: bool = (false, parse_bool, [UNTRACKED],
@@ -2541,6 +2606,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "simulate the effect of remap-debuginfo = true at bootstrapping by remapping path \
         to rust's source base directory. only meant for testing purposes"),
+    size_report: Option<SizeReportFormat> = (None, parse_size_report, [UNTRACKED],
+        "write a report attributing the size of the linked binary to symbols, crates and \
+        generic functions next to it (`text` (default) or `json`)"),
     small_data_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
         "Set the threshold for objects to be stored in a \"small data\" section"),
     span_debug: bool = (false, parse_bool, [UNTRACKED],
@@ -637,6 +637,11 @@ pub fn walk_chain_collapsed(span: Span, to: Span) -> Span {
     HygieneData::with(|data| data.walk_chain_collapsed(span, to))
 }
 
+/// Returns the hashes of all expansions of the local crate, indexed by their `LocalExpnId`.
+pub fn local_expn_hashes() -> IndexVec<LocalExpnId, ExpnHash> {
+    HygieneData::with(|data| data.local_expn_hashes.clone())
+}
+
 pub fn update_dollar_crate_names(mut get_name: impl FnMut(SyntaxContext) -> Symbol) {
     // The new contexts that need updating are at the end of the list and have `$crate` as a name.
     let mut to_update = vec![];
@@ -1099,6 +1099,7 @@
         generators,
         generic_arg_infer,
         generic_assert,
+        generic_assert_sub_expressions,
         generic_associated_types,
         generic_associated_types_extended,
         generic_const_exprs,
@@ -83,6 +83,9 @@ pub(super) fn mangle<'tcx>(
         ty::InstanceKind::ThreadLocalShim(..) => {
             p.write_str("{{tls-shim}}").unwrap();
         }
+        ty::InstanceKind::OutlinedColdPath(_, index) => {
+            p.write_str(&format!("{{{{cold-path-{index}}}}}")).unwrap();
+        }
         ty::InstanceKind::VTableShim(..) => {
             p.write_str("{{vtable-shim}}").unwrap();
         }
@@ -70,6 +70,8 @@ pub(super) fn mangle<'tcx>(
         };
         let drop_ty = cor_args.first().unwrap().expect_ty();
         p.print_def_path(def_id, tcx.mk_args(&[GenericArg::from(drop_ty)])).unwrap()
+    } else if let ty::InstanceKind::OutlinedColdPath(_, index) = instance.def {
+        p.path_append_ns(|p| p.print_def_path(def_id, args), 'S', index as u64, "cold").unwrap()
     } else if let Some(shim_kind) = shim_kind {
         p.path_append_ns(|p| p.print_def_path(def_id, args), 'S', 0, shim_kind).unwrap()
     } else {
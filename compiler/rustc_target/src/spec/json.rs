@@ -16,11 +16,15 @@
 
 impl Target {
     /// Loads a target descriptor from a JSON object.
+    ///
+    /// If the object has an `inherits` field naming a built-in target, its other fields override
+    /// the ones of that target.
     pub fn from_json(json: &str) -> Result<(Target, TargetWarnings), String> {
-        let json_deserializer = &mut serde_json::Deserializer::from_str(json);
-
-        let json: TargetSpecJson =
-            serde_path_to_error::deserialize(json_deserializer).map_err(|err| err.to_string())?;
+        let json: TargetSpecJson = match inherit_builtin(json)? {
+            Some(merged) => serde_path_to_error::deserialize(merged),
+            None => serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(json)),
+        }
+        .map_err(|err| err.to_string())?;
 
         let mut base = Target {
             llvm_target: json.llvm_target,
@@ -238,6 +242,37 @@ macro_rules! forward_opt {
     }
 }
 
+/// Merges a target specification with an `inherits` field into the specification of the built-in
+/// target it names. Returns `None` for specifications without that field, and for invalid JSON,
+/// which is reported when the specification is deserialized.
+fn inherit_builtin(json: &str) -> Result<Option<Json>, String> {
+    let Ok(Json::Object(mut overrides)) = serde_json::from_str(json) else {
+        return Ok(None);
+    };
+    let Some(inherits) = overrides.remove("inherits") else {
+        return Ok(None);
+    };
+    let Json::String(name) = inherits else {
+        return Err("`inherits` must be the name of a built-in target".to_string());
+    };
+    let Some(base) = super::load_builtin(&name) else {
+        return Err(format!("`inherits` names unknown built-in target `{name}`"));
+    };
+    let Json::Object(mut merged) = base.to_json() else {
+        unreachable!("targets are serialized as objects");
+    };
+    // `-Clink-self-contained` is serialized with one of two keys, so overriding it with one of
+    // them must remove the other.
+    if overrides.contains_key("crt-objects-fallback")
+        || overrides.contains_key("link-self-contained")
+    {
+        merged.remove("crt-objects-fallback");
+        merged.remove("link-self-contained");
+    }
+    merged.extend(overrides);
+    Ok(Some(Json::Object(merged)))
+}
+
 impl ToJson for Target {
     fn to_json(&self) -> Json {
         let mut d = serde_json::Map::new();
@@ -1,4 +1,4 @@
-use crate::spec::Target;
+use crate::spec::{Target, TargetTuple};
 
 #[test]
 fn report_unused_fields() {
@@ -15,3 +15,43 @@ fn report_unused_fields() {
     eprintln!("{result:#?}");
     assert!(result.is_err());
 }
+
+#[test]
+fn inherit_builtin_target() {
+    let json = r#"
+    {
+        "inherits": "thumbv7em-none-eabihf",
+        "cpu": "cortex-m4",
+        "max-atomic-width": 16
+    }
+    "#;
+    let (target, _) = Target::from_json(json).unwrap();
+    let base = Target::expect_builtin(&TargetTuple::from_tuple("thumbv7em-none-eabihf"));
+    assert_eq!(target.llvm_target, base.llvm_target);
+    assert_eq!(target.data_layout, base.data_layout);
+    assert_eq!(target.features, base.features);
+    assert_eq!(target.cpu, "cortex-m4");
+    assert_eq!(target.max_atomic_width, Some(16));
+}
+
+#[test]
+fn inherit_unknown_target() {
+    let json = r#"
+    {
+        "inherits": "thumbv7em-none-unknown",
+        "cpu": "cortex-m4"
+    }
+    "#;
+    assert!(Target::from_json(json).is_err());
+}
+
+#[test]
+fn inherit_and_report_unused_fields() {
+    let json = r#"
+    {
+        "inherits": "thumbv7em-none-eabihf",
+        "code-mode": "foo"
+    }
+    "#;
+    assert!(Target::from_json(json).is_err());
+}
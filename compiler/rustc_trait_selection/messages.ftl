@@ -231,6 +231,8 @@ trait_selection_negative_positive_conflict = found both positive and negative im
     .positive_implementation_here = positive implementation here
     .positive_implementation_in_crate = positive implementation in crate `{$positive_impl_cname}`
 
+trait_selection_next_solver_dump_failed = failed to write proof tree to `{$path}`: {$error}
+
 trait_selection_nothing = {""}
 
 trait_selection_oc_cant_coerce_force_inline =
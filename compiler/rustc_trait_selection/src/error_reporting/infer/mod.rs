@@ -58,12 +58,13 @@
 };
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
-use rustc_hir::intravisit::Visitor;
+use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::lang_items::LangItem;
 use rustc_hir::{self as hir};
 use rustc_macros::extension;
 use rustc_middle::bug;
 use rustc_middle::dep_graph::DepContext;
+use rustc_middle::hir::nested_filter;
 use rustc_middle::traits::PatternOriginExpr;
 use rustc_middle::ty::error::{ExpectedFound, TypeError, TypeErrorToStringExt};
 use rustc_middle::ty::print::{
@@ -74,7 +75,7 @@
     TypeVisitableExt,
 };
 use rustc_span::def_id::LOCAL_CRATE;
-use rustc_span::{BytePos, DUMMY_SP, DesugaringKind, Pos, Span, Symbol, sym};
+use rustc_span::{BytePos, DUMMY_SP, DesugaringKind, ExpnKind, MacroKind, Pos, Span, Symbol, sym};
 use tracing::{debug, instrument};
 
 use crate::error_reporting::TypeErrCtxt;
@@ -2104,9 +2105,121 @@ pub fn report_and_explain_type_error(
             false,
             None,
         );
+        if let Some((expected, found)) = trace.values.ty() {
+            self.note_inference_history(&mut diag, &[expected, found]);
+        }
         diag
     }
 
+    /// `-Zexplain-inference`: adds a note for each step of type inference which determined the
+    /// type variables in `tys`, in the order in which they happened.
+    pub fn note_inference_history(&self, diag: &mut Diag<'_>, tys: &[Ty<'tcx>]) {
+        for step in self.infcx.inference_history(tys) {
+            let var = self.describe_ty_var(step.vid);
+            let msg = match *step.value.kind() {
+                ty::Infer(ty::TyVar(other)) => {
+                    format!("{var} unified with {} here", self.describe_ty_var(other))
+                }
+                _ => format!("{var} inferred as `{}` here", step.value),
+            };
+            if step.span.is_dummy() {
+                diag.note(msg);
+            } else {
+                diag.span_note(step.span, msg);
+            }
+        }
+    }
+
+    fn describe_ty_var(&self, vid: ty::TyVid) -> String {
+        let origin = self.infcx.type_var_origin(vid);
+        if let Some(def_id) = origin.param_def_id {
+            return format!(
+                "type parameter `{}` of `{}`",
+                self.tcx.item_name(def_id),
+                self.tcx.def_path_str(self.tcx.parent(def_id)),
+            );
+        }
+        // Find what the variable was created for in the body being checked, to describe it by
+        // the name of the binding or the kind of expression.
+        struct OriginVisitor<'tcx> {
+            tcx: TyCtxt<'tcx>,
+            span: Span,
+        }
+        impl<'tcx> Visitor<'tcx> for OriginVisitor<'tcx> {
+            type NestedFilter = nested_filter::OnlyBodies;
+            type Result = ControlFlow<hir::Node<'tcx>>;
+
+            fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+                self.tcx
+            }
+
+            fn visit_local(&mut self, local: &'tcx hir::LetStmt<'tcx>) -> Self::Result {
+                if local.span == self.span {
+                    return ControlFlow::Break(hir::Node::LetStmt(local));
+                }
+                intravisit::walk_local(self, local)
+            }
+
+            fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) -> Self::Result {
+                if pat.span == self.span {
+                    return ControlFlow::Break(hir::Node::Pat(pat));
+                }
+                intravisit::walk_pat(self, pat)
+            }
+
+            fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) -> Self::Result {
+                if expr.span == self.span {
+                    return ControlFlow::Break(hir::Node::Expr(expr));
+                }
+                intravisit::walk_expr(self, expr)
+            }
+        }
+        let node = self
+            .typeck_results
+            .as_ref()
+            .and_then(|typeck_results| {
+                self.tcx.hir_maybe_body_owned_by(typeck_results.hir_owner.def_id)
+            })
+            .and_then(|body| {
+                OriginVisitor { tcx: self.tcx, span: origin.span }.visit_body(body).break_value()
+            });
+        match node {
+            Some(hir::Node::LetStmt(local)) => match local.pat.kind {
+                hir::PatKind::Binding(_, _, ident, None) => {
+                    format!("the declared type of `{ident}`")
+                }
+                _ => "the declared type of this `let`".to_string(),
+            },
+            Some(hir::Node::Pat(pat)) => match pat.kind {
+                hir::PatKind::Binding(_, _, ident, None) => format!("the type of `{ident}`"),
+                _ => "the type of this pattern".to_string(),
+            },
+            Some(hir::Node::Expr(expr)) => Self::describe_expr_kind(expr),
+            _ => "an inferred type".to_string(),
+        }
+    }
+
+    fn describe_expr_kind(expr: &hir::Expr<'_>) -> String {
+        if let ExpnKind::Macro(MacroKind::Bang, name) = expr.span.ctxt().outer_expn_data().kind {
+            return format!("the type of this `{name}!` invocation");
+        }
+        match expr.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(_, path)) => {
+                format!("the type of `{}`", path.segments.last().unwrap().ident)
+            }
+            hir::ExprKind::Call(..) => "the type of this call".to_string(),
+            hir::ExprKind::MethodCall(segment, ..) => {
+                format!("the type of this call to `{}`", segment.ident)
+            }
+            hir::ExprKind::Closure(..) => "the type of this closure".to_string(),
+            hir::ExprKind::Lit(..) => "the type of this literal".to_string(),
+            hir::ExprKind::Block(..) => "the type of this block".to_string(),
+            hir::ExprKind::If(..) => "the type of this `if` expression".to_string(),
+            hir::ExprKind::Match(..) => "the type of this `match` expression".to_string(),
+            _ => "the type of this expression".to_string(),
+        }
+    }
+
     fn suggest_wrap_to_build_a_tuple(
         &self,
         span: Span,
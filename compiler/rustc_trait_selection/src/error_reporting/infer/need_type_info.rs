@@ -474,6 +474,27 @@ pub fn emit_inference_failure_err(
         should_label_span: bool,
     ) -> Diag<'a> {
         let term = self.resolve_vars_if_possible(term);
+        let mut err = self.inference_failure_err(
+            body_def_id,
+            failure_span,
+            term,
+            error_code,
+            should_label_span,
+        );
+        if let Some(ty) = term.as_type() {
+            self.note_inference_history(&mut err, &[ty]);
+        }
+        err
+    }
+
+    fn inference_failure_err(
+        &self,
+        body_def_id: LocalDefId,
+        failure_span: Span,
+        term: Term<'tcx>,
+        error_code: TypeAnnotationNeeded,
+        should_label_span: bool,
+    ) -> Diag<'a> {
         let arg_data = self
             .extract_inference_diagnostics_data(term, ty::print::RegionHighlightMode::default());
 
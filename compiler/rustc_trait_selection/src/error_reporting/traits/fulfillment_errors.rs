@@ -2121,6 +2121,9 @@ pub(super) fn report_similar_impl_candidates(
             if candidates.is_empty() {
                 return false;
             }
+            for candidate in &mut candidates {
+                *candidate = self.with_default_string_allocator(*candidate);
+            }
             if let &[cand] = &candidates[..] {
                 if self.tcx.is_diagnostic_item(sym::FromResidual, cand.def_id)
                     && !self.tcx.features().enabled(sym::try_trait_v2)
@@ -2225,6 +2228,7 @@ pub(super) fn report_similar_impl_candidates(
                         cand.trait_ref,
                     )
                     .unwrap_or(cand.trait_ref);
+                cand.trait_ref = self.with_default_string_allocator(cand.trait_ref);
                 cand
             })
             .collect();
@@ -2236,6 +2240,36 @@ pub(super) fn report_similar_impl_candidates(
         report(impl_candidates, err)
     }
 
+    /// `String` is generic over its allocator, but is nearly always used with the default one,
+    /// so impls for `String<A>` are shown as impls for `String`, with `A` replaced by the default
+    /// allocator everywhere in the trait ref.
+    fn with_default_string_allocator(&self, trait_ref: TraitRef<'tcx>) -> TraitRef<'tcx> {
+        let mut defaults = FxHashMap::default();
+        for arg in trait_ref.args.iter().flat_map(|arg| arg.walk()) {
+            if let ty::GenericArgKind::Type(ty) = arg.kind()
+                && let ty::Adt(def, args) = ty.kind()
+                && self.tcx.is_lang_item(def.did(), LangItem::String)
+                && let ty::Param(param) = args.type_at(0).kind()
+                && let Some(default) =
+                    self.tcx.generics_of(def.did()).own_params[0].default_value(self.tcx)
+            {
+                defaults.insert(*param, default.instantiate_identity().expect_ty());
+            }
+        }
+        if defaults.is_empty() {
+            return trait_ref;
+        }
+        trait_ref.fold_with(&mut ty::BottomUpFolder {
+            tcx: self.tcx,
+            ty_op: |ty| match ty.kind() {
+                ty::Param(param) => defaults.get(param).copied().unwrap_or(ty),
+                _ => ty,
+            },
+            lt_op: |lt| lt,
+            ct_op: |ct| ct,
+        })
+    }
+
     fn report_similar_impl_candidates_for_root_obligation(
         &self,
         obligation: &PredicateObligation<'tcx>,
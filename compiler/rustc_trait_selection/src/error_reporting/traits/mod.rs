@@ -141,6 +141,14 @@ pub fn report_fulfillment_errors(
         &self,
         mut errors: Vec<FulfillmentError<'tcx>>,
     ) -> ErrorGuaranteed {
+        if self.next_trait_solver()
+            && let Some(dir) = &self.tcx.sess.opts.unstable_opts.next_solver_dump
+        {
+            for error in &errors {
+                crate::solve::inspect::dump_proof_tree(self, error, dir);
+            }
+        }
+
         self.sub_relations
             .borrow_mut()
             .add_constraints(self, errors.iter().map(|e| e.obligation.predicate));
@@ -23,6 +23,13 @@
 
 pub mod note_and_explain;
 
+#[derive(Diagnostic)]
+#[diag(trait_selection_next_solver_dump_failed)]
+pub struct NextSolverDumpFailed {
+    pub path: PathBuf,
+    pub error: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(trait_selection_unable_to_construct_constant_value)]
 pub struct UnableToConstructConstantValue<'a> {
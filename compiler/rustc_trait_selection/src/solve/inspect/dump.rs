@@ -0,0 +1,178 @@
+//! `-Znext-solver-dump`: writes the proof trees of unfulfilled obligations as JSON.
+//!
+//! Each reported fulfillment error gets its own file in the dump directory, containing
+//! the full proof tree of its root obligation: every goal with its candidates, the
+//! nested goals of each candidate and, for candidates which did not hold, why not.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rustc_infer::infer::InferCtxt;
+use rustc_infer::traits::solve::{CandidateSource, MaybeCause};
+use rustc_middle::traits::query::NoSolution;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+use rustc_span::def_id::LOCAL_CRATE;
+use serde_json::{Value, json};
+
+use crate::errors::NextSolverDumpFailed;
+use crate::solve::Certainty;
+use crate::solve::inspect::{
+    InspectCandidate, InspectConfig, InspectGoal, ProbeKind, ProofTreeInferCtxtExt,
+    ProofTreeVisitor,
+};
+use crate::traits::{FulfillmentError, FulfillmentErrorCode};
+
+/// Used to give each dumped proof tree of this process a unique file name.
+static DUMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the proof tree of the root obligation of `error` to a new file in `dir`.
+pub(crate) fn dump_proof_tree<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    error: &FulfillmentError<'tcx>,
+    dir: &Path,
+) {
+    let tcx = infcx.tcx;
+    let obligation = infcx.resolve_vars_if_possible(error.root_obligation.clone());
+    let mut visitor = DumpVisitor { span: obligation.cause.span, goals: vec![] };
+    infcx.probe(|_| infcx.visit_proof_tree(obligation.as_goal(), &mut visitor));
+
+    let kind = match error.code {
+        FulfillmentErrorCode::Ambiguity { .. } => "ambiguous",
+        _ => "unsatisfied",
+    };
+    let dump = json!({
+        "obligation": obligation.predicate.to_string(),
+        "span": describe_span(tcx, obligation.cause.span),
+        "kind": kind,
+        "proof_tree": visitor.goals.pop().unwrap_or(Value::Null),
+    });
+
+    let index = DUMP_COUNT.fetch_add(1, Ordering::Relaxed);
+    let path = dir.join(format!("{}-{index}.json", tcx.crate_name(LOCAL_CRATE)));
+    let result = fs::create_dir_all(dir).and_then(|()| {
+        fs::write(&path, serde_json::to_string_pretty(&dump).expect("proof tree is valid JSON"))
+    });
+    if let Err(error) = result {
+        tcx.dcx().emit_warn(NextSolverDumpFailed { path, error });
+    }
+}
+
+struct DumpVisitor {
+    span: Span,
+    /// The rendered goals at the current level of the tree.
+    goals: Vec<Value>,
+}
+
+impl<'tcx> ProofTreeVisitor<'tcx> for DumpVisitor {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn config(&self) -> InspectConfig {
+        InspectConfig { max_depth: 64 }
+    }
+
+    fn visit_goal(&mut self, goal: &InspectGoal<'_, 'tcx>) {
+        let tcx = goal.infcx().tcx;
+        let candidates: Vec<_> = goal
+            .candidates()
+            .iter()
+            .map(|candidate| {
+                let (nested_goals, rejected_because) = goal.infcx().probe(|_| {
+                    let parent_goals = std::mem::take(&mut self.goals);
+                    let mut rejected_because = None;
+                    for nested in candidate.instantiate_nested_goals(self.span) {
+                        if rejected_because.is_none() {
+                            rejected_because = rejection_reason(candidate, &nested);
+                        }
+                        nested.visit_with(self);
+                    }
+                    (std::mem::replace(&mut self.goals, parent_goals), rejected_because)
+                });
+                let rejected_because = rejected_because.or_else(|| match candidate.result() {
+                    Ok(Certainty::Yes) => None,
+                    Ok(Certainty::Maybe(MaybeCause::Ambiguity)) => {
+                        Some("the candidate is ambiguous".to_string())
+                    }
+                    Ok(Certainty::Maybe(MaybeCause::Overflow { .. })) => {
+                        Some("the candidate overflowed".to_string())
+                    }
+                    Err(NoSolution) => Some("the candidate does not apply to the goal".to_string()),
+                });
+
+                let mut rendered = json!({
+                    "kind": describe_candidate(tcx, candidate),
+                    "result": describe_result(candidate.result()),
+                    "nested_goals": nested_goals,
+                });
+                if let ProbeKind::TraitCandidate { source: CandidateSource::Impl(def_id), .. } =
+                    candidate.kind()
+                {
+                    rendered["span"] = describe_span(tcx, tcx.def_span(def_id)).into();
+                }
+                if let Some(rejected_because) = rejected_because {
+                    rendered["rejected_because"] = rejected_because.into();
+                }
+                rendered
+            })
+            .collect();
+
+        self.goals.push(json!({
+            "goal": goal.goal().predicate.to_string(),
+            "source": format!("{:?}", goal.source()),
+            "result": describe_result(goal.result()),
+            "candidates": candidates,
+        }));
+    }
+}
+
+/// Why `candidate` does not hold, if that is because of its nested goal `nested`.
+fn rejection_reason<'tcx>(
+    candidate: &InspectCandidate<'_, 'tcx>,
+    nested: &InspectGoal<'_, 'tcx>,
+) -> Option<String> {
+    let predicate = nested.goal().predicate;
+    match (candidate.result(), nested.result()) {
+        (Err(NoSolution), Err(NoSolution)) => {
+            Some(format!("the nested goal `{predicate}` does not hold"))
+        }
+        (Ok(Certainty::Maybe(_)), Ok(Certainty::Maybe(_))) => {
+            Some(format!("the nested goal `{predicate}` is ambiguous"))
+        }
+        _ => None,
+    }
+}
+
+fn describe_candidate<'tcx>(tcx: TyCtxt<'tcx>, candidate: &InspectCandidate<'_, 'tcx>) -> String {
+    match candidate.kind() {
+        ProbeKind::Root { .. } => "root".to_string(),
+        ProbeKind::TraitCandidate { source, .. } => match source {
+            CandidateSource::Impl(def_id) => format!("impl `{}`", tcx.def_path_str(def_id)),
+            CandidateSource::BuiltinImpl(source) => format!("builtin impl ({source:?})"),
+            CandidateSource::ParamEnv(_) => "where-clause".to_string(),
+            CandidateSource::AliasBound => "alias bound".to_string(),
+            CandidateSource::CoherenceUnknowable => "coherence unknowable".to_string(),
+        },
+        ProbeKind::OpaqueTypeStorageLookup { .. } => "opaque type storage lookup".to_string(),
+        ProbeKind::RigidAlias { .. } => "rigid alias".to_string(),
+        kind @ (ProbeKind::NormalizedSelfTyAssembly
+        | ProbeKind::UnsizeAssembly
+        | ProbeKind::ProjectionCompatibility
+        | ProbeKind::ShadowedEnvProbing) => format!("{kind:?}"),
+    }
+}
+
+fn describe_result(result: Result<Certainty, NoSolution>) -> &'static str {
+    match result {
+        Ok(Certainty::Yes) => "yes",
+        Ok(Certainty::Maybe(MaybeCause::Ambiguity)) => "ambiguous",
+        Ok(Certainty::Maybe(MaybeCause::Overflow { .. })) => "overflow",
+        Err(NoSolution) => "no solution",
+    }
+}
+
+fn describe_span(tcx: TyCtxt<'_>, span: Span) -> String {
+    tcx.sess.source_map().span_to_diagnostic_string(span)
+}
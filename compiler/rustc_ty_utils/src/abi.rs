@@ -43,6 +43,18 @@ fn fn_sig_for_fn_abi<'tcx>(
         );
     }
 
+    if let InstanceKind::OutlinedColdPath(..) = instance.def {
+        // The outlined body takes the locals it uses as arguments, and never returns.
+        let body = tcx.instance_mir(instance.def);
+        return tcx.mk_fn_sig(
+            body.args_iter().map(|local| body.local_decls[local].ty),
+            body.return_ty(),
+            false,
+            hir::Safety::Safe,
+            rustc_abi::ExternAbi::Rust,
+        );
+    }
+
     let ty = instance.ty(tcx, typing_env);
     match *ty.kind() {
         ty::FnDef(def_id, args) => {
@@ -256,6 +256,7 @@ impl<T> Box<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     #[must_use]
     #[rustc_diagnostic_item = "box_new"]
+    #[lang = "box_new"]
     #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
     pub fn new(x: T) -> Self {
         return box_new(x);
@@ -1803,10 +1804,10 @@ fn clone_from(&mut self, source: &Self) {
 
 #[cfg(not(no_global_oom_handling))]
 #[stable(feature = "box_slice_clone", since = "1.3.0")]
-impl Clone for Box<str> {
+impl<A: Allocator + Clone> Clone for Box<str, A> {
     fn clone(&self) -> Self {
         // this makes a copy of the data
-        let buf: Box<[u8]> = self.as_bytes().into();
+        let buf = self.as_bytes().to_vec_in(Box::allocator(self).clone()).into_boxed_slice();
         unsafe { from_boxed_utf8_unchecked(buf) }
     }
 }
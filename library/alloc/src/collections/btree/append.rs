@@ -1,8 +1,11 @@
 use core::alloc::Allocator;
 use core::iter::FusedIterator;
 
+use super::map::MIN_LEN;
 use super::merge_iter::MergeIterInner;
-use super::node::{self, Root};
+use super::node::ForceResult::*;
+use super::node::LeftOrRight::*;
+use super::node::{self, LeftOrRight, Root};
 
 impl<K, V> Root<K, V> {
     /// Appends all key-value pairs from the union of two ascending iterators,
@@ -93,6 +96,126 @@ pub(super) fn bulk_push<I, A: Allocator + Clone>(
         }
         self.fix_right_border_of_plentiful();
     }
+
+    /// Appends all key-value pairs of `right` to the end of the tree, in time
+    /// logarithmic in the size of both trees.
+    ///
+    /// The result is meaningful only if all keys in `right` are greater than
+    /// all keys in the tree. If both trees respect all `BTreeMap` tree
+    /// invariants, then so does the resulting tree.
+    pub(super) fn append_tree<A: Allocator + Clone>(&mut self, mut right: Root<K, V>, alloc: A) {
+        if right.height() == 0 && right.len() == 0 {
+            // SAFETY: `right` is an empty leaf that nothing else refers to.
+            unsafe { right.into_dying().deallocate_and_ascend(alloc) };
+            return;
+        }
+        // The first key-value pair of `right` separates both trees.
+        let (key, value) = right.pop_first_kv(alloc.clone());
+        self.join(key, value, right, alloc);
+    }
+
+    /// Removes the first key-value pair of a non-empty tree.
+    fn pop_first_kv<A: Allocator + Clone>(&mut self, alloc: A) -> (K, V) {
+        let mut emptied_internal_root = false;
+        let first_kv = self.borrow_mut().first_leaf_edge().right_kv().ok().unwrap();
+        let (kv, _) = first_kv
+            .forget_node_type()
+            .remove_kv_tracking(|| emptied_internal_root = true, alloc.clone());
+        if emptied_internal_root {
+            self.pop_internal_level(alloc);
+        }
+        kv
+    }
+
+    /// Joins the tree, the key-value pair and the `right` tree, in that order.
+    ///
+    /// The shorter tree is hung into the border of the taller one, at the
+    /// lowest level where there is space left, with empty nodes on top of it
+    /// to make up for the difference in height, like `bulk_push` does. The
+    /// only nodes that may end up underfull are on that border, so they are
+    /// stocked up like after splitting.
+    fn join<A: Allocator + Clone>(&mut self, key: K, value: V, right: Root<K, V>, alloc: A) {
+        let (left_height, right_height) = (self.height(), right.height());
+        if left_height == right_height {
+            // Make both trees the children of a new root, and balance them.
+            let mut root = self.push_internal_level(alloc.clone());
+            root.push(key, value, right);
+            let mut kv = root.first_kv().consider_for_balancing();
+            let (left_len, right_len) = (kv.left_child_len(), kv.right_child_len());
+            if kv.can_merge() {
+                kv.merge_tracking_parent(alloc.clone());
+                self.fix_top(alloc);
+            } else if left_len < MIN_LEN {
+                kv.bulk_steal_right(MIN_LEN - left_len);
+            } else if right_len < MIN_LEN {
+                kv.bulk_steal_left(MIN_LEN - right_len);
+            }
+        } else if left_height > right_height {
+            self.push_onto_border(key, value, Right(right), alloc.clone());
+            self.fix_right_border(alloc);
+        } else {
+            let left = core::mem::replace(self, right);
+            self.push_onto_border(key, value, Left(left), alloc.clone());
+            self.fix_left_border(alloc);
+        }
+    }
+
+    /// Adds the key-value pair and `other`, a tree lower than this one, to the
+    /// border of the tree on the side of `other`, without fixing that border.
+    ///
+    /// They are added to the lowest node on the border, above the height of
+    /// `other`, that has space left, or to a new root if there is none, with
+    /// `other` raised to the right height.
+    fn push_onto_border<A: Allocator + Clone>(
+        &mut self,
+        key: K,
+        value: V,
+        other: LeftOrRight<Root<K, V>>,
+        alloc: A,
+    ) {
+        let (Left(other_tree) | Right(other_tree)) = &other;
+        let other_height = other_tree.height();
+        // Find the node on the border just above the height of `other`, and go
+        // up until there's space left.
+        let mut test_node = self.borrow_mut();
+        while test_node.height() > other_height + 1 {
+            test_node = match (test_node.force(), &other) {
+                (Internal(internal), Left(_)) => internal.first_edge().descend(),
+                (Internal(internal), Right(_)) => internal.last_edge().descend(),
+                (Leaf(_), _) => unreachable!(),
+            };
+        }
+        let mut open_node;
+        loop {
+            let node = match test_node.force() {
+                Internal(internal) => internal,
+                Leaf(_) => unreachable!(),
+            };
+            if node.len() < node::CAPACITY {
+                open_node = node;
+                break;
+            }
+            match node.forget_type().ascend() {
+                Ok(parent) => test_node = parent.into_node().forget_type(),
+                Err(_) => {
+                    open_node = self.push_internal_level(alloc.clone());
+                    break;
+                }
+            }
+        }
+
+        let open_height = open_node.height();
+        let raise = |mut tree: Root<K, V>| {
+            for _ in other_height..open_height - 1 {
+                tree.push_internal_level(alloc.clone());
+            }
+            tree
+        };
+        match other {
+            Left(left) => open_node.push_front(key, value, raise(left)),
+            Right(right) => open_node.push(key, value, raise(right)),
+        }
+    }
 }
 
 // An iterator for merging two sorted sequences into one
@@ -47,3 +47,41 @@ fn next(&mut self) -> Option<(K, V)> {
         }
     }
 }
+
+/// An iterator that panics when the keys of a supposedly sorted iterator turn
+/// out not to be in ascending order.
+///
+/// Used by [`BTreeMap::from_sorted_iter`][1].
+///
+/// [1]: crate::collections::BTreeMap::from_sorted_iter
+pub(super) struct AssertSortedIter<K, V, I>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    iter: Peekable<I>,
+}
+
+impl<K, V, I> AssertSortedIter<K, V, I>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    pub(super) fn new(iter: I) -> Self {
+        Self { iter: iter.peekable() }
+    }
+}
+
+impl<K, V, I> Iterator for AssertSortedIter<K, V, I>
+where
+    K: Ord,
+    I: Iterator<Item = (K, V)>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let next = self.iter.next()?;
+        if let Some(peeked) = self.iter.peek() {
+            assert!(next.0 <= peeked.0, "keys are not sorted in BTreeMap::from_sorted_iter");
+        }
+        Some(next)
+    }
+}
@@ -10,12 +10,12 @@
 use core::ptr;
 
 use super::borrow::DormantMutRef;
-use super::dedup_sorted_iter::DedupSortedIter;
+use super::dedup_sorted_iter::{AssertSortedIter, DedupSortedIter};
 use super::navigate::{LazyLeafRange, LeafRange};
 use super::node::ForceResult::*;
 use super::node::{self, Handle, NodeRef, Root, marker};
-use super::search::SearchBound;
 use super::search::SearchResult::*;
+use super::search::{self, SearchBound};
 use super::set_val::SetValZST;
 use crate::alloc::{Allocator, Global};
 use crate::vec::Vec;
@@ -628,6 +628,43 @@ impl<K, V> BTreeMap<K, V> {
     pub const fn new() -> BTreeMap<K, V> {
         BTreeMap { root: None, length: 0, alloc: ManuallyDrop::new(Global), _marker: PhantomData }
     }
+
+    /// Makes a `BTreeMap` from an iterator of key-value pairs that is sorted by key.
+    ///
+    /// Unlike collecting into a map, this does not need to sort the pairs or to
+    /// search for their place in the map, and builds the map in linear time.
+    /// If the iterator yields several pairs with the same key, the last one is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not in ascending order. The pairs that were
+    /// already taken from the iterator are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_bulk_ops)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from_sorted_iter([(1, "a"), (2, "b"), (2, "c"), (5, "d")]);
+    /// assert_eq!(map.len(), 3);
+    /// assert_eq!(map[&2], "c");
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 5]);
+    /// ```
+    #[unstable(feature = "btree_bulk_ops", issue = "none")]
+    pub fn from_sorted_iter<I>(iter: I) -> BTreeMap<K, V>
+    where
+        K: Ord,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = BTreeMap::new();
+        // Push into the map itself, so that it drops the pairs pushed so far
+        // if the iterator panics.
+        let root = map.root.insert(Root::new(Global));
+        let iter = DedupSortedIter::new(AssertSortedIter::new(iter.into_iter()));
+        root.bulk_push(iter, &mut map.length, Global);
+        map
+    }
 }
 
 impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
@@ -1397,6 +1434,81 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
         }
     }
 
+    /// Removes the key-value pairs in the specified range from the map, and
+    /// returns them in a new map.
+    ///
+    /// This takes time logarithmic in the size of the map, plus time linear in
+    /// the number of removed pairs, because the parts of the tree in the range
+    /// are split off and the remaining parts joined together as a whole, instead
+    /// of rebalancing the tree after every removed pair.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus
+    /// `remove_range(min..max)` will remove the keys from min (inclusive) to max
+    /// (exclusive). The range may also be entered as `(Bound<T>, Bound<T>)`, so
+    /// for example `remove_range((Excluded(4), Included(10)))` will remove the
+    /// keys from 4 (exclusive) to 10 (inclusive).
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_bulk_ops)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map: BTreeMap<i32, i32> = (0..10).map(|i| (i, i * 10)).collect();
+    /// let removed = map.remove_range(3..7);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [0, 1, 2, 7, 8, 9]);
+    /// assert_eq!(removed.keys().copied().collect::<Vec<_>>(), [3, 4, 5, 6]);
+    /// assert_eq!(removed[&5], 50);
+    /// ```
+    #[unstable(feature = "btree_bulk_ops", issue = "none")]
+    pub fn remove_range<T: ?Sized, R>(&mut self, range: R) -> Self
+    where
+        T: Ord,
+        K: Borrow<T> + Ord,
+        R: RangeBounds<T>,
+        A: Clone,
+    {
+        // Inlining these variables should be avoided. We assume the bounds reported by `range`
+        // remain the same, but an adversarial implementation could change between calls (#81138).
+        let (start, end) = (range.start_bound(), range.end_bound());
+        search::check_range_bounds::<T, V>(start, end);
+
+        let alloc = (*self.alloc).clone();
+        let Some(root) = self.root.as_mut() else {
+            return Self::new_in(alloc);
+        };
+        // If a comparison panics halfway, the map is left with fewer pairs than
+        // it had, so forget about their number until we know it again.
+        let total_len = mem::replace(&mut self.length, 0);
+
+        let mut removed_root = root.split_off_bound(start, alloc.clone());
+        let after_root = match end {
+            Bound::Included(key) => {
+                removed_root.split_off_bound(Bound::Excluded(key), alloc.clone())
+            }
+            Bound::Excluded(key) => {
+                removed_root.split_off_bound(Bound::Included(key), alloc.clone())
+            }
+            Bound::Unbounded => Root::new(alloc.clone()),
+        };
+        root.append_tree(after_root, alloc.clone());
+
+        let removed_len = removed_root.reborrow().calc_length();
+        self.length = total_len - removed_len;
+        BTreeMap {
+            root: Some(removed_root),
+            length: removed_len,
+            alloc: ManuallyDrop::new(alloc),
+            _marker: PhantomData,
+        }
+    }
+
     /// Creates an iterator that visits elements (key-value pairs) in the specified range in
     /// ascending key order and uses a closure to determine if an element
     /// should be removed.
@@ -2270,6 +2270,131 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
 
+fn check_remove_range(map: &BTreeMap<usize, usize>, range: (Bound<usize>, Bound<usize>)) {
+    let mut kept = map.clone();
+    let removed = kept.remove_range(range);
+    kept.check();
+    removed.check();
+    assert!(kept.iter().eq(map.iter().filter(|(k, _)| !range.contains(*k))));
+    assert!(removed.iter().eq(map.iter().filter(|(k, _)| range.contains(*k))));
+}
+
+#[test]
+fn test_remove_range_small() {
+    for len in [0, 1, 2, node::CAPACITY, node::CAPACITY + 1, MIN_INSERTS_HEIGHT_1 + 7] {
+        let map = BTreeMap::from_iter((0..len).map(|i| (i * 2, i)));
+        let bounds = |k| [Included(k), Excluded(k)];
+        for start in (0..len * 2 + 2).flat_map(bounds).chain([Unbounded]) {
+            for end in (0..len * 2 + 2).flat_map(bounds).chain([Unbounded]) {
+                let valid = match (start, end) {
+                    (Excluded(s), Excluded(e)) => s < e,
+                    (Included(s) | Excluded(s), Included(e) | Excluded(e)) => s <= e,
+                    _ => true,
+                };
+                if valid {
+                    check_remove_range(&map, (start, end));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_remove_range_heights() {
+    // Remove ranges at both ends and in the middle, so that the trees being
+    // joined afterwards have all kinds of height differences.
+    let len = if cfg!(miri) { MIN_INSERTS_HEIGHT_2 } else { 2000 };
+    let mut map = BTreeMap::from_iter((0..len).map(|i| (i, i)));
+    check_remove_range(&map, (Unbounded, Unbounded));
+    for _ in 0..2 {
+        for cut in [0, 1, node::CAPACITY, MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2, len / 2] {
+            check_remove_range(&map, (Unbounded, Excluded(cut)));
+            check_remove_range(&map, (Excluded(len - 1 - cut), Unbounded));
+            check_remove_range(&map, (Included(cut), Excluded(len - cut)));
+            check_remove_range(&map, (Included(len / 2 - cut / 2), Included(len / 2)));
+            check_remove_range(&map, (Excluded(len / 2), Excluded(len / 2 + cut / 2 + 1)));
+        }
+        // Insertion in non-ascending order creates some variation in node length.
+        let mut rng = DeterministicRng::new();
+        map = BTreeMap::from_iter((0..len).map(|_| (rng.next() as usize % len, 0)));
+    }
+}
+
+#[test]
+fn test_remove_range_large_random() {
+    let mut rng = DeterministicRng::new();
+    let data = if cfg!(miri) { rand_data(529) } else { rand_data(1529) };
+    let map = BTreeMap::from_iter(data.iter().map(|&(k, v)| (k as usize, v as usize)));
+    for _ in 0..10 {
+        let (a, b) = (rng.next() as usize, rng.next() as usize);
+        check_remove_range(&map, (Included(a.min(b)), Excluded(a.max(b))));
+    }
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_remove_range_backwards() {
+    let mut map = BTreeMap::from_iter((0..8).map(|i| (i, i)));
+    map.remove_range(5..3);
+}
+
+#[test]
+#[should_panic(expected = "range start and end are equal and excluded in BTreeMap")]
+fn test_remove_range_equal_excluded() {
+    let mut map = BTreeMap::from_iter((0..8).map(|i| (i, i)));
+    map.remove_range((Excluded(5), Excluded(5)));
+}
+
+#[test]
+#[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
+fn test_remove_range_drop_leak() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let mut map = BTreeMap::new();
+    map.insert(0, a.spawn(Panic::Never));
+    map.insert(1, b.spawn(Panic::InDrop));
+    map.insert(2, c.spawn(Panic::Never));
+
+    let removed = map.remove_range(1..);
+    catch_unwind(move || drop(removed)).unwrap_err();
+    map.check();
+    assert_eq!(map.len(), 1);
+    assert_eq!(a.dropped(), 0);
+    assert_eq!(b.dropped(), 1);
+    assert_eq!(c.dropped(), 1);
+}
+
+#[test]
+fn test_from_sorted_iter() {
+    for len in
+        [0, 1, node::CAPACITY, node::CAPACITY + 1, MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2]
+    {
+        let map = BTreeMap::from_sorted_iter((0..len).map(|i| (i, i * 2)));
+        map.check();
+        assert!(map.into_iter().eq((0..len).map(|i| (i, i * 2))));
+    }
+}
+
+#[test]
+fn test_from_sorted_iter_duplicates() {
+    let map = BTreeMap::from_sorted_iter([(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (3, 'e')]);
+    map.check();
+    assert!(map.into_iter().eq([(1, 'b'), (2, 'c'), (3, 'e')]));
+}
+
+#[test]
+#[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
+fn test_from_sorted_iter_unsorted() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let pairs =
+        [(1, a.spawn(Panic::Never)), (3, b.spawn(Panic::Never)), (2, a.spawn(Panic::Never))];
+    catch_unwind(move || BTreeMap::from_sorted_iter(pairs)).unwrap_err();
+    assert_eq!(a.dropped(), 2);
+    assert_eq!(b.dropped(), 1);
+}
+
 #[test]
 #[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
 fn test_into_iter_drop_leak_height_0() {
@@ -695,6 +695,22 @@ pub(super) fn push(&mut self, key: K, val: V, edge: Root<K, V>) {
             Handle::new_edge(self.reborrow_mut(), idx + 1).correct_parent_link();
         }
     }
+
+    /// Adds a key-value pair, and an edge to go to the left of that pair,
+    /// to the beginning of the node.
+    pub(super) fn push_front(&mut self, key: K, val: V, edge: Root<K, V>) {
+        assert!(edge.height == self.height - 1);
+
+        let new_len = self.len() + 1;
+        assert!(new_len <= CAPACITY);
+        unsafe {
+            slice_insert(self.key_area_mut(..new_len), 0, key);
+            slice_insert(self.val_area_mut(..new_len), 0, val);
+            slice_insert(self.edge_area_mut(..new_len + 1), 0, edge.node);
+            *self.len_mut() = new_len as u16;
+        }
+        self.correct_all_childrens_parent_links();
+    }
 }
 
 impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Leaf> {
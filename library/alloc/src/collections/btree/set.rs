@@ -1187,6 +1187,46 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, value: &Q) -> Self
         BTreeSet { map: self.map.split_off(value) }
     }
 
+    /// Removes the elements in the specified range from the set, and returns
+    /// them in a new set.
+    ///
+    /// This takes time logarithmic in the size of the set, plus time linear in
+    /// the number of removed elements.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus
+    /// `remove_range(min..max)` will remove the elements from min (inclusive) to
+    /// max (exclusive). The range may also be entered as `(Bound<T>, Bound<T>)`,
+    /// so for example `remove_range((Excluded(4), Included(10)))` will remove the
+    /// elements from 4 (exclusive) to 10 (inclusive).
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_bulk_ops)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set: BTreeSet<i32> = (0..10).collect();
+    /// let removed = set.remove_range(..=2);
+    ///
+    /// assert_eq!(set.iter().copied().collect::<Vec<_>>(), [3, 4, 5, 6, 7, 8, 9]);
+    /// assert_eq!(removed.iter().copied().collect::<Vec<_>>(), [0, 1, 2]);
+    /// ```
+    #[unstable(feature = "btree_bulk_ops", issue = "none")]
+    pub fn remove_range<K: ?Sized, R>(&mut self, range: R) -> Self
+    where
+        K: Ord,
+        T: Borrow<K> + Ord,
+        R: RangeBounds<K>,
+        A: Clone,
+    {
+        BTreeSet { map: self.map.remove_range(range) }
+    }
+
     /// Creates an iterator that visits elements in the specified range in ascending order and
     /// uses a closure to determine if an element should be removed.
     ///
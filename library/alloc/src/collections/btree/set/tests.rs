@@ -826,6 +826,33 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
 }
 
+#[test]
+fn test_remove_range() {
+    let data = if cfg!(miri) { rand_data(529) } else { rand_data(1529) };
+    let set = BTreeSet::from_iter(data.iter().copied());
+    let mut sorted = data;
+    sorted.sort();
+    sorted.dedup();
+    let (low, high) = (sorted[sorted.len() / 3], sorted[sorted.len() * 2 / 3]);
+
+    let mut kept = set.clone();
+    let removed = kept.remove_range((Excluded(low), Included(high)));
+    assert!(kept.iter().eq(sorted.iter().filter(|&&x| x <= low || x > high)));
+    assert!(removed.iter().eq(sorted.iter().filter(|&&x| x > low && x <= high)));
+
+    let mut kept = set;
+    let removed = kept.remove_range(..);
+    assert!(kept.is_empty());
+    assert!(removed.into_iter().eq(sorted));
+}
+
+#[should_panic(expected = "range start is greater than range end in BTreeSet")]
+#[test]
+fn test_remove_range_panic() {
+    let mut set = BTreeSet::from([1, 2, 3, 4]);
+    set.remove_range(3..2);
+}
+
 #[test]
 fn from_array() {
     let set = BTreeSet::from([1, 2, 3, 4]);
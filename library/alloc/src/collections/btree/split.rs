@@ -1,5 +1,7 @@
 use core::alloc::Allocator;
 use core::borrow::Borrow;
+use core::mem;
+use core::ops::Bound;
 
 use super::node::ForceResult::*;
 use super::node::Root;
@@ -39,6 +41,26 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
     where
         K: Borrow<Q>,
     {
+        self.split_off_bound(Bound::Included(key), alloc)
+    }
+
+    /// Split off a tree with the key-value pairs above the given lower bound,
+    /// i.e. at and after the key of an `Included` bound, after the key of an
+    /// `Excluded` bound, or all of them if `Unbounded`.
+    /// The same requirements and guarantees as for `split_off` apply.
+    pub(super) fn split_off_bound<Q: ?Sized + Ord, A: Allocator + Clone>(
+        &mut self,
+        bound: Bound<&Q>,
+        alloc: A,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let (key, key_goes_right) = match bound {
+            Bound::Included(key) => (key, true),
+            Bound::Excluded(key) => (key, false),
+            Bound::Unbounded => return mem::replace(self, Root::new(alloc)),
+        };
         let left_root = self;
         let mut right_root = Root::new_pillar(left_root.height(), alloc.clone());
         let mut left_node = left_root.borrow_mut();
@@ -47,7 +69,9 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
         loop {
             let mut split_edge = match left_node.search_node(key) {
                 // key is going to the right tree
-                Found(kv) => kv.left_edge(),
+                Found(kv) if key_goes_right => kv.left_edge(),
+                // key is staying in the left tree
+                Found(kv) => kv.right_edge(),
                 GoDown(edge) => edge,
             };
 
@@ -912,3 +912,61 @@ fn test_str_concat() {
     let s: String = format!("{a}{b}");
     assert_eq!(s.as_bytes()[9], 'd' as u8);
 }
+
+#[test]
+fn test_string_in_allocator() {
+    use std::alloc::System;
+
+    let mut s = String::with_capacity_in(4, System);
+    assert!(s.capacity() >= 4);
+    s.push_str("hello");
+    s += ", world";
+    s.extend(['!', '?']);
+    assert_eq!(s, "hello, world!?");
+    assert_eq!(s.pop(), Some('?'));
+
+    let clone = s.clone();
+    assert_eq!(clone, s);
+
+    let world = s.split_off(7);
+    assert_eq!(s, "hello, ");
+    assert_eq!(world, "world!");
+
+    let drained: String = s.drain(..5).collect();
+    assert_eq!(drained, "hello");
+    assert_eq!(s, ", ");
+
+    let boxed: Box<str, System> = world.into_boxed_str();
+    assert_eq!(&*boxed.clone(), "world!");
+    let world = boxed.into_string();
+    assert_eq!(world.into_bytes(), b"world!");
+
+    let mut bytes = Vec::new_in(System);
+    bytes.extend_from_slice(b"ok\xFF");
+    let err = String::from_utf8_in(bytes).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 2);
+    let mut bytes = err.into_bytes();
+    bytes.pop();
+    let ok = String::try_from(bytes).unwrap();
+    assert_eq!(ok, "ok");
+}
+
+#[test]
+fn test_string_in_allocator_borrow() {
+    use std::alloc::System;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    let mut key = String::new_in(System);
+    key.push_str("key");
+    let mut map = HashMap::new();
+    map.insert(key, 1);
+    assert_eq!(map.get("key"), Some(&1));
+    assert_eq!(map.get("other"), None);
+
+    let mut s = String::new_in(System);
+    s.push_str("cow");
+    assert_eq!(Cow::Borrowed("cow"), s);
+    assert_eq!(s, Cow::Borrowed("cow"));
+    assert_ne!(Cow::<str>::Owned(String::from("bull")), s);
+}
@@ -91,6 +91,7 @@
 use crate::backtrace_rs::{self, BytesOrWideString};
 use crate::ffi::c_void;
 use crate::panic::UnwindSafe;
+use crate::path::PathBuf;
 use crate::sync::LazyLock;
 use crate::sync::atomic::Ordering::Relaxed;
 use crate::sync::atomic::{Atomic, AtomicU8};
@@ -159,7 +160,13 @@ enum RawFrame {
     Fake,
 }
 
-struct BacktraceSymbol {
+/// A symbol of a [`BacktraceFrame`], describing a function executing at the frame.
+///
+/// A frame has several symbols if functions were inlined into each other at its instruction
+/// pointer. The innermost inlined function comes first, and the function they were all inlined
+/// into comes last.
+#[unstable(feature = "backtrace_frames", issue = "79676")]
+pub struct BacktraceSymbol {
     name: Option<Vec<u8>>,
     filename: Option<BytesOrWide>,
     lineno: Option<u32>,
@@ -207,6 +214,7 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "backtrace_frames", issue = "79676")]
 impl fmt::Debug for BacktraceSymbol {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         // FIXME: improve formatting: https://github.com/rust-lang/rust/issues/65280
@@ -233,6 +241,75 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "backtrace_frames", issue = "79676")]
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    ///
+    /// This is usually the address of the next instruction to execute in the frame, i.e. just
+    /// after the call which created the frame above it.
+    #[must_use]
+    pub fn ip(&self) -> *mut c_void {
+        self.frame.ip()
+    }
+
+    /// Returns the symbols the instruction pointer of this frame was resolved to.
+    ///
+    /// This is empty if no symbol could be found, e.g. because the binary has no symbols.
+    #[must_use]
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        &self.symbols
+    }
+}
+
+#[unstable(feature = "backtrace_frames", issue = "79676")]
+impl BacktraceSymbol {
+    /// Returns the demangled name of the function, without the hash of legacy symbol names.
+    #[must_use]
+    pub fn name(&self) -> Option<String> {
+        self.name.as_ref().map(|b| format!("{:#}", backtrace_rs::SymbolName::new(b)))
+    }
+
+    /// Returns the raw name of the function, as it is found in the binary.
+    #[must_use]
+    pub fn raw_name(&self) -> Option<&[u8]> {
+        self.name.as_deref()
+    }
+
+    /// Returns the path of the source file containing the function, if there is debug
+    /// information for it.
+    #[must_use]
+    pub fn filename(&self) -> Option<PathBuf> {
+        match self.filename.as_ref()? {
+            #[cfg(unix)]
+            BytesOrWide::Bytes(bytes) => {
+                use crate::os::unix::ffi::OsStrExt;
+                Some(PathBuf::from(crate::ffi::OsStr::from_bytes(bytes)))
+            }
+            #[cfg(not(unix))]
+            BytesOrWide::Bytes(bytes) => crate::str::from_utf8(bytes).ok().map(PathBuf::from),
+            #[cfg(windows)]
+            BytesOrWide::Wide(wide) => {
+                use crate::os::windows::ffi::OsStringExt;
+                Some(PathBuf::from(crate::ffi::OsString::from_wide(wide)))
+            }
+            #[cfg(not(windows))]
+            BytesOrWide::Wide(_) => None,
+        }
+    }
+
+    /// Returns the line in [`filename`](Self::filename), if there is debug information for it.
+    #[must_use]
+    pub fn line(&self) -> Option<u32> {
+        self.lineno
+    }
+
+    /// Returns the column in [`filename`](Self::filename), if there is debug information for it.
+    #[must_use]
+    pub fn column(&self) -> Option<u32> {
+        self.colno
+    }
+}
+
 impl fmt::Debug for BytesOrWide {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         output_filename(
@@ -371,11 +448,25 @@ pub fn status(&self) -> BacktraceStatus {
 
 impl<'a> Backtrace {
     /// Returns an iterator over the backtrace frames.
+    ///
+    /// The symbols of the frames are resolved first if that has not happened yet.
     #[must_use]
     #[unstable(feature = "backtrace_frames", issue = "79676")]
     pub fn frames(&'a self) -> &'a [BacktraceFrame] {
         if let Inner::Captured(c) = &self.inner { &c.frames } else { &[] }
     }
+
+    /// Resolves the symbols of the captured frames.
+    ///
+    /// Backtraces are captured without resolving symbols, which is expensive, and resolve them
+    /// when they are first formatted or [`frames`](Self::frames) is called. This allows paying
+    /// that cost at a convenient time instead, e.g. before sending the backtrace to another thread.
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn resolve(&self) {
+        if let Inner::Captured(c) = &self.inner {
+            LazyLock::force(c);
+        }
+    }
 }
 
 #[stable(feature = "backtrace", since = "1.65.0")]
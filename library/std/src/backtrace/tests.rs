@@ -1,5 +1,6 @@
 use super::*;
 use crate::panic::RefUnwindSafe;
+use crate::path::Path;
 
 fn generate_fake_frames() -> Vec<BacktraceFrame> {
     vec![
@@ -93,6 +94,41 @@ fn test_frames() {
     assert!(iter.all(|(f, e)| format!("{f:#?}") == *e));
 }
 
+#[test]
+fn test_frame_accessors() {
+    let backtrace = Backtrace {
+        inner: Inner::Captured(LazyLock::preinit(Capture {
+            actual_start: 1,
+            frames: generate_fake_frames(),
+        })),
+    };
+    backtrace.resolve();
+
+    let frames = backtrace.frames();
+    assert_eq!(frames.len(), 3);
+    assert!(frames.iter().all(|frame| !frame.ip().is_null()));
+
+    let symbols = frames[0].symbols();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name().as_deref(), Some("std::backtrace::Backtrace::create"));
+    assert_eq!(symbols[0].raw_name(), Some(&b"std::backtrace::Backtrace::create"[..]));
+    assert_eq!(symbols[0].filename().as_deref(), Some(Path::new("rust/backtrace.rs")));
+    assert_eq!(symbols[0].line(), Some(100));
+    assert_eq!(symbols[0].column(), None);
+
+    let symbols = frames[1].symbols();
+    assert_eq!(symbols[0].name().as_deref(), Some("__rust_maybe_catch_panic"));
+    assert_eq!(symbols[0].filename(), None);
+    assert_eq!(symbols[0].line(), None);
+
+    let symbols = frames[2].symbols();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name().as_deref(), Some("std::rt::lang_start_internal"));
+    assert_eq!(symbols[0].column(), Some(5));
+    assert_eq!(symbols[1].name().as_deref(), Some("std::rt::lang_start"));
+    assert_eq!(symbols[1].line(), Some(400));
+}
+
 #[test]
 fn backtrace_unwind_safe() {
     fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
@@ -274,6 +274,43 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// Iterator over the entries in a directory tree.
+///
+/// This iterator is returned from the [`walk_dir`] function of this module
+/// and from [`WalkDirOptions::walk`], and will yield instances of
+/// <code>[io::Result]<[WalkDirEntry]></code>.
+///
+/// Unless [`WalkDirOptions::sort_by_file_name`] is used, the order in which
+/// the entries of a single directory are returned is platform and filesystem
+/// dependent. A directory is always yielded before its contents, unless
+/// [`WalkDirOptions::contents_first`] is used.
+///
+/// # Errors
+///
+/// This [`io::Result`] will be an [`Err`] if an error occurred while reading
+/// a directory, while opening a directory to descend into it, or if following
+/// symbolic links leads back into a directory that is already being walked.
+/// The walk continues with the next entry after an error.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+#[derive(Debug)]
+pub struct WalkDir(fs_imp::WalkDir);
+
+/// Entries returned by the [`WalkDir`] iterator.
+///
+/// An instance of `WalkDirEntry` represents an entry somewhere below the root
+/// of the walk, which is never yielded itself.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDirEntry(fs_imp::WalkDirEntry);
+
+/// Options which can be used to configure how a directory tree is walked.
+///
+/// The [`walk_dir`] function is an alias for walking with the default
+/// options, which visit the whole tree in pre-order without following
+/// symbolic links.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct WalkDirOptions(fs_imp::WalkDirOptions);
+
 /// Reads the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -3105,6 +3142,52 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
     fs_imp::read_dir(path.as_ref()).map(ReadDir)
 }
 
+/// Returns an iterator over the entries within a directory and all of its
+/// subdirectories, recursively.
+///
+/// The iterator will yield instances of <code>[io::Result]<[WalkDirEntry]></code>.
+/// Every directory is yielded before its contents, symbolic links are not
+/// followed, and the directory at `path` itself is not yielded. This is
+/// equivalent to `WalkDirOptions::new().walk(path)`; see [`WalkDirOptions`]
+/// for other ways to walk a directory tree.
+///
+/// # Platform-specific behavior
+///
+/// On most Unix platforms, each directory is opened relative to the already
+/// open directory containing it, with `openat`. Replacing a directory with a
+/// symbolic link while it is being walked therefore cannot make the walk
+/// continue outside of the tree. Other platforms open each directory by its
+/// full path. Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the same situations as
+/// [`read_dir`]. Errors encountered further down the tree are yielded by the
+/// iterator.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::{fs, io};
+///
+/// fn main() -> io::Result<()> {
+///     for entry in fs::walk_dir(".")? {
+///         let entry = entry?;
+///         if entry.file_type().is_file() {
+///             println!("{}", entry.path().display());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> io::Result<WalkDir> {
+    WalkDirOptions::new().walk(path)
+}
+
 /// Changes the permissions found on a file or a directory.
 ///
 /// # Platform-specific behavior
@@ -3255,6 +3338,215 @@ fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
     }
 }
 
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkDirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkDirEntry>> {
+        self.0.next().map(|entry| entry.map(WalkDirEntry))
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl crate::iter::FusedIterator for WalkDir {}
+
+impl WalkDirEntry {
+    /// Returns the full path to the file that this entry represents.
+    ///
+    /// The full path is created by joining the path given to [`walk_dir`]
+    /// with the names of the directories leading to this entry, and the file
+    /// name of this entry.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn path(&self) -> PathBuf {
+        self.0.path()
+    }
+
+    /// Returns the file name of this entry, without any leading path
+    /// component(s).
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_name(&self) -> OsString {
+        self.0.file_name()
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    ///
+    /// If symbolic links are followed, this is the type of the file the link
+    /// points to, unless the link is dangling. Otherwise, symbolic links are
+    /// reported as such, like [`DirEntry::file_type`] does.
+    ///
+    /// The file type was already determined while walking, so this never
+    /// queries the filesystem.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_type(&self) -> FileType {
+        FileType(self.0.file_type())
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    ///
+    /// Like [`file_type`], this traverses the entry if it is a symbolic link
+    /// that was followed, and does not otherwise.
+    ///
+    /// [`file_type`]: WalkDirEntry::file_type
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.0.metadata().map(Metadata)
+    }
+
+    /// Returns `true` if this entry is a symbolic link, whether or not it was
+    /// followed.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn path_is_symlink(&self) -> bool {
+        self.0.path_is_symlink()
+    }
+
+    /// Returns the depth of this entry below the root of the walk.
+    ///
+    /// The entries of the root directory have a depth of 1.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn depth(&self) -> usize {
+        self.0.depth()
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WalkDirEntry").field(&self.path()).finish()
+    }
+}
+
+impl WalkDirOptions {
+    /// Creates a blank new set of options, which walk the whole tree in
+    /// pre-order without following symbolic links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fs_walk_dir)]
+    /// use std::fs::WalkDirOptions;
+    ///
+    /// let options = WalkDirOptions::new();
+    /// ```
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn new() -> Self {
+        WalkDirOptions(fs_imp::WalkDirOptions::new())
+    }
+
+    /// Sets the option to follow symbolic links, descending into the
+    /// directories they point to.
+    ///
+    /// A symbolic link leading back into a directory that is already being
+    /// walked is reported as an error of kind
+    /// [`io::ErrorKind::FilesystemLoop`] instead of being descended into.
+    /// Detecting such cycles is only supported on Unix and Windows.
+    ///
+    /// The root of the walk is always followed if it is a symbolic link.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.0.follow_symlinks(follow_symlinks);
+        self
+    }
+
+    /// Sets the maximum depth of the entries yielded.
+    ///
+    /// The entries of the root directory have a depth of 1, so a maximum
+    /// depth of 1 yields the same entries as [`read_dir`].
+    ///
+    /// This option defaults to [`usize::MAX`].
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.0.max_depth(max_depth);
+        self
+    }
+
+    /// Sets the option to yield the entries of each directory sorted by
+    /// their file name.
+    ///
+    /// This requires reading all entries of a directory before yielding any
+    /// of them, and keeping them in memory while its subdirectories are
+    /// walked.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by_file_name(&mut self, sort_by_file_name: bool) -> &mut Self {
+        self.0.sort_by_file_name(sort_by_file_name);
+        self
+    }
+
+    /// Sets the option to yield the contents of each directory before the
+    /// directory itself, walking the tree in post-order.
+    ///
+    /// This is the order needed to, for example, remove the entries of a
+    /// directory tree one by one.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn contents_first(&mut self, contents_first: bool) -> &mut Self {
+        self.0.contents_first(contents_first);
+        self
+    }
+
+    /// Sets the option to not descend into directories on a different file
+    /// system than the root of the walk. Such directories are still yielded.
+    ///
+    /// This is only supported on Unix and Windows. On other platforms,
+    /// [`walk`] returns an error of kind [`io::ErrorKind::Unsupported`] if
+    /// this option is set.
+    ///
+    /// This option defaults to `false`.
+    ///
+    /// [`walk`]: WalkDirOptions::walk
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn same_file_system(&mut self, same_file_system: bool) -> &mut Self {
+        self.0.same_file_system(same_file_system);
+        self
+    }
+
+    /// Walks the directory tree at `path` with the options specified by
+    /// `self`.
+    ///
+    /// The directory at `path` itself is not yielded.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the same situations as
+    /// [`read_dir`], and in the situations described for each option.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_walk_dir)]
+    /// use std::fs::{self, WalkDirOptions};
+    /// use std::io;
+    ///
+    /// // Remove the contents of a directory, but not the directory itself
+    /// fn empty_dir(path: &str) -> io::Result<()> {
+    ///     let walk = WalkDirOptions::new().contents_first(true).walk(path)?;
+    ///     for entry in walk {
+    ///         let entry = entry?;
+    ///         if entry.file_type().is_dir() {
+    ///             fs::remove_dir(entry.path())?;
+    ///         } else {
+    ///             fs::remove_file(entry.path())?;
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn walk<P: AsRef<Path>>(&self, path: P) -> io::Result<WalkDir> {
+        self.0.walk(path.as_ref()).map(WalkDir)
+    }
+}
+
 /// Returns `Ok(true)` if the path points at an existing entity.
 ///
 /// This function will traverse symbolic links to query information about the
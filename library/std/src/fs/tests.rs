@@ -1556,6 +1556,90 @@ fn read_dir_not_found() {
     assert_eq!(res.err().unwrap().kind(), ErrorKind::NotFound);
 }
 
+fn walk_dir_entries(walk: fs::WalkDir, root: &Path) -> Vec<(String, usize)> {
+    walk.map(|entry| {
+        let entry = check!(entry);
+        let path = entry.path().strip_prefix(root).unwrap().to_str().unwrap().replace('\\', "/");
+        (path, entry.depth())
+    })
+    .collect()
+}
+
+#[test]
+fn walk_dir_orders() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(File::create(tmpdir.join("a/b/c")));
+    check!(File::create(tmpdir.join("a/d")));
+    check!(File::create(tmpdir.join("e")));
+
+    let mut opts = fs::WalkDirOptions::new();
+    opts.sort_by_file_name(true);
+    let pre = walk_dir_entries(check!(opts.walk(tmpdir.path())), tmpdir.path());
+    assert_eq!(
+        pre,
+        [("a", 1), ("a/b", 2), ("a/b/c", 3), ("a/d", 2), ("e", 1)].map(|(p, d)| (p.to_owned(), d))
+    );
+
+    opts.contents_first(true);
+    let post = walk_dir_entries(check!(opts.walk(tmpdir.path())), tmpdir.path());
+    assert_eq!(
+        post,
+        [("a/b/c", 3), ("a/b", 2), ("a/d", 2), ("a", 1), ("e", 1)].map(|(p, d)| (p.to_owned(), d))
+    );
+
+    opts.max_depth(2);
+    let shallow = walk_dir_entries(check!(opts.walk(tmpdir.path())), tmpdir.path());
+    assert_eq!(
+        shallow,
+        [("a/b", 2), ("a/d", 2), ("a", 1), ("e", 1)].map(|(p, d)| (p.to_owned(), d))
+    );
+
+    let mut all: Vec<_> = walk_dir_entries(check!(fs::walk_dir(tmpdir.path())), tmpdir.path());
+    all.sort();
+    assert_eq!(all, pre);
+}
+
+#[test]
+fn walk_dir_not_a_directory() {
+    let tmpdir = tmpdir();
+    let file = tmpdir.join("file");
+    check!(File::create(&file));
+    assert!(fs::walk_dir(&file).is_err());
+    assert_eq!(fs::walk_dir(tmpdir.join("missing")).unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg_attr(
+    all(windows, target_arch = "aarch64"),
+    ignore = "SymLinks not enabled on Arm64 Windows runners https://github.com/actions/partner-runner-images/issues/94"
+)]
+fn walk_dir_symlinks() {
+    let tmpdir = tmpdir();
+    if !got_symlink_permission(&tmpdir) {
+        return;
+    };
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(symlink_dir(tmpdir.join("a"), tmpdir.join("a/b/up")));
+
+    // without following symlinks, the link is yielded but not descended into
+    let entries = walk_dir_entries(check!(fs::walk_dir(tmpdir.path())), tmpdir.path());
+    assert_eq!(entries.len(), 3);
+    let link = check!(fs::walk_dir(tmpdir.join("a/b"))).next().unwrap().unwrap();
+    assert!(link.file_type().is_symlink());
+    assert!(link.path_is_symlink());
+
+    // following it leads back to an ancestor, which is reported as an error
+    let walk = check!(fs::WalkDirOptions::new().follow_symlinks(true).walk(tmpdir.path()));
+    let (ok, err): (Vec<_>, Vec<_>) = walk.partition(|entry| entry.is_ok());
+    assert_eq!(ok.len(), 3);
+    assert_eq!(err.len(), 1);
+    assert_eq!(err[0].as_ref().unwrap_err().kind(), ErrorKind::FilesystemLoop);
+    let link = ok.into_iter().map(Result::unwrap).find(|entry| entry.file_name() == "up").unwrap();
+    assert!(link.file_type().is_dir());
+    assert!(link.path_is_symlink());
+}
+
 #[test]
 fn file_open_not_found() {
     let res = File::open("/path/that/does/not/exist");
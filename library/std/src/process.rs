@@ -169,7 +169,8 @@
 use crate::sys::pipe::{AnonPipe, read2};
 use crate::sys::process as imp;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
-use crate::{fmt, fs, str};
+use crate::time::{Duration, Instant};
+use crate::{fmt, fs, str, thread};
 
 /// Representation of a running or exited child process.
 ///
@@ -2297,6 +2298,83 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits for the child to exit completely, for at most `timeout`.
+    ///
+    /// If the child exits in time, then `Ok(Some(status))` is returned, and
+    /// the process ID is reaped on Unix, as with [`wait`]. If the timeout
+    /// elapses first, then `Ok(None)` is returned and the child keeps running.
+    /// If an error occurs, then that error is returned.
+    ///
+    /// Like [`wait`], this function closes the stdin handle to the child
+    /// process, if any, before waiting.
+    ///
+    /// See [`wait_deadline`] for how the waiting is done on each platform.
+    ///
+    /// [`wait`]: Child::wait
+    /// [`wait_deadline`]: Child::wait_deadline
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("60").spawn()?;
+    ///
+    /// match child.wait_timeout(Duration::from_secs(5))? {
+    ///     Some(status) => println!("exited with: {status}"),
+    ///     None => {
+    ///         child.kill()?;
+    ///         child.wait()?;
+    ///         println!("the child took too long and was killed");
+    ///     }
+    /// }
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            // a timeout this long is as good as none
+            None => self.wait().map(Some),
+        }
+    }
+
+    /// Waits for the child to exit completely, until `deadline` at the latest.
+    ///
+    /// If the child exits in time, then `Ok(Some(status))` is returned, and
+    /// the process ID is reaped on Unix, as with [`wait`]. If the deadline
+    /// passes first, then `Ok(None)` is returned and the child keeps running.
+    /// If an error occurs, then that error is returned. A deadline that has
+    /// already passed makes this function behave like [`try_wait`].
+    ///
+    /// Like [`wait`], this function closes the stdin handle to the child
+    /// process, if any, before waiting.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux, this waits on a pidfd for the child, opening one if the child
+    /// was not spawned with one. On Windows and Fuchsia, this waits on the
+    /// process handle. On other Unix platforms, or if the kernel does not
+    /// support pidfds, the first call installs a handler for `SIGCHLD`, and the
+    /// waiting thread wakes up whenever any child exits. The handler is only
+    /// installed if the signal still has its default disposition, and with
+    /// `SA_RESTART`, although system calls that are never restarted, such as
+    /// `poll` or `nanosleep`, can then fail with `EINTR` in other threads. If
+    /// the program handles or ignores `SIGCHLD` itself, the child is polled
+    /// with increasing intervals of up to 50 milliseconds instead.
+    ///
+    /// **Note**: This behavior may change in the future.
+    ///
+    /// [`wait`]: Child::wait
+    /// [`try_wait`]: Child::try_wait
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        drop(self.stdin.take());
+        Ok(self.handle.wait_deadline(deadline)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -2353,6 +2431,84 @@ pub fn wait_with_output(mut self) -> io::Result<Output> {
         let status = self.wait()?;
         Ok(Output { status, stdout, stderr })
     }
+
+    /// Simultaneously waits for the child to exit and collects all remaining
+    /// output on the stdout/stderr handles, killing the child if it has not
+    /// exited by `deadline`.
+    ///
+    /// This behaves like [`wait_with_output`], except that the child is
+    /// killed with [`kill`] once the deadline passes, in which case the
+    /// returned status is the one resulting from that, and the output is
+    /// whatever the child wrote before it was killed.
+    ///
+    /// The output is read on a separate thread, so that the deadline can be
+    /// enforced while the child is writing. Note that processes spawned by the
+    /// child may inherit its stdout and stderr, and that the output is only
+    /// complete once they close them too; such processes are not killed along
+    /// with the child, and can keep this function from returning after the
+    /// deadline.
+    ///
+    /// [`wait_with_output`]: Child::wait_with_output
+    /// [`kill`]: Child::kill
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::{Command, Stdio};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let child = Command::new("/bin/cat")
+    ///     .arg("file.txt")
+    ///     .stdout(Stdio::piped())
+    ///     .spawn()
+    ///     .expect("failed to execute child");
+    ///
+    /// let output = child
+    ///     .wait_with_output_deadline(Instant::now() + Duration::from_secs(10))
+    ///     .expect("failed to wait on child");
+    ///
+    /// assert!(output.status.success());
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_with_output_deadline(mut self, deadline: Instant) -> io::Result<Output> {
+        drop(self.stdin.take());
+
+        let pipes = (self.stdout.take(), self.stderr.take());
+        let reader = if let (None, None) = pipes {
+            None
+        } else {
+            Some(thread::Builder::new().spawn(move || -> io::Result<_> {
+                let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+                match pipes {
+                    (None, None) => {}
+                    (Some(mut out), None) => {
+                        out.read_to_end(&mut stdout)?;
+                    }
+                    (None, Some(mut err)) => {
+                        err.read_to_end(&mut stderr)?;
+                    }
+                    (Some(out), Some(err)) => {
+                        read2(out.inner, &mut stdout, err.inner, &mut stderr)?;
+                    }
+                }
+                Ok((stdout, stderr))
+            })?)
+        };
+
+        let status = match self.wait_deadline(deadline)? {
+            Some(status) => status,
+            None => {
+                self.kill()?;
+                self.wait()?
+            }
+        };
+        let (stdout, stderr) = match reader {
+            Some(reader) => reader.join().unwrap()?,
+            None => (Vec::new(), Vec::new()),
+        };
+        Ok(Output { status, stdout, stderr })
+    }
 }
 
 /// Terminates the current process with the specified exit code.
@@ -3,6 +3,7 @@
 use crate::io::{BorrowedBuf, ErrorKind};
 use crate::mem::MaybeUninit;
 use crate::str;
+use crate::time::{Duration, Instant};
 
 fn known_command() -> Command {
     if cfg!(windows) { Command::new("help") } else { Command::new("echo") }
@@ -260,6 +261,75 @@ fn test_wait_with_output_once() {
     assert_eq!(stderr, Vec::new());
 }
 
+fn sleep_cmd() -> Command {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(&["/C", "ping -n 60 127.0.0.1 >NUL"]);
+        cmd
+    } else {
+        let mut cmd = shell_cmd();
+        cmd.arg("-c").arg("exec sleep 60");
+        cmd
+    };
+    cmd.stdin(Stdio::null());
+    cmd
+}
+
+#[test]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_timeout() {
+    let mut prog = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "exit 1"]).spawn().unwrap()
+    } else {
+        shell_cmd().arg("-c").arg("false").spawn().unwrap()
+    };
+    let status = prog.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+    assert_eq!(status.code(), Some(1));
+    // the status is remembered, as with `wait`
+    assert_eq!(prog.wait_timeout(Duration::ZERO).unwrap(), Some(status));
+
+    let mut prog = sleep_cmd().spawn().unwrap();
+    let start = Instant::now();
+    assert_eq!(prog.wait_timeout(Duration::from_millis(100)).unwrap(), None);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    prog.kill().unwrap();
+    assert!(!prog.wait_timeout(Duration::MAX).unwrap().unwrap().success());
+}
+
+#[test]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_deadline_passed() {
+    let mut prog = sleep_cmd().spawn().unwrap();
+    assert_eq!(prog.wait_deadline(Instant::now()).unwrap(), None);
+    prog.kill().unwrap();
+    prog.wait().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_with_output_deadline() {
+    use crate::os::unix::process::ExitStatusExt;
+
+    let prog = shell_cmd().arg("-c").arg("echo hello").stdout(Stdio::piped()).spawn().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let Output { status, stdout, stderr } = prog.wait_with_output_deadline(deadline).unwrap();
+    assert!(status.success());
+    assert_eq!(str::from_utf8(&stdout).unwrap().trim(), "hello");
+    assert_eq!(stderr, Vec::new());
+
+    let prog = shell_cmd()
+        .arg("-c")
+        .arg("echo hello; exec sleep 60")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let Output { status, stdout, .. } = prog.wait_with_output_deadline(deadline).unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGKILL));
+    assert_eq!(str::from_utf8(&stdout).unwrap().trim(), "hello");
+}
+
 #[cfg(all(unix, not(target_os = "android")))]
 pub fn env_cmd() -> Command {
     Command::new("env")
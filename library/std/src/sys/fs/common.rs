@@ -1,5 +1,6 @@
 #![allow(dead_code)] // not used on all platforms
 
+use super::{DirEntry, FileAttr, ReadDir};
 use crate::fs;
 use crate::io::{self, Error, ErrorKind};
 use crate::path::Path;
@@ -58,3 +59,21 @@ pub fn exists(path: &Path) -> io::Result<bool> {
         Err(error) => Err(error),
     }
 }
+
+pub fn walk_dir_open(path: &Path) -> io::Result<(ReadDir, FileAttr)> {
+    let attr = super::metadata(path)?;
+    Ok((super::read_dir(path)?, attr))
+}
+
+pub fn walk_dir_open_child(
+    entry: &DirEntry,
+    follow_symlinks: bool,
+) -> io::Result<(ReadDir, FileAttr)> {
+    let path = entry.path();
+    let attr =
+        if follow_symlinks { super::metadata(&path)? } else { super::symlink_metadata(&path)? };
+    if !attr.file_type().is_dir() {
+        return Err(io::const_error!(ErrorKind::NotADirectory, "not a directory"));
+    }
+    Ok((super::read_dir(&path)?, attr))
+}
@@ -4,6 +4,7 @@
 use crate::path::{Path, PathBuf};
 
 pub mod common;
+mod walk_dir;
 
 cfg_if::cfg_if! {
     if #[cfg(target_family = "unix")] {
@@ -50,6 +51,7 @@ pub fn with_native_path<T>(path: &Path, f: &dyn Fn(&Path) -> io::Result<T>) -> i
     DirBuilder, DirEntry, File, FileAttr, FilePermissions, FileTimes, FileType, OpenOptions,
     ReadDir,
 };
+pub use walk_dir::{WalkDir, WalkDirEntry, WalkDirOptions};
 
 pub fn read_dir(path: &Path) -> io::Result<ReadDir> {
     // FIXME: use with_native_path on all platforms
@@ -2336,3 +2336,87 @@ pub fn remove_dir_all(p: &Path) -> io::Result<()> {
         run_path_with_cstr(p, &remove_dir_all_modern)
     }
 }
+
+pub use walk_dir_impl::{walk_dir_open, walk_dir_open_child};
+
+// Fallback for the platforms `remove_dir_impl` falls back on
+#[cfg(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "vxworks",
+    miri
+))]
+mod walk_dir_impl {
+    pub use crate::sys::fs::common::{walk_dir_open, walk_dir_open_child};
+}
+
+// Modern implementation opening each directory relative to its parent using openat() and
+// fdopendir()
+#[cfg(not(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "vxworks",
+    miri
+)))]
+mod walk_dir_impl {
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    use libc::{dirfd, fdopendir, openat};
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    use libc::{dirfd, fdopendir, openat64 as openat};
+
+    use super::{Dir, DirEntry, File, FileAttr, InnerReadDir, ReadDir};
+    use crate::ffi::CStr;
+    use crate::io;
+    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+    use crate::os::unix::prelude::{OwnedFd, RawFd};
+    use crate::path::{Path, PathBuf};
+    use crate::sys::common::small_c_string::run_path_with_cstr;
+    use crate::sys::fd::FileDesc;
+    use crate::sys::{cvt, cvt_r};
+    use crate::sys_common::FromInner;
+
+    fn openat_dironly(parent_fd: RawFd, p: &CStr, follow_symlinks: bool) -> io::Result<OwnedFd> {
+        let mut flags = libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY;
+        if !follow_symlinks {
+            flags |= libc::O_NOFOLLOW;
+        }
+        let fd = cvt_r(|| unsafe { openat(parent_fd, p.as_ptr(), flags) })?;
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn fdreaddir(dir_fd: OwnedFd, root: PathBuf) -> io::Result<(ReadDir, FileAttr)> {
+        // stat through the descriptor, so that the attributes are those of the directory read
+        let file = File::from_inner(FileDesc::from_inner(dir_fd));
+        let attr = file.file_attr()?;
+        let ptr = unsafe { fdopendir(file.as_raw_fd()) };
+        if ptr.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let dirp = Dir(ptr);
+        // file descriptor is automatically closed by libc::closedir() now, so give up ownership
+        let _ = file.into_raw_fd();
+        // unlike in `remove_dir_all`, the root is needed since entries are handed out
+        let inner = InnerReadDir { dirp, root };
+        Ok((ReadDir::new(inner), attr))
+    }
+
+    pub fn walk_dir_open(path: &Path) -> io::Result<(ReadDir, FileAttr)> {
+        let fd = run_path_with_cstr(path, &|p| openat_dironly(libc::AT_FDCWD, p, true))?;
+        fdreaddir(fd, path.to_path_buf())
+    }
+
+    pub fn walk_dir_open_child(
+        entry: &DirEntry,
+        follow_symlinks: bool,
+    ) -> io::Result<(ReadDir, FileAttr)> {
+        let parent_fd = cvt(unsafe { dirfd(entry.dir.dirp.0) })?;
+        let fd = openat_dironly(parent_fd, entry.name_cstr(), follow_symlinks)?;
+        fdreaddir(fd, entry.path())
+    }
+}
@@ -0,0 +1,282 @@
+//! Recursive directory traversal.
+//!
+//! The traversal itself is shared by all platforms. Opening directories is left to the platform
+//! through `walk_dir_open` and `walk_dir_open_child`: most Unix platforms open each directory
+//! relative to the file descriptor of its parent, so that replacing a directory with a symlink
+//! while it is being walked cannot redirect the traversal elsewhere, like `remove_dir_all` does.
+//! Other platforms go through the full path of each directory.
+
+#[cfg(not(target_family = "unix"))]
+use super::common::{walk_dir_open, walk_dir_open_child};
+#[cfg(target_family = "unix")]
+use super::imp::{walk_dir_open, walk_dir_open_child};
+use super::{DirEntry, FileAttr, FileType, ReadDir};
+use crate::ffi::OsString;
+use crate::path::{Path, PathBuf};
+use crate::{fmt, io, vec};
+
+#[derive(Clone, Debug)]
+pub struct WalkDirOptions {
+    follow_symlinks: bool,
+    max_depth: usize,
+    sort_by_file_name: bool,
+    contents_first: bool,
+    same_file_system: bool,
+}
+
+/// Identifies a directory, to detect cycles and file system boundaries.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct DirId {
+    dev: u64,
+    ino: u64,
+}
+
+#[cfg(target_family = "unix")]
+fn dir_id(attr: &FileAttr) -> Option<DirId> {
+    use crate::sys_common::AsInner;
+
+    let stat = attr.as_inner();
+    Some(DirId { dev: stat.st_dev as u64, ino: stat.st_ino as u64 })
+}
+
+#[cfg(windows)]
+fn dir_id(attr: &FileAttr) -> Option<DirId> {
+    Some(DirId { dev: attr.volume_serial_number()?.into(), ino: attr.file_index()? })
+}
+
+#[cfg(not(any(target_family = "unix", windows)))]
+fn dir_id(_attr: &FileAttr) -> Option<DirId> {
+    None
+}
+
+impl WalkDirOptions {
+    pub fn new() -> WalkDirOptions {
+        WalkDirOptions {
+            follow_symlinks: false,
+            max_depth: usize::MAX,
+            sort_by_file_name: false,
+            contents_first: false,
+            same_file_system: false,
+        }
+    }
+
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) {
+        self.follow_symlinks = follow_symlinks;
+    }
+
+    pub fn max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn sort_by_file_name(&mut self, sort_by_file_name: bool) {
+        self.sort_by_file_name = sort_by_file_name;
+    }
+
+    pub fn contents_first(&mut self, contents_first: bool) {
+        self.contents_first = contents_first;
+    }
+
+    pub fn same_file_system(&mut self, same_file_system: bool) {
+        self.same_file_system = same_file_system;
+    }
+
+    pub fn walk(&self, root: &Path) -> io::Result<WalkDir> {
+        let (dir, attr) = walk_dir_open(root)?;
+        let id = dir_id(&attr);
+        if self.same_file_system && id.is_none() {
+            return Err(io::const_error!(
+                io::ErrorKind::Unsupported,
+                "restricting a directory walk to one file system is not supported on this platform",
+            ));
+        }
+
+        let mut stack = Vec::new();
+        if self.max_depth > 0 {
+            stack.push(Frame::new(dir, id, None, self.sort_by_file_name));
+        }
+        Ok(WalkDir {
+            opts: self.clone(),
+            root: root.to_path_buf(),
+            root_dev: id.map(|id| id.dev),
+            stack,
+            pending: None,
+        })
+    }
+}
+
+/// A directory that is being walked.
+struct Frame {
+    dir: ReadDir,
+    /// The entries of `dir`, read ahead of time to be sorted.
+    sorted: Option<vec::IntoIter<io::Result<DirEntry>>>,
+    id: Option<DirId>,
+    /// The entry for the directory itself, yielded after its contents in contents-first mode.
+    entry: Option<WalkDirEntry>,
+}
+
+impl Frame {
+    fn new(mut dir: ReadDir, id: Option<DirId>, entry: Option<WalkDirEntry>, sort: bool) -> Frame {
+        let sorted = sort.then(|| {
+            let mut entries: Vec<_> = dir.by_ref().collect();
+            // errors sort before all entries, in the order they were encountered
+            entries.sort_by_cached_key(|entry| entry.as_ref().ok().map(DirEntry::file_name));
+            entries.into_iter()
+        });
+        Frame { dir, sorted, id, entry }
+    }
+
+    fn next_entry(&mut self) -> Option<io::Result<DirEntry>> {
+        match &mut self.sorted {
+            Some(entries) => entries.next(),
+            None => self.dir.next(),
+        }
+    }
+}
+
+pub struct WalkDir {
+    opts: WalkDirOptions,
+    root: PathBuf,
+    root_dev: Option<u64>,
+    stack: Vec<Frame>,
+    /// An item to yield before walking any further, for when an entry and an error are produced
+    /// at the same time.
+    pending: Option<io::Result<WalkDirEntry>>,
+}
+
+impl WalkDir {
+    /// Handles an entry found at `depth`, descending into it if it is a directory. Returns the
+    /// next item to yield, if there is one yet.
+    fn visit(&mut self, entry: DirEntry, depth: usize) -> Option<io::Result<WalkDirEntry>> {
+        let entry = match WalkDirEntry::new(entry, depth, self.opts.follow_symlinks) {
+            Ok(entry) => entry,
+            Err(err) => return Some(Err(err)),
+        };
+        if !entry.file_type.is_dir() || depth >= self.opts.max_depth {
+            return Some(Ok(entry));
+        }
+
+        let (dir, id) = match self.open_child(&entry) {
+            Ok(Some(dir)) => dir,
+            Ok(None) => return Some(Ok(entry)),
+            Err(err) => {
+                // still report the directory itself, on the side of the error it would have
+                // been yielded on had its contents been walked
+                return if self.opts.contents_first {
+                    self.pending = Some(Ok(entry));
+                    Some(Err(err))
+                } else {
+                    self.pending = Some(Err(err));
+                    Some(Ok(entry))
+                };
+            }
+        };
+
+        if self.opts.contents_first {
+            self.stack.push(Frame::new(dir, id, Some(entry), self.opts.sort_by_file_name));
+            None
+        } else {
+            self.stack.push(Frame::new(dir, id, None, self.opts.sort_by_file_name));
+            Some(Ok(entry))
+        }
+    }
+
+    /// Opens the directory `entry` refers to, or returns `None` if it should not be walked.
+    fn open_child(&self, entry: &WalkDirEntry) -> io::Result<Option<(ReadDir, Option<DirId>)>> {
+        let (dir, attr) = walk_dir_open_child(&entry.entry, self.opts.follow_symlinks)?;
+        let id = dir_id(&attr);
+        if self.opts.same_file_system && id.map(|id| id.dev) != self.root_dev {
+            return Ok(None);
+        }
+        // without following symlinks, a directory cannot be its own ancestor
+        if self.opts.follow_symlinks
+            && id.is_some()
+            && self.stack.iter().any(|frame| frame.id == id)
+        {
+            return Err(io::const_error!(
+                io::ErrorKind::FilesystemLoop,
+                "symbolic links form a cycle in the directory tree",
+            ));
+        }
+        Ok(Some((dir, id)))
+    }
+}
+
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkDirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkDirEntry>> {
+        if let Some(item) = self.pending.take() {
+            return Some(item);
+        }
+        loop {
+            let depth = self.stack.len();
+            let frame = self.stack.last_mut()?;
+            match frame.next_entry() {
+                Some(Ok(entry)) => {
+                    if let Some(item) = self.visit(entry, depth) {
+                        return Some(item);
+                    }
+                }
+                Some(Err(err)) => return Some(Err(err)),
+                None => {
+                    if let Some(entry) = self.stack.pop().and_then(|frame| frame.entry) {
+                        return Some(Ok(entry));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkDir").field("root", &self.root).field("opts", &self.opts).finish()
+    }
+}
+
+pub struct WalkDirEntry {
+    entry: DirEntry,
+    file_type: FileType,
+    depth: usize,
+    /// Whether the entry is a symlink, and `file_type` is the type of its target.
+    followed: bool,
+}
+
+impl WalkDirEntry {
+    fn new(entry: DirEntry, depth: usize, follow_symlinks: bool) -> io::Result<WalkDirEntry> {
+        let mut file_type = entry.file_type()?;
+        let mut followed = false;
+        if follow_symlinks && file_type.is_symlink() {
+            // dangling symlinks are reported as symlinks
+            if let Ok(attr) = super::metadata(&entry.path()) {
+                file_type = attr.file_type();
+                followed = true;
+            }
+        }
+        Ok(WalkDirEntry { entry, file_type, depth, followed })
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.entry.path()
+    }
+
+    pub fn file_name(&self) -> OsString {
+        self.entry.file_name()
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn metadata(&self) -> io::Result<FileAttr> {
+        if self.followed { super::metadata(&self.entry.path()) } else { self.entry.metadata() }
+    }
+
+    pub fn path_is_symlink(&self) -> bool {
+        self.followed || self.file_type.is_symlink()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
@@ -31,6 +31,7 @@
 pub mod stdio;
 pub mod sync;
 pub mod thread_local;
+pub mod thread_sched;
 
 // FIXME(117276): remove this, move feature implementations into individual
 //                submodules.
@@ -4,6 +4,7 @@
 use crate::sys::fd::FileDesc;
 use crate::sys::process::ExitStatus;
 use crate::sys_common::{AsInner, FromInner, IntoInner};
+use crate::time::Instant;
 
 #[cfg(test)]
 mod tests;
@@ -12,6 +13,13 @@
 pub(crate) struct PidFd(FileDesc);
 
 impl PidFd {
+    /// Opens a pidfd for an existing process, such as a child that was spawned without one.
+    pub fn open(pid: libc::pid_t) -> io::Result<PidFd> {
+        // pidfd_open sets CLOEXEC by default
+        let fd = cvt(unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) })?;
+        Ok(unsafe { PidFd::from_raw_fd(fd as RawFd) })
+    }
+
     pub fn kill(&self) -> io::Result<()> {
         self.send_signal(libc::SIGKILL)
     }
@@ -54,6 +62,25 @@ pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::from_waitid_siginfo(siginfo)))
         }
     }
+
+    /// Blocks until the process exits or `deadline` passes, without reaping it. Returns whether
+    /// the process has exited.
+    pub fn wait_exit_until(&self, deadline: Instant) -> io::Result<bool> {
+        let mut pfd = libc::pollfd { fd: self.0.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            // round up, so as to not wake up just before the deadline
+            let millis = timeout.as_nanos().div_ceil(1_000_000).min(libc::c_int::MAX as u128);
+            match cvt(unsafe { libc::poll(&mut pfd, 1, millis as libc::c_int) }) {
+                Ok(0) if millis == 0 => return Ok(false),
+                // the timeout was clamped, or the clock is coarser than the poll timeout
+                Ok(0) => {}
+                Ok(_) => return Ok(true),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
 }
 
 impl AsInner<FileDesc> for PidFd {
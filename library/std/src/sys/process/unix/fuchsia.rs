@@ -3,6 +3,7 @@
 use super::common::*;
 use crate::num::NonZero;
 use crate::sys::pal::fuchsia::*;
+use crate::time::Instant;
 use crate::{fmt, io, mem, ptr};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -221,6 +222,22 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
         Ok(Some(ExitStatus(proc_info.return_code)))
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let timeout = zx_time_t::try_from(timeout.as_nanos()).unwrap_or(ZX_TIME_INFINITE);
+        let deadline = zx_clock_get_monotonic().saturating_add(timeout);
+        let status = unsafe {
+            zx_object_wait_one(self.handle.raw(), ZX_TASK_TERMINATED, deadline, ptr::null_mut())
+        };
+        match status {
+            x if x == ZX_ERR_TIMED_OUT => Ok(None),
+            _ => {
+                zx_cvt(status)?;
+                self.try_wait()
+            }
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
@@ -13,6 +13,9 @@
         use unsupported as imp;
         pub use unsupported::output;
     } else {
+        // Restoring errno in a signal handler needs `set_errno`, which RTEMS lacks.
+        #[cfg(not(target_os = "rtems"))]
+        mod sigchld;
         mod unix;
         use unix as imp;
     }
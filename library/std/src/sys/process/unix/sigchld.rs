@@ -0,0 +1,136 @@
+//! Waiting for `SIGCHLD` with a deadline.
+//!
+//! Without pidfds there is nothing to wait on for a specific child, so the only notification
+//! that a child has exited is `SIGCHLD`. The first timed wait installs a handler for it, unless
+//! the program handles or ignores the signal itself, and the handler wakes every waiting thread
+//! through a pipe. Each waiter then checks with `waitpid` whether it was its own child that
+//! exited.
+//!
+//! The pipes live in a fixed set of slots and are never closed, so that the handler cannot write
+//! to a descriptor that a waiter has just closed and something else has reused.
+
+use crate::os::unix::io::IntoRawFd;
+use crate::sync::Once;
+use crate::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use crate::sys::{cvt, os, pipe};
+use crate::time::{Duration, Instant};
+use crate::{io, mem, ptr};
+
+/// How many threads can wait at once; any further waiters fall back to polling.
+const SLOTS: usize = 16;
+
+/// The longest a waiter sleeps without checking on its child. This only matters if the program
+/// replaces the handler while a thread is waiting, and the wakeups stop.
+const MAX_SLEEP: Duration = Duration::from_secs(1);
+
+struct Slot {
+    /// Whether a waiter currently owns this slot.
+    busy: AtomicBool,
+    /// The read end of the slot's pipe, or -1 if it has not been created yet.
+    read: AtomicI32,
+    /// The write end of the slot's pipe, which the handler writes to.
+    write: AtomicI32,
+}
+
+static SLOT: [Slot; SLOTS] = [const {
+    Slot { busy: AtomicBool::new(false), read: AtomicI32::new(-1), write: AtomicI32::new(-1) }
+}; SLOTS];
+
+static INSTALL: Once = Once::new();
+
+extern "C" fn handler(_signum: libc::c_int) {
+    let errno = os::errno();
+    for slot in &SLOT {
+        let fd = slot.write.load(Ordering::Acquire);
+        if fd >= 0 {
+            // The pipe is non-blocking: if it is full, the waiter has a wakeup pending anyway.
+            unsafe { libc::write(fd, [0u8].as_ptr().cast(), 1) };
+        }
+    }
+    os::set_errno(errno);
+}
+
+/// Returns whether `handler` is the current handler for `SIGCHLD`, installing it first if the
+/// signal still has its default disposition.
+fn handler_installed() -> bool {
+    INSTALL.call_once(|| unsafe {
+        // SAFETY: assuming all platforms define struct sigaction as "zero-initializable"
+        let mut action: libc::sigaction = mem::zeroed();
+        if libc::sigaction(libc::SIGCHLD, ptr::null_mut(), &mut action) == 0
+            && action.sa_sigaction == libc::SIG_DFL
+        {
+            // The default disposition discards the signal, so handling it only adds wakeups.
+            // `SA_RESTART` keeps it from interrupting most system calls in other threads.
+            action.sa_sigaction = handler as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART | libc::SA_NOCLDSTOP;
+            libc::sigaction(libc::SIGCHLD, &action, ptr::null_mut());
+        }
+    });
+    // The program may have replaced the handler since.
+    let mut action: libc::sigaction = unsafe { mem::zeroed() };
+    let ok = unsafe { libc::sigaction(libc::SIGCHLD, ptr::null_mut(), &mut action) } == 0;
+    ok && action.sa_sigaction == handler as libc::sighandler_t
+}
+
+/// A thread's registration for wakeups on `SIGCHLD`.
+pub struct Waiter {
+    slot: &'static Slot,
+}
+
+impl Waiter {
+    /// Registers the current thread for wakeups, or returns `None` if `SIGCHLD` is handled
+    /// elsewhere, too many threads are waiting already, or no pipe could be created.
+    ///
+    /// Children that exited before this call do not cause a wakeup, so the caller should check
+    /// on its child after registering.
+    pub fn new() -> Option<Waiter> {
+        if !handler_installed() {
+            return None;
+        }
+        let slot = SLOT.iter().find(|slot| {
+            slot.busy.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok()
+        })?;
+        let waiter = Waiter { slot };
+        if slot.read.load(Ordering::Relaxed) < 0 {
+            let (read, write) = pipe::anon_pipe().ok()?;
+            read.as_file_desc().set_nonblocking(true).ok()?;
+            write.as_file_desc().set_nonblocking(true).ok()?;
+            slot.read.store(read.into_raw_fd(), Ordering::Relaxed);
+            slot.write.store(write.into_raw_fd(), Ordering::Release);
+        } else {
+            // Throw away the wakeups meant for earlier waiters.
+            waiter.drain();
+        }
+        Some(waiter)
+    }
+
+    /// Sleeps until a child changes state, or until `deadline` at the latest. Spurious wakeups
+    /// are possible.
+    pub fn wait_until(&self, deadline: Instant) -> io::Result<()> {
+        let fd = self.slot.read.load(Ordering::Relaxed);
+        let mut pfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+        let timeout = deadline.saturating_duration_since(Instant::now()).min(MAX_SLEEP);
+        // round up, so as to not wake up just before the deadline
+        let millis = timeout.as_nanos().div_ceil(1_000_000) as libc::c_int;
+        match cvt(unsafe { libc::poll(&mut pfd, 1, millis) }) {
+            Ok(_) => {}
+            // most likely the signal we are waiting for
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+        self.drain();
+        Ok(())
+    }
+
+    fn drain(&self) {
+        let fd = self.slot.read.load(Ordering::Relaxed);
+        let mut buf = [0u8; 64];
+        while unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) } > 0 {}
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        self.slot.busy.store(false, Ordering::Release);
+    }
+}
@@ -16,13 +16,12 @@
 use crate::sys::cvt;
 #[cfg(target_os = "linux")]
 use crate::sys::pal::linux::pidfd::PidFd;
-use crate::{fmt, mem, sys};
+use crate::time::{Duration, Instant};
+use crate::{fmt, mem, sys, thread};
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "nto")] {
-        use crate::thread;
         use libc::{c_char, posix_spawn_file_actions_t, posix_spawnattr_t};
-        use crate::time::Duration;
         use crate::sync::LazyLock;
         // Get smallest amount of time we can sleep.
         // Return a common value if it cannot be determined.
@@ -1034,6 +1033,55 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.try_wait()? {
+            return Ok(Some(status));
+        }
+        #[cfg(target_os = "linux")]
+        {
+            // A pidfd becomes readable once the process exits. If the child was spawned without
+            // one, open one now: the child cannot have been reaped yet, so its pid is still ours.
+            let opened;
+            let pid_fd = match self.pidfd.as_ref() {
+                Some(pid_fd) => Some(pid_fd),
+                None => {
+                    opened = PidFd::open(self.pid).ok();
+                    opened.as_ref()
+                }
+            };
+            if let Some(pid_fd) = pid_fd {
+                return if pid_fd.wait_exit_until(deadline)? { self.try_wait() } else { Ok(None) };
+            }
+        }
+        // Without pidfds, sleep until some child exits, then check whether it was this one.
+        #[cfg(not(target_os = "rtems"))]
+        if let Some(waiter) = super::sigchld::Waiter::new() {
+            loop {
+                if let Some(status) = self.try_wait()? {
+                    return Ok(Some(status));
+                }
+                if Instant::now() >= deadline {
+                    return Ok(None);
+                }
+                waiter.wait_until(deadline)?;
+            }
+        }
+        // The program handles SIGCHLD itself, so poll instead, backing off so that long waits
+        // don't keep the CPU busy.
+        let mut delay = Duration::from_millis(1);
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            thread::sleep(delay.min(deadline - now));
+            if let Some(status) = self.try_wait()? {
+                return Ok(Some(status));
+            }
+            delay = (delay * 2).min(Duration::from_millis(50));
+        }
+    }
 }
 
 /// Unix exit statuses
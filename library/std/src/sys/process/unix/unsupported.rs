@@ -4,6 +4,7 @@
 use crate::io;
 use crate::num::NonZero;
 use crate::sys::pal::unsupported::*;
+use crate::time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -55,6 +56,10 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         unsupported()
     }
+
+    pub fn wait_deadline(&mut self, _deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        unsupported()
+    }
 }
 
 mod wait_status;
@@ -6,6 +6,7 @@
 use crate::num::NonZero;
 use crate::sys::cvt;
 use crate::sys::pal::thread;
+use crate::time::{Duration, Instant};
 use crate::{fmt, sys};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -185,6 +186,22 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        // there is no way to wait for a process with a timeout, so poll with some backoff
+        let mut delay = Duration::from_millis(1);
+        loop {
+            if let Some(status) = self.try_wait()? {
+                return Ok(Some(status));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            crate::thread::sleep(delay.min(deadline - now));
+            delay = (delay * 2).min(Duration::from_millis(50));
+        }
+    }
 }
 
 /// Unix exit statuses
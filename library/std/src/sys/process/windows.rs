@@ -21,10 +21,11 @@
 use crate::sys::fs::{File, OpenOptions};
 use crate::sys::handle::Handle;
 use crate::sys::pal::api::{self, WinError, utf16};
-use crate::sys::pal::{ensure_no_nuls, fill_utf16_buf};
+use crate::sys::pal::{dur2timeout, ensure_no_nuls, fill_utf16_buf};
 use crate::sys::pipe::{self, AnonPipe};
 use crate::sys::{cvt, path, stdio};
 use crate::sys_common::IntoInner;
+use crate::time::Instant;
 use crate::{cmp, env, fmt, ptr};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -733,6 +734,20 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
     }
 
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            // long timeouts are waited for in several steps rather than forever
+            let millis = dur2timeout(timeout).min(c::INFINITE - 1);
+            match unsafe { c::WaitForSingleObject(self.handle.as_raw_handle(), millis) } {
+                c::WAIT_OBJECT_0 => return self.try_wait(),
+                c::WAIT_TIMEOUT if millis == 0 => return Ok(None),
+                c::WAIT_TIMEOUT => {}
+                _ => return Err(io::Error::last_os_error()),
+            }
+        }
+    }
+
     pub fn handle(&self) -> &Handle {
         &self.handle
     }
@@ -0,0 +1,56 @@
+use crate::io;
+use crate::sys::cvt;
+use crate::sys::os::{errno, set_errno};
+use crate::thread::Priority;
+
+/// Restricts the current thread to the given CPUs.
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    // SAFETY: `cpu_set_t` is a plain bitmask, for which all zeroes is the empty set.
+    let mut set: libc::cpu_set_t = unsafe { crate::mem::zeroed() };
+    let capacity = 8 * size_of::<libc::cpu_set_t>();
+    for &cpu in cpus {
+        if cpu >= capacity {
+            return Err(io::const_error!(
+                io::ErrorKind::InvalidInput,
+                "CPU index is too large for the affinity mask",
+            ));
+        }
+        // SAFETY: `cpu` was checked to be within the set.
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    // A pid of 0 refers to the calling thread rather than to the whole process.
+    // SAFETY: `set` is a valid `cpu_set_t` of the given size.
+    cvt(unsafe { libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) }).map(drop)
+}
+
+/// Sets the nice value of the current thread, which on Linux is a per-thread attribute
+/// despite what POSIX says about `setpriority`.
+///
+/// `Low` and `Normal` only ever raise the inherited nice value, and `High` only ever lowers
+/// it, so that a thread spawned from a process started with `nice` does not need privileges
+/// to go back to the priority it inherited.
+pub fn set_priority(priority: Priority) -> io::Result<()> {
+    let current = current_nice()?;
+    let nice = match priority {
+        Priority::Low => current.max(10),
+        Priority::Normal => current.max(0),
+        // raising the priority above the default requires `CAP_SYS_NICE`, or an
+        // `RLIMIT_NICE` allowing it
+        Priority::High => current.min(-10),
+    };
+    if nice == current {
+        return Ok(());
+    }
+    // A `who` of 0 refers to the calling thread for `PRIO_PROCESS`.
+    // SAFETY: FFI call with no preconditions.
+    cvt(unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) }).map(drop)
+}
+
+/// Returns the nice value of the current thread.
+fn current_nice() -> io::Result<libc::c_int> {
+    // -1 is a valid nice value, so errors can only be told apart through `errno`.
+    set_errno(0);
+    // SAFETY: FFI call with no preconditions.
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    if nice == -1 && errno() != 0 { Err(io::Error::last_os_error()) } else { Ok(nice) }
+}
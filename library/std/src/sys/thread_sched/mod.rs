@@ -0,0 +1,14 @@
+//! Scheduling controls for the current thread, applied by newly spawned threads
+//! before they run any user code.
+
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        mod linux;
+        pub use linux::{set_affinity, set_priority};
+    } else {
+        mod unsupported;
+        pub use unsupported::{set_affinity, set_priority};
+    }
+}
@@ -0,0 +1,10 @@
+use crate::io;
+use crate::thread::Priority;
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    Err(io::Error::UNSUPPORTED_PLATFORM)
+}
+
+pub fn set_priority(_priority: Priority) -> io::Result<()> {
+    Err(io::Error::UNSUPPORTED_PLATFORM)
+}
@@ -1,4 +1,4 @@
-use super::{Thread, ThreadId, imp};
+use super::{SchedOptions, Thread, ThreadId, imp};
 use crate::mem::ManuallyDrop;
 use crate::ptr;
 use crate::sys::thread_local::local_pointer;
@@ -190,7 +190,7 @@ pub(crate) fn current_or_unnamed() -> Thread {
             (*current).clone()
         }
     } else if current == DESTROYED {
-        Thread::new(id::get_or_init(), None)
+        Thread::new(id::get_or_init(), None, SchedOptions::default())
     } else {
         init_current(current)
     }
@@ -235,7 +235,7 @@ fn init_current(current: *mut ()) -> Thread {
         CURRENT.set(BUSY);
         // If the thread ID was initialized already, use it.
         let id = id::get_or_init();
-        let thread = Thread::new(id, None);
+        let thread = Thread::new(id, None, SchedOptions::default());
 
         // Make sure that `crate::rt::thread_cleanup` will be run, which will
         // call `drop_current`.
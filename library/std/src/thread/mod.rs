@@ -165,8 +165,8 @@
 use crate::mem::{self, ManuallyDrop, forget};
 use crate::num::NonZero;
 use crate::pin::Pin;
-use crate::sync::Arc;
 use crate::sync::atomic::{Atomic, AtomicUsize, Ordering};
+use crate::sync::{Arc, mpsc};
 use crate::sys::sync::Parker;
 use crate::sys::thread as imp;
 use crate::sys_common::{AsInner, IntoInner};
@@ -191,6 +191,12 @@
 #[unstable(feature = "thread_spawn_hook", issue = "132951")]
 pub use spawnhook::add_spawn_hook;
 
+mod sched;
+
+#[unstable(feature = "thread_scheduling", issue = "none")]
+pub use sched::Priority;
+use sched::SchedOptions;
+
 ////////////////////////////////////////////////////////////////////////////////
 // Thread-local storage
 ////////////////////////////////////////////////////////////////////////////////
@@ -264,6 +270,8 @@ pub struct Builder {
     stack_size: Option<usize>,
     // Skip running and inheriting the thread spawn hooks
     no_hooks: bool,
+    // The CPU affinity and priority to apply to the thread-to-be
+    sched: SchedOptions,
 }
 
 impl Builder {
@@ -287,7 +295,7 @@ impl Builder {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Builder {
-        Builder { name: None, stack_size: None, no_hooks: false }
+        Builder { name: None, stack_size: None, no_hooks: false, sched: SchedOptions::default() }
     }
 
     /// Names the thread-to-be. Currently the name is used for identification
@@ -353,6 +361,79 @@ pub fn no_hooks(mut self) -> Builder {
         self
     }
 
+    /// Restricts the thread-to-be to run on the given CPUs only, identified
+    /// by their index starting at 0.
+    ///
+    /// The affinity is applied by the new thread before it runs any code
+    /// from spawn hooks or the closure it was spawned with, and [`spawn`]
+    /// waits for that to be done. If the affinity cannot be applied, for
+    /// example because none of the CPUs is available or because the platform
+    /// does not support it, then the thread runs anyway and
+    /// [`JoinHandle::scheduling_error`] returns the error.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This is currently only supported on Linux, where it corresponds to
+    /// `sched_setaffinity`. The index of each CPU must be lower than
+    /// `CPU_SETSIZE`. Setting an affinity fails with
+    /// [`io::ErrorKind::Unsupported`] on other platforms.
+    ///
+    /// **Note**: This behavior may change in the future.
+    ///
+    /// [`spawn`]: Builder::spawn
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread;
+    ///
+    /// let handler = thread::Builder::new()
+    ///     .affinity(&[0, 1])
+    ///     .spawn(|| {
+    ///         assert_eq!(thread::current().affinity(), Some(&[0, 1][..]));
+    ///     })
+    ///     .unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    pub fn affinity(mut self, cpus: &[usize]) -> Builder {
+        self.sched.affinity = Some(cpus.into());
+        self
+    }
+
+    /// Sets the scheduling priority of the thread-to-be.
+    ///
+    /// Like the [affinity], the priority is applied by the new thread before
+    /// it runs any code from spawn hooks or the closure it was spawned with,
+    /// and [`JoinHandle::scheduling_error`] returns the error if that fails.
+    /// See [`Priority`] for how the priority is implemented on each platform.
+    ///
+    /// [affinity]: Builder::affinity
+    /// [`spawn`]: Builder::spawn
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread::{self, Priority};
+    ///
+    /// let handler = thread::Builder::new()
+    ///     .priority(Priority::Low)
+    ///     .spawn(|| {
+    ///         // background work
+    ///     })
+    ///     .unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    pub fn priority(mut self, priority: Priority) -> Builder {
+        self.sched.priority = Some(priority);
+        self
+    }
+
     /// Spawns a new thread by taking ownership of the `Builder`, and returns an
     /// [`io::Result`] to its [`JoinHandle`].
     ///
@@ -478,7 +559,7 @@ unsafe fn spawn_unchecked_<'scope, F, T>(
         F: Send,
         T: Send,
     {
-        let Builder { name, stack_size, no_hooks } = self;
+        let Builder { name, stack_size, no_hooks, sched } = self;
 
         let stack_size = stack_size.unwrap_or_else(|| {
             static MIN: Atomic<usize> = AtomicUsize::new(0);
@@ -499,7 +580,16 @@ unsafe fn spawn_unchecked_<'scope, F, T>(
         });
 
         let id = ThreadId::new();
-        let my_thread = Thread::new(id, name);
+        let my_thread = Thread::new(id, name, sched);
+
+        // If scheduling settings were requested, the new thread reports whether it could apply
+        // them before anything else happens, so that the error is known once this returns.
+        let (sched_result_tx, sched_result_rx) = if my_thread.inner.sched.is_empty() {
+            (None, None)
+        } else {
+            let (tx, rx) = mpsc::sync_channel(1);
+            (Some(tx), Some(rx))
+        };
 
         let hooks = if no_hooks {
             spawnhook::ChildSpawnHooks::default()
@@ -553,6 +643,11 @@ fn drop(&mut self) {
                 imp::Thread::set_name(name);
             }
 
+            if let Some(tx) = sched_result_tx {
+                // The thread runs even if the settings could not be applied.
+                let _ = tx.send(their_thread.inner.sched.apply());
+            }
+
             let f = f.into_inner();
             let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
                 crate::sys::backtrace::__rust_begin_short_backtrace(|| hooks.run());
@@ -581,24 +676,24 @@ fn drop(&mut self) {
         let main =
             unsafe { Box::from_raw(Box::into_raw(main) as *mut (dyn FnOnce() + Send + 'static)) };
 
-        Ok(JoinInner {
-            // SAFETY:
-            //
-            // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
-            // through FFI or otherwise used with low-level threading primitives that have no
-            // notion of or way to enforce lifetimes.
-            //
-            // As mentioned in the `Safety` section of this function's documentation, the caller of
-            // this function needs to guarantee that the passed-in lifetime is sufficiently long
-            // for the lifetime of the thread.
-            //
-            // Similarly, the `sys` implementation must guarantee that no references to the closure
-            // exist after the thread has terminated, which is signaled by `Thread::join`
-            // returning.
-            native: unsafe { imp::Thread::new(stack_size, my_thread.name(), main)? },
-            thread: my_thread,
-            packet: my_packet,
-        })
+        // SAFETY:
+        //
+        // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
+        // through FFI or otherwise used with low-level threading primitives that have no
+        // notion of or way to enforce lifetimes.
+        //
+        // As mentioned in the `Safety` section of this function's documentation, the caller of
+        // this function needs to guarantee that the passed-in lifetime is sufficiently long
+        // for the lifetime of the thread.
+        //
+        // Similarly, the `sys` implementation must guarantee that no references to the closure
+        // exist after the thread has terminated, which is signaled by `Thread::join`
+        // returning.
+        let native = unsafe { imp::Thread::new(stack_size, my_thread.name(), main)? };
+
+        let sched_error = sched_result_rx.and_then(|rx| rx.recv().ok()?.err());
+
+        Ok(JoinInner { native, thread: my_thread, packet: my_packet, sched_error })
     }
 }
 
@@ -1407,6 +1502,7 @@ pub(crate) fn with_current_name<F, R>(f: F) -> R
 struct Inner {
     name: Option<ThreadNameString>,
     id: ThreadId,
+    sched: SchedOptions,
     parker: Parker,
 }
 
@@ -1441,7 +1537,7 @@ pub struct Thread {
 }
 
 impl Thread {
-    pub(crate) fn new(id: ThreadId, name: Option<String>) -> Thread {
+    pub(crate) fn new(id: ThreadId, name: Option<String>, sched: SchedOptions) -> Thread {
         let name = name.map(ThreadNameString::from);
 
         // We have to use `unsafe` here to construct the `Parker` in-place,
@@ -1454,6 +1550,7 @@ pub(crate) fn new(id: ThreadId, name: Option<String>) -> Thread {
             let ptr = Arc::get_mut_unchecked(&mut arc).as_mut_ptr();
             (&raw mut (*ptr).name).write(name);
             (&raw mut (*ptr).id).write(id);
+            (&raw mut (*ptr).sched).write(sched);
             Parker::new_in_place(&raw mut (*ptr).parker);
             Pin::new_unchecked(arc.assume_init())
         };
@@ -1584,6 +1681,47 @@ pub fn name(&self) -> Option<&str> {
         }
     }
 
+    /// Gets the CPU affinity the thread was spawned with.
+    ///
+    /// This is the set of CPUs passed to [`Builder::affinity`], if any, even
+    /// if it could not be applied, see [`JoinHandle::scheduling_error`]. It
+    /// does not reflect changes made to the affinity of the thread by other
+    /// means.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread;
+    ///
+    /// let handler = thread::Builder::new()
+    ///     .affinity(&[0])
+    ///     .spawn(|| {
+    ///         assert_eq!(thread::current().affinity(), Some(&[0][..]));
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(handler.thread().affinity(), Some(&[0][..]));
+    /// assert!(thread::current().affinity().is_none());
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    #[must_use]
+    pub fn affinity(&self) -> Option<&[usize]> {
+        self.inner.sched.affinity.as_deref()
+    }
+
+    /// Gets the priority the thread was spawned with.
+    ///
+    /// This is the priority passed to [`Builder::priority`], if any, even if
+    /// it could not be applied, see [`JoinHandle::scheduling_error`]. It does
+    /// not reflect changes made to the priority of the thread by other means.
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    #[must_use]
+    pub fn priority(&self) -> Option<Priority> {
+        self.inner.sched.priority
+    }
+
     /// Consumes the `Thread`, returning a raw pointer.
     ///
     /// To avoid a memory leak the pointer must be converted
@@ -1760,6 +1898,8 @@ struct JoinInner<'scope, T> {
     native: imp::Thread,
     thread: Thread,
     packet: Arc<Packet<'scope, T>>,
+    // The error from applying the scheduling settings of the thread
+    sched_error: Option<io::Error>,
 }
 
 impl<'scope, T> JoinInner<'scope, T> {
@@ -1936,6 +2076,40 @@ pub fn join(self) -> Result<T> {
     pub fn is_finished(&self) -> bool {
         Arc::strong_count(&self.0.packet) == 1
     }
+
+    /// Returns the error from applying the [affinity] or the [priority] the
+    /// thread was spawned with, if any.
+    ///
+    /// The thread applies them before it runs any user code, and runs even
+    /// if that fails, for example because the platform does not support it.
+    /// The error is known as soon as the thread is spawned.
+    ///
+    /// [affinity]: Builder::affinity
+    /// [priority]: Builder::priority
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread::{self, Priority};
+    ///
+    /// let handler = thread::Builder::new()
+    ///     .priority(Priority::Low)
+    ///     .spawn(|| {
+    ///         // background work
+    ///     })
+    ///     .unwrap();
+    ///
+    /// if let Some(err) = handler.scheduling_error() {
+    ///     eprintln!("running at the default priority: {err}");
+    /// }
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    #[must_use]
+    pub fn scheduling_error(&self) -> Option<&io::Error> {
+        self.0.sched_error.as_ref()
+    }
 }
 
 impl<T> AsInner<imp::Thread> for JoinHandle<T> {
@@ -0,0 +1,57 @@
+use crate::io;
+use crate::sys::thread_sched as imp;
+
+/// A portable hint for the scheduling priority of a thread, set with
+/// [`Builder::priority`].
+///
+/// The levels are relative to the default priority of the platform, and how
+/// they map onto the platform's notion of priority is unspecified. Raising
+/// the priority of a thread may require privileges.
+///
+/// # Platform-specific behavior
+///
+/// On Linux, this sets the nice value of the thread: `Low` maps to at least
+/// 10, `Normal` to at least 0 and `High` to at most -10. Threads inherit the
+/// nice value of the thread that spawned them, which is kept if it already
+/// satisfies the requested priority, so that for example `Normal` is a no-op
+/// in a process started with `nice`. Setting a lower nice value than the
+/// inherited one requires the `CAP_SYS_NICE` capability or a sufficient
+/// `RLIMIT_NICE`. The real-time scheduling policies are not used.
+///
+/// Setting a priority is not supported on other platforms.
+///
+/// **Note**: This behavior may change in the future.
+///
+/// [`Builder::priority`]: super::Builder::priority
+#[unstable(feature = "thread_scheduling", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Priority {
+    /// A priority below the default, for background work.
+    Low,
+    /// The default priority of the platform.
+    Normal,
+    /// A priority above the default, for latency-sensitive work.
+    High,
+}
+
+/// The scheduling settings a thread was spawned with.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SchedOptions {
+    pub(super) affinity: Option<Box<[usize]>>,
+    pub(super) priority: Option<Priority>,
+}
+
+impl SchedOptions {
+    pub(super) fn is_empty(&self) -> bool {
+        self.affinity.is_none() && self.priority.is_none()
+    }
+
+    /// Applies the settings to the current thread.
+    pub(super) fn apply(&self) -> io::Result<()> {
+        // Both settings are attempted, and the first error is returned.
+        let affinity = self.affinity.as_deref().map_or(Ok(()), imp::set_affinity);
+        let priority = self.priority.map_or(Ok(()), imp::set_priority);
+        affinity.and(priority)
+    }
+}
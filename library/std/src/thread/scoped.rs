@@ -328,6 +328,19 @@ pub fn join(self) -> Result<T> {
     pub fn is_finished(&self) -> bool {
         Arc::strong_count(&self.0.packet) == 1
     }
+
+    /// Returns the error from applying the [affinity] or the [priority] the
+    /// thread was spawned with, if any.
+    ///
+    /// See [`JoinHandle::scheduling_error`](super::JoinHandle::scheduling_error).
+    ///
+    /// [affinity]: super::Builder::affinity
+    /// [priority]: super::Builder::priority
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    #[must_use]
+    pub fn scheduling_error(&self) -> Option<&io::Error> {
+        self.0.sched_error.as_ref()
+    }
 }
 
 #[stable(feature = "scoped_threads", since = "1.63.0")]
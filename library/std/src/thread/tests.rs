@@ -353,6 +353,83 @@ fn test_thread_os_id_not_equal() {
     assert!(current_id != spawned_id);
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_affinity() {
+    // pick a CPU this thread is allowed to run on
+    let mut set: libc::cpu_set_t = unsafe { crate::mem::zeroed() };
+    assert_eq!(unsafe { libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &mut set) }, 0);
+    let cpu = (0..libc::CPU_SETSIZE as usize).find(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) });
+    let cpu = cpu.unwrap();
+
+    let handle = Builder::new()
+        .affinity(&[cpu])
+        .spawn(move || {
+            let mut set: libc::cpu_set_t = unsafe { crate::mem::zeroed() };
+            let size = size_of::<libc::cpu_set_t>();
+            assert_eq!(unsafe { libc::sched_getaffinity(0, size, &mut set) }, 0);
+            assert_eq!(unsafe { libc::CPU_COUNT(&set) }, 1);
+            assert!(unsafe { libc::CPU_ISSET(cpu, &set) });
+            thread::current().affinity().map(<[usize]>::to_vec)
+        })
+        .unwrap();
+    assert_eq!(handle.thread().affinity(), Some(&[cpu][..]));
+    assert_eq!(handle.join().unwrap(), Some(vec![cpu]));
+    assert!(thread::current().affinity().is_none());
+}
+
+#[test]
+fn test_affinity_error() {
+    let handle = Builder::new().affinity(&[usize::MAX]).spawn(|| 42).unwrap();
+    let err = handle.scheduling_error().unwrap();
+    if cfg!(target_os = "linux") {
+        assert_eq!(err.kind(), crate::io::ErrorKind::InvalidInput);
+    } else {
+        assert_eq!(err.kind(), crate::io::ErrorKind::Unsupported);
+    }
+    // the thread runs anyway
+    assert_eq!(handle.join().unwrap(), 42);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(miri, ignore)] // Miri does not support `setpriority`
+fn test_priority() {
+    // lowering the priority never requires privileges, and keeps the inherited
+    // nice value if it is already higher
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    let handle = Builder::new()
+        .priority(thread::Priority::Low)
+        .spawn(|| {
+            assert_eq!(unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) }, nice.max(10));
+            thread::current().priority()
+        })
+        .unwrap();
+    assert!(handle.scheduling_error().is_none());
+    assert_eq!(handle.join().unwrap(), Some(thread::Priority::Low));
+    assert!(thread::current().priority().is_none());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(miri, ignore)] // Miri does not support `setpriority`
+fn test_priority_normal_keeps_inherited_nice() {
+    // like running the test binary under `nice`
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    let handle = Builder::new()
+        .priority(thread::Priority::Low)
+        .spawn(move || {
+            let inner = Builder::new()
+                .priority(thread::Priority::Normal)
+                .spawn(|| unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) })
+                .unwrap();
+            assert!(inner.scheduling_error().is_none());
+            inner.join().unwrap()
+        })
+        .unwrap();
+    assert_eq!(handle.join().unwrap(), nice.max(10));
+}
+
 #[test]
 fn test_scoped_threads_drop_result_before_join() {
     let actually_finished = &AtomicBool::new(false);
@@ -102,8 +102,10 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document
+            tap    = Output a TAP version 14 document
+            ctrf   = Output a Common Test Report Format document",
+            "pretty|terse|json|junit|tap|ctrf",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -421,9 +423,21 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Tap
+        }
+        Some("ctrf") => {
+            if !allow_unstable {
+                return Err("The \"ctrf\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Ctrf
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit, tap or ctrf (was \
                  {v})"
             ));
         }
@@ -0,0 +1,208 @@
+use std::io::prelude::Write;
+use std::io::{self};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::json::EscapedString;
+use super::{OutputFormatter, split_module_path};
+use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation};
+use crate::test_result::TestResult;
+use crate::time;
+use crate::types::{TestDesc, TestType};
+
+/// The version of the CTRF specification the report follows.
+const SPEC_VERSION: &str = "1.0.0";
+
+/// Writes a report in the [Common Test Report Format](https://ctrf.io).
+pub(crate) struct CtrfFormatter<T> {
+    out: OutputLocation<T>,
+    /// The start of the run, in milliseconds since the Unix epoch.
+    start: u128,
+    results: Vec<(TestDesc, TestResult, Option<time::TestExecTime>, Vec<u8>)>,
+}
+
+impl<T: Write> CtrfFormatter<T> {
+    pub(crate) fn new(out: OutputLocation<T>) -> Self {
+        Self { out, start: 0, results: Vec::new() }
+    }
+
+    fn writeln_message(&mut self, s: &str) -> io::Result<()> {
+        assert!(!s.contains('\n'));
+
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+}
+
+fn unix_time_millis() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default()
+}
+
+fn test_to_json(
+    desc: &TestDesc,
+    result: &TestResult,
+    exec_time: Option<&time::TestExecTime>,
+    stdout: &[u8],
+    display_output: bool,
+) -> String {
+    let (status, message) = match result {
+        TestResult::TrOk | TestResult::TrBench(_) => ("passed", None),
+        TestResult::TrFailed => ("failed", None),
+        TestResult::TrFailedMsg(msg) => ("failed", Some(msg.as_str())),
+        TestResult::TrTimedFail => ("failed", Some("time limit exceeded")),
+        TestResult::TrIgnored => ("skipped", desc.ignore_message),
+    };
+    let duration = exec_time.map(|t| t.0.as_millis()).unwrap_or_default();
+
+    let mut json = format!(
+        r#"{{ "name": "{}", "status": "{status}", "duration": {duration}"#,
+        EscapedString(desc.name.as_slice())
+    );
+    if let Some(message) = message {
+        json.push_str(&format!(r#", "message": "{}""#, EscapedString(message)));
+    }
+    if let (Some(module_path), _) = split_module_path(desc) {
+        json.push_str(&format!(r#", "suite": "{}""#, EscapedString(module_path)));
+    }
+    let test_type = match desc.test_type {
+        TestType::UnitTest => Some("unit"),
+        TestType::IntegrationTest => Some("integration"),
+        TestType::DocTest => Some("doc"),
+        TestType::Unknown => None,
+    };
+    if let Some(test_type) = test_type {
+        json.push_str(&format!(r#", "type": "{test_type}""#));
+    }
+    if !desc.source_file.is_empty() {
+        json.push_str(&format!(
+            r#", "filePath": "{}", "line": {}"#,
+            EscapedString(desc.source_file),
+            desc.start_line
+        ));
+    }
+    let show_stdout = status == "failed" || (display_output && *result == TestResult::TrOk);
+    if show_stdout && !stdout.is_empty() {
+        let stdout = String::from_utf8_lossy(stdout);
+        let lines: Vec<String> =
+            stdout.lines().map(|line| format!(r#""{}""#, EscapedString(line))).collect();
+        json.push_str(&format!(r#", "stdout": [{}]"#, lines.join(", ")));
+    }
+    if let TestResult::TrBench(bs) = result {
+        let mbps = if bs.mb_s == 0 {
+            String::new()
+        } else {
+            format!(r#", "mib_per_second": {}"#, bs.mb_s)
+        };
+        json.push_str(&format!(
+            r#", "extra": {{ "median_ns": {}, "deviation_ns": {}{mbps} }}"#,
+            bs.ns_iter_summ.median,
+            bs.ns_iter_summ.max - bs.ns_iter_summ.min
+        ));
+    }
+    json.push_str(" }");
+    json
+}
+
+impl<T: Write> OutputFormatter for CtrfFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_run_start(
+        &mut self,
+        _test_count: usize,
+        _shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
+        self.start = unix_time_millis();
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test timeout.
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // The report starts with a summary of all results, so we can't write anything until
+        // the run is complete.
+        self.results.push((desc.clone(), result.clone(), exec_time.cloned(), stdout.to_vec()));
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let stop = unix_time_millis();
+        let results = std::mem::take(&mut self.results);
+
+        let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+        for (_, result, _, _) in &results {
+            match result {
+                TestResult::TrOk | TestResult::TrBench(_) => passed += 1,
+                TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
+                    failed += 1
+                }
+                TestResult::TrIgnored => skipped += 1,
+            }
+        }
+
+        self.writeln_message("{")?;
+        self.writeln_message(r#"  "reportFormat": "CTRF","#)?;
+        self.writeln_message(&format!(r#"  "specVersion": "{SPEC_VERSION}","#))?;
+        self.writeln_message(r#"  "results": {"#)?;
+        self.writeln_message(r#"    "tool": { "name": "libtest" },"#)?;
+        self.writeln_message(&format!(
+            r#"    "summary": {{ "tests": {}, "passed": {passed}, "failed": {failed}, "pending": 0, "skipped": {skipped}, "other": 0, "start": {}, "stop": {stop} }},"#,
+            results.len(),
+            self.start,
+        ))?;
+        if results.is_empty() {
+            self.writeln_message(r#"    "tests": []"#)?;
+        } else {
+            self.writeln_message(r#"    "tests": ["#)?;
+            for (i, (desc, result, exec_time, stdout)) in results.iter().enumerate() {
+                let test = test_to_json(
+                    desc,
+                    result,
+                    exec_time.as_ref(),
+                    stdout,
+                    state.options.display_output,
+                );
+                let separator = if i + 1 < results.len() { "," } else { "" };
+                self.writeln_message(&format!("      {test}{separator}"))?;
+            }
+            self.writeln_message("    ]")?;
+        }
+        self.writeln_message("  }")?;
+        self.writeln_message("}")?;
+
+        Ok(state.failed == 0)
+    }
+
+    fn write_merged_doctests_times(
+        &mut self,
+        _total_time: f64,
+        _compilation_time: f64,
+    ) -> io::Result<()> {
+        // The report is already complete at this point, and writing anything after it would
+        // make the output invalid JSON.
+        Ok(())
+    }
+}
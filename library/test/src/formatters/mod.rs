@@ -4,16 +4,20 @@
 use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState};
 use crate::test_result::TestResult;
 use crate::time;
-use crate::types::{TestDesc, TestName};
+use crate::types::{TestDesc, TestName, TestType};
 
+mod ctrf;
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
+pub(crate) use self::ctrf::CtrfFormatter;
 pub(crate) use self::json::JsonFormatter;
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -48,3 +52,16 @@ pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &Test
     }
     writeln!(test_output, "---- {test_name} stderr ----").unwrap();
 }
+
+/// Splits the name of a test into the path of the module containing it and its own name.
+/// Doctests are named after the item they document, so they are never split.
+pub(crate) fn split_module_path(desc: &TestDesc) -> (Option<&str>, &str) {
+    let name = desc.name.as_slice();
+    match desc.test_type {
+        TestType::DocTest => (None, name),
+        _ => match name.rsplit_once("::") {
+            Some((module_path, name)) => (Some(module_path), name),
+            None => (None, name),
+        },
+    }
+}
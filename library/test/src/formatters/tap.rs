@@ -0,0 +1,236 @@
+use std::io::prelude::Write;
+use std::io::{self};
+
+use super::json::EscapedString;
+use super::{OutputFormatter, split_module_path};
+use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation};
+use crate::test_result::TestResult;
+use crate::time;
+use crate::types::TestDesc;
+
+/// Writes a [TAP version 14](https://testanything.org/tap-version-14-specification.html)
+/// document. Tests are grouped into subtests by the module containing them.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    results: Vec<TapItem>,
+}
+
+struct TapTest {
+    name: String,
+    desc: TestDesc,
+    result: TestResult,
+    exec_time: Option<time::TestExecTime>,
+    stdout: Vec<u8>,
+}
+
+enum TapItem {
+    Test(TapTest),
+    Module { name: String, items: Vec<TapItem> },
+}
+
+impl TapItem {
+    fn is_failure(&self) -> bool {
+        match self {
+            TapItem::Test(test) => matches!(
+                test.result,
+                TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail
+            ),
+            TapItem::Module { items, .. } => items.iter().any(TapItem::is_failure),
+        }
+    }
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub(crate) fn new(out: OutputLocation<T>) -> Self {
+        Self { out, results: Vec::new() }
+    }
+
+    fn write_line(&mut self, indent: usize, s: &str) -> io::Result<()> {
+        assert!(!s.contains('\n'));
+
+        for _ in 0..indent {
+            self.out.write_all(b"    ")?;
+        }
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+
+    /// Writes the plan and test points for `items`, nesting modules as subtests.
+    fn write_items(
+        &mut self,
+        items: &[TapItem],
+        indent: usize,
+        display_output: bool,
+    ) -> io::Result<()> {
+        self.write_line(indent, &format!("1..{}", items.len()))?;
+        for (item, number) in items.iter().zip(1..) {
+            match item {
+                TapItem::Module { name, items: module_items } => {
+                    self.write_line(indent, &format!("# Subtest: {name}"))?;
+                    self.write_items(module_items, indent + 1, display_output)?;
+                    let status = if item.is_failure() { "not ok" } else { "ok" };
+                    self.write_line(indent, &format!("{status} {number} - {}", escape(name)))?;
+                }
+                TapItem::Test(test) => {
+                    self.write_test_point(test, item.is_failure(), number, indent, display_output)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_test_point(
+        &mut self,
+        test: &TapTest,
+        failed: bool,
+        number: usize,
+        indent: usize,
+        display_output: bool,
+    ) -> io::Result<()> {
+        let status = if failed { "not ok" } else { "ok" };
+        let directive = match test.result {
+            TestResult::TrIgnored => match test.desc.ignore_message {
+                Some(msg) => format!(" # SKIP {msg}"),
+                None => String::from(" # SKIP"),
+            },
+            _ => String::new(),
+        };
+        self.write_line(indent, &format!("{status} {number} - {}{directive}", escape(&test.name)))?;
+
+        // Everything else we know about the test goes into a YAML diagnostics block.
+        let mut diagnostics = Vec::new();
+        match test.result {
+            TestResult::TrFailedMsg(ref msg) => {
+                diagnostics.push(format!("message: \"{}\"", EscapedString(msg)));
+            }
+            TestResult::TrTimedFail => {
+                diagnostics.push(String::from("message: \"time limit exceeded\""));
+            }
+            TestResult::TrBench(ref bs) => {
+                diagnostics.push(format!("median_ns: {}", bs.ns_iter_summ.median));
+                diagnostics
+                    .push(format!("deviation_ns: {}", bs.ns_iter_summ.max - bs.ns_iter_summ.min));
+                if bs.mb_s != 0 {
+                    diagnostics.push(format!("mib_per_second: {}", bs.mb_s));
+                }
+            }
+            _ => {}
+        }
+        if let Some(exec_time) = &test.exec_time {
+            diagnostics.push(format!("duration_ms: {}", exec_time.0.as_secs_f64() * 1000.0));
+        }
+        let show_stdout = failed || (display_output && test.result == TestResult::TrOk);
+        if show_stdout && !test.stdout.is_empty() {
+            let stdout = String::from_utf8_lossy(&test.stdout);
+            // Use a literal block with an explicit indentation, so that leading whitespace in
+            // the output is preserved.
+            let chomping = if stdout.ends_with('\n') { "" } else { "-" };
+            diagnostics.push(format!("stdout: |2{chomping}"));
+            diagnostics.extend(stdout.lines().map(|line| format!("  {line}")));
+        }
+
+        if !diagnostics.is_empty() {
+            self.write_line(indent, "  ---")?;
+            for line in diagnostics {
+                self.write_line(indent, format!("  {line}").trim_end())?;
+            }
+            self.write_line(indent, "  ...")?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the characters that have a special meaning in the description of a test point.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+/// Adds `test` to `items`, inside the modules named by `module_path`.
+fn insert_test(items: &mut Vec<TapItem>, module_path: &[&str], test: TapTest) {
+    let Some((module, rest)) = module_path.split_first() else {
+        items.push(TapItem::Test(test));
+        return;
+    };
+    let existing = items
+        .iter()
+        .position(|item| matches!(item, TapItem::Module { name, .. } if name.as_str() == *module));
+    let index = existing.unwrap_or_else(|| {
+        items.push(TapItem::Module { name: String::from(*module), items: Vec::new() });
+        items.len() - 1
+    });
+    let TapItem::Module { items: module_items, .. } = &mut items[index] else { unreachable!() };
+    insert_test(module_items, rest, test);
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_run_start(
+        &mut self,
+        _test_count: usize,
+        _shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
+        self.write_line(0, "TAP version 14")
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test timeout.
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // The plan of each subtest needs to know how many tests the module contains, so we
+        // collect all results and write them once the run is complete.
+        let (module_path, name) = split_module_path(desc);
+        let module_path: Vec<&str> =
+            module_path.map(|p| p.split("::").collect()).unwrap_or_default();
+        let test = TapTest {
+            name: String::from(name),
+            desc: desc.clone(),
+            result: result.clone(),
+            exec_time: exec_time.cloned(),
+            stdout: stdout.to_vec(),
+        };
+        insert_test(&mut self.results, &module_path, test);
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let results = std::mem::take(&mut self.results);
+        self.write_items(&results, 0, state.options.display_output)?;
+        Ok(state.failed == 0)
+    }
+
+    fn write_merged_doctests_times(
+        &mut self,
+        total_time: f64,
+        compilation_time: f64,
+    ) -> io::Result<()> {
+        self.write_line(
+            0,
+            &format!("# total_time: {total_time}, compilation_time: {compilation_time}"),
+        )
+    }
+}
@@ -36,6 +36,10 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP version 14 output
+    Tap,
+    /// Common Test Report Format output
+    Ctrf,
 }
 
 /// Whether ignored test should be run or not
@@ -0,0 +1,184 @@
+//! Collection of the items referenced by function bodies, for `-Z json-call-graph`.
+//!
+//! Unlike the rest of the JSON output, this looks at the bodies of functions, so it needs the
+//! results of type checking them.
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_hir::def::{CtorOf, DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::{self as hir, HirId, QPath};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::{self, Instance, TyCtxt, TypeVisitableExt, TypeckResults};
+use rustc_span::Span;
+use rustdoc_json_types as types;
+
+use super::JsonRenderer;
+
+/// A reference found in a body, before it is converted to [`types::Reference`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum RawReference {
+    Call(DefId),
+    ResolvedTraitMethod { impl_method: DefId, trait_method: DefId },
+    Type(DefId),
+    Value(DefId),
+}
+
+struct ReferenceCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typeck_results: &'tcx TypeckResults<'tcx>,
+    references: FxIndexSet<RawReference>,
+}
+
+impl<'tcx> ReferenceCollector<'tcx> {
+    fn add_res(&mut self, res: Res, id: HirId) {
+        let Res::Def(kind, def_id) = res else { return };
+        let reference = match kind {
+            DefKind::Fn | DefKind::AssocFn => {
+                self.add_call(def_id, id);
+                return;
+            }
+            DefKind::Ctor(CtorOf::Struct, _) => RawReference::Type(self.tcx.parent(def_id)),
+            DefKind::Ctor(CtorOf::Variant, _) => RawReference::Value(self.tcx.parent(def_id)),
+            DefKind::Struct
+            | DefKind::Union
+            | DefKind::Enum
+            | DefKind::Trait
+            | DefKind::TraitAlias
+            | DefKind::TyAlias
+            | DefKind::ForeignTy
+            | DefKind::AssocTy => RawReference::Type(def_id),
+            DefKind::Variant | DefKind::Const | DefKind::AssocConst | DefKind::Static { .. } => {
+                RawReference::Value(def_id)
+            }
+            _ => return,
+        };
+        self.references.insert(reference);
+    }
+
+    /// Records a call to `def_id` by the expression `id`, and the impl method it resolves to if
+    /// it is a trait method.
+    fn add_call(&mut self, def_id: DefId, id: HirId) {
+        self.references.insert(RawReference::Call(def_id));
+
+        let tcx = self.tcx;
+        if tcx.trait_of_assoc(def_id).is_none() {
+            return;
+        }
+        let Some(args) = self.typeck_results.node_args_opt(id) else { return };
+        // We can only know which impl gets called if the arguments don't depend on the generic
+        // parameters of the function.
+        if args.has_non_region_param() || args.has_infer() || args.references_error() {
+            return;
+        }
+        let args = tcx.erase_regions(args);
+        if let Ok(Some(instance)) =
+            Instance::try_resolve(tcx, ty::TypingEnv::fully_monomorphized(), def_id, args)
+            && let ty::InstanceKind::Item(impl_method) = instance.def
+            && impl_method != def_id
+        {
+            self.references
+                .insert(RawReference::ResolvedTraitMethod { impl_method, trait_method: def_id });
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for ReferenceCollector<'tcx> {
+    // Closures and constants in the body are part of the function, but nested items are not.
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_nested_body(&mut self, body_id: hir::BodyId) {
+        let old_typeck_results = self.typeck_results;
+        self.typeck_results = self.tcx.typeck_body(body_id);
+        self.visit_body(self.tcx.hir_body(body_id));
+        self.typeck_results = old_typeck_results;
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx QPath<'tcx>, id: HirId, _span: Span) {
+        // This covers paths in expressions, patterns and types. Type-relative paths like
+        // `Vec::new` are resolved by type checking.
+        self.add_res(self.typeck_results.qpath_res(qpath, id), id);
+        intravisit::walk_qpath(self, qpath, id);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let hir::ExprKind::MethodCall(..) = expr.kind
+            && let Some(def_id) = self.typeck_results.type_dependent_def_id(expr.hir_id)
+        {
+            self.add_call(def_id, expr.hir_id);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+impl JsonRenderer<'_> {
+    /// Returns the items referenced by the body of the function `def_id`, if it has a body in the
+    /// local crate.
+    pub(super) fn function_references(&self, def_id: DefId) -> Option<Vec<types::Reference>> {
+        let tcx = self.tcx;
+        let body = tcx.hir_maybe_body_owned_by(def_id.as_local()?)?;
+        let typeck_results = tcx.typeck_body(body.id());
+        if typeck_results.tainted_by_errors.is_some() {
+            return None;
+        }
+
+        let mut collector =
+            ReferenceCollector { tcx, typeck_results, references: FxIndexSet::default() };
+        collector.visit_body(body);
+
+        let id = |def_id: DefId| self.id_from_item_default(def_id.into());
+        let references = collector
+            .references
+            .into_iter()
+            .map(|reference| match reference {
+                RawReference::Call(def_id) => {
+                    types::Reference { id: id(def_id), kind: types::ReferenceKind::Call }
+                }
+                RawReference::ResolvedTraitMethod { impl_method, trait_method } => {
+                    types::Reference {
+                        id: id(impl_method),
+                        kind: types::ReferenceKind::ResolvedTraitMethod {
+                            trait_method: id(trait_method),
+                        },
+                    }
+                }
+                RawReference::Type(def_id) => {
+                    types::Reference { id: id(def_id), kind: types::ReferenceKind::Type }
+                }
+                RawReference::Value(def_id) => {
+                    types::Reference { id: id(def_id), kind: types::ReferenceKind::Value }
+                }
+            })
+            .collect();
+        Some(references)
+    }
+}
+
+/// Removes the references to items that are not part of the output, so that every ID in
+/// [`types::Function::references`] can be looked up.
+pub(super) fn retain_known_references(
+    index: &mut types::FxHashMap<types::Id, types::Item>,
+    paths: &types::FxHashMap<types::Id, types::ItemSummary>,
+) {
+    let known_ids: FxHashSet<types::Id> = index.keys().chain(paths.keys()).copied().collect();
+    for item in index.values_mut() {
+        let types::ItemEnum::Function(types::Function { references: Some(references), .. }) =
+            &mut item.inner
+        else {
+            continue;
+        };
+        references.retain(|reference| {
+            known_ids.contains(&reference.id)
+                && match &reference.kind {
+                    types::ReferenceKind::ResolvedTraitMethod { trait_method } => {
+                        known_ids.contains(trait_method)
+                    }
+                    _ => true,
+                }
+        });
+    }
+}
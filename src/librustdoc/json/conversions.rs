@@ -281,20 +281,36 @@ fn from_clean_item(item: &clean::Item, renderer: &JsonRenderer<'_>) -> ItemEnum
         StructFieldItem(f) => ItemEnum::StructField(f.into_json(renderer)),
         EnumItem(e) => ItemEnum::Enum(e.into_json(renderer)),
         VariantItem(v) => ItemEnum::Variant(v.into_json(renderer)),
-        FunctionItem(f) => {
-            ItemEnum::Function(from_clean_function(f, true, header.unwrap(), renderer))
-        }
-        ForeignFunctionItem(f, _) => {
-            ItemEnum::Function(from_clean_function(f, false, header.unwrap(), renderer))
-        }
+        FunctionItem(f) => ItemEnum::Function(from_clean_function(
+            f,
+            true,
+            header.unwrap(),
+            item.item_id,
+            renderer,
+        )),
+        ForeignFunctionItem(f, _) => ItemEnum::Function(from_clean_function(
+            f,
+            false,
+            header.unwrap(),
+            item.item_id,
+            renderer,
+        )),
         TraitItem(t) => ItemEnum::Trait(t.into_json(renderer)),
         TraitAliasItem(t) => ItemEnum::TraitAlias(t.into_json(renderer)),
-        MethodItem(m, _) => {
-            ItemEnum::Function(from_clean_function(m, true, header.unwrap(), renderer))
-        }
-        RequiredMethodItem(m) => {
-            ItemEnum::Function(from_clean_function(m, false, header.unwrap(), renderer))
-        }
+        MethodItem(m, _) => ItemEnum::Function(from_clean_function(
+            m,
+            true,
+            header.unwrap(),
+            item.item_id,
+            renderer,
+        )),
+        RequiredMethodItem(m) => ItemEnum::Function(from_clean_function(
+            m,
+            false,
+            header.unwrap(),
+            item.item_id,
+            renderer,
+        )),
         ImplItem(i) => ItemEnum::Impl(i.into_json(renderer)),
         StaticItem(s) => ItemEnum::Static(from_clean_static(s, rustc_hir::Safety::Safe, renderer)),
         ForeignStaticItem(s, safety) => ItemEnum::Static(from_clean_static(s, *safety, renderer)),
@@ -739,13 +755,20 @@ pub(crate) fn from_clean_function(
     clean::Function { decl, generics }: &clean::Function,
     has_body: bool,
     header: rustc_hir::FnHeader,
+    item_id: ItemId,
     renderer: &JsonRenderer<'_>,
 ) -> Function {
+    let references = if has_body && renderer.tcx.sess.opts.unstable_opts.json_call_graph {
+        item_id.as_def_id().and_then(|def_id| renderer.function_references(def_id))
+    } else {
+        None
+    };
     Function {
         sig: decl.into_json(renderer),
         generics: generics.into_json(renderer),
         header: header.into_json(renderer),
         has_body,
+        references,
     }
 }
 
@@ -4,6 +4,7 @@
 //! output. See [the RFC](https://github.com/rust-lang/rfcs/pull/2963) and the [`types`] module
 //! docs for usage and details.
 
+mod call_graph;
 mod conversions;
 mod ids;
 mod import_finder;
@@ -309,7 +310,7 @@ fn after_krate(mut self) -> Result<(), Error> {
         let e = ExternalCrate { crate_num: LOCAL_CRATE };
 
         // We've finished using the index, and don't want to clone it, because it is big.
-        let index = std::mem::take(&mut self.index);
+        let mut index = std::mem::take(&mut self.index);
 
         // Note that tcx.rust_target_features is inappropriate here because rustdoc tries to run for
         // multiple targets: https://github.com/rust-lang/rust/pull/137632
@@ -317,28 +318,34 @@ fn after_krate(mut self) -> Result<(), Error> {
         // We want to describe a single target, so pass tcx.sess rather than tcx.
         let target = target(self.tcx.sess);
 
+        let paths: FxHashMap<_, _> = self
+            .cache
+            .paths
+            .iter()
+            .chain(&self.cache.external_paths)
+            .map(|(&k, &(ref path, kind))| {
+                (
+                    self.id_from_item_default(k.into()),
+                    types::ItemSummary {
+                        crate_id: k.krate.as_u32(),
+                        path: path.iter().map(|s| s.to_string()).collect(),
+                        kind: kind.into_json(&self),
+                    },
+                )
+            })
+            .collect();
+
+        if self.tcx.sess.opts.unstable_opts.json_call_graph {
+            call_graph::retain_known_references(&mut index, &paths);
+        }
+
         debug!("Constructing Output");
         let output_crate = types::Crate {
             root: self.id_from_item_default(e.def_id().into()),
             crate_version: self.cache.crate_version.clone(),
             includes_private: self.cache.document_private,
             index,
-            paths: self
-                .cache
-                .paths
-                .iter()
-                .chain(&self.cache.external_paths)
-                .map(|(&k, &(ref path, kind))| {
-                    (
-                        self.id_from_item_default(k.into()),
-                        types::ItemSummary {
-                            crate_id: k.krate.as_u32(),
-                            path: path.iter().map(|s| s.to_string()).collect(),
-                            kind: kind.into_json(&self),
-                        },
-                    )
-                })
-                .collect(),
+            paths,
             external_crates: self
                 .cache
                 .extern_locations
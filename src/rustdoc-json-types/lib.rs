@@ -37,8 +37,8 @@
 // will instead cause conflicts. See #94591 for more. (This paragraph and the "Latest feature" line
 // are deliberately not in a doc comment, because they need not be in public docs.)
 //
-// Latest feature: Add Attribute::MacroUse
-pub const FORMAT_VERSION: u32 = 55;
+// Latest feature: Add Function::references
+pub const FORMAT_VERSION: u32 = 56;
 
 /// The root of the emitted JSON blob.
 ///
@@ -896,6 +896,44 @@ pub struct Function {
     pub header: FunctionHeader,
     /// Whether the function has a body, i.e. an implementation.
     pub has_body: bool,
+    /// The items referenced from the body of the function, in the order they first appear.
+    ///
+    /// This is only recorded when rustdoc is passed `-Z json-call-graph`, and only for functions
+    /// whose body is defined in the local crate. It is `None` otherwise. References to items that
+    /// are in neither [`Crate::index`] nor [`Crate::paths`] are omitted.
+    pub references: Option<Vec<Reference>>,
+}
+
+/// An item referenced from the body of a [`Function`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Reference {
+    /// The ID of the referenced item.
+    pub id: Id,
+    /// How the item is used by the function.
+    pub kind: ReferenceKind,
+}
+
+/// The way in which a [`Reference`] is used by the function body it appears in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    /// The item is a function or method, which is either called or used as a value.
+    ///
+    /// For trait methods, this is the method as declared in the trait.
+    Call,
+    /// The item is the method of an impl that a call to a trait method resolves to.
+    ///
+    /// This is only recorded when the implementing type is fully known inside the body, i.e.
+    /// it does not depend on the generic parameters of the function.
+    ResolvedTraitMethod {
+        /// The ID of the method as declared in the trait.
+        trait_method: Id,
+    },
+    /// The item is a type, trait, or type alias named in the body, or a struct whose constructor
+    /// is used.
+    Type,
+    /// The item is a constant, static, or enum variant.
+    Value,
 }
 
 /// Generic parameters accepted by an item and `where` clauses imposed on it and the parameters.
@@ -121,6 +121,16 @@ pub enum FailMode {
     Run(RunFailMode),
 }
 
+string_enum! {
+    /// How much of the message text `//@ snapshot` tests compare.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum SnapshotMessages {
+        Exact => "exact",
+        FirstLine => "first-line",
+        Ignore => "ignore",
+    }
+}
+
 string_enum! {
     #[derive(Clone, Debug, PartialEq)]
     pub enum CompareMode {
@@ -1233,6 +1243,7 @@ pub fn expected_output_path(
     UI_STDERR_16,
     UI_COVERAGE,
     UI_COVERAGE_MAP,
+    UI_SNAPSHOT,
 ];
 pub const UI_STDERR: &str = "stderr";
 pub const UI_SVG: &str = "svg";
@@ -1246,6 +1257,7 @@ pub fn expected_output_path(
 pub const UI_STDERR_16: &str = "16bit.stderr";
 pub const UI_COVERAGE: &str = "coverage";
 pub const UI_COVERAGE_MAP: &str = "cov-map";
+pub const UI_SNAPSHOT: &str = "snap";
 
 /// Absolute path to the directory where all output for all tests in the given `relative_dir` group
 /// should reside. Example:
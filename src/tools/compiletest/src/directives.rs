@@ -9,7 +9,9 @@
 use semver::Version;
 use tracing::*;
 
-use crate::common::{CodegenBackend, Config, Debugger, FailMode, PassMode, RunFailMode, TestMode};
+use crate::common::{
+    CodegenBackend, Config, Debugger, FailMode, PassMode, RunFailMode, SnapshotMessages, TestMode,
+};
 use crate::debuggers::{extract_cdb_version, extract_gdb_version};
 use crate::directives::auxiliary::{AuxProps, parse_and_update_aux};
 use crate::directives::directive_names::{
@@ -179,6 +181,9 @@ pub struct TestProps {
     pub run_rustfix: bool,
     // If true, `rustfix` will only apply `MachineApplicable` suggestions.
     pub rustfix_only_machine_applicable: bool,
+    /// Compare a structural snapshot of the JSON diagnostics (`.snap`) instead of the rendered
+    /// stderr, with the given tolerance for message text.
+    pub snapshot: Option<SnapshotMessages>,
     pub assembly_output: Option<String>,
     // If true, the test is expected to ICE
     pub should_ice: bool,
@@ -242,6 +247,7 @@ mod directives {
     pub const RUN_RUSTFIX: &'static str = "run-rustfix";
     pub const RUSTFIX_ONLY_MACHINE_APPLICABLE: &'static str = "rustfix-only-machine-applicable";
     pub const ASSEMBLY_OUTPUT: &'static str = "assembly-output";
+    pub const SNAPSHOT: &'static str = "snapshot";
     pub const STDERR_PER_BITWIDTH: &'static str = "stderr-per-bitwidth";
     pub const INCREMENTAL: &'static str = "incremental";
     pub const KNOWN_BUG: &'static str = "known-bug";
@@ -298,6 +304,7 @@ pub fn new() -> Self {
             dont_check_failure_status: false,
             run_rustfix: false,
             rustfix_only_machine_applicable: false,
+            snapshot: None,
             assembly_output: None,
             should_ice: false,
             stderr_per_bitwidth: false,
@@ -576,6 +583,20 @@ fn split_flags(flags: &str) -> Vec<String> {
                         RUSTFIX_ONLY_MACHINE_APPLICABLE,
                         &mut self.rustfix_only_machine_applicable,
                     );
+
+                    if let Some(messages) =
+                        config.parse_name_value_directive(ln, SNAPSHOT, testfile, line_number)
+                    {
+                        let messages = messages.trim();
+                        self.snapshot = Some(messages.parse().unwrap_or_else(|_| {
+                            panic!(
+                                "Invalid snapshot value: {messages}\nExpected one of {:?}.",
+                                SnapshotMessages::STR_VARIANTS
+                            )
+                        }));
+                    } else if config.parse_name_directive(ln, SNAPSHOT) {
+                        self.snapshot = Some(SnapshotMessages::Exact);
+                    }
                     config.set_name_value_directive(
                         ln,
                         ASSEMBLY_OUTPUT,
@@ -257,6 +257,7 @@
     "rustfix-only-machine-applicable",
     "should-fail",
     "should-ice",
+    "snapshot",
     "stderr-per-bitwidth",
     "test-mir-pass",
     "unique-doc-out-dir",
@@ -1,11 +1,13 @@
 //! These structs are a subset of the ones found in `rustc_errors::json`.
 
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use regex::Regex;
 use serde::Deserialize;
 
+use crate::common::SnapshotMessages;
 use crate::errors::{Error, ErrorKind};
 
 #[derive(Deserialize)]
@@ -36,10 +38,13 @@ struct UnusedExternNotification {
 struct DiagnosticSpan {
     file_name: String,
     line_start: usize,
+    line_end: usize,
     column_start: usize,
+    column_end: usize,
     is_primary: bool,
     label: Option<String>,
     suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
 
@@ -137,6 +142,104 @@ pub fn extract_rendered(output: &str) -> String {
         .collect()
 }
 
+/// Renders the diagnostics in `output` as the structural snapshot compared by `//@ snapshot`
+/// tests.
+///
+/// Each diagnostic becomes a `level[code]: message` line, followed by one indented line per span,
+/// suggestion and child diagnostic. Everything after the first `: ` of a line is message text
+/// which tests may choose to ignore (see [`mask_snapshot_messages`]); levels, codes, span
+/// positions and suggested replacements are always compared. Lines that aren't diagnostics, as
+/// well as the "aborting due to" and "warnings emitted" summaries, are left out.
+pub fn render_snapshot(output: &str) -> String {
+    let mut snapshot = String::new();
+    for line in output.lines().filter(|line| line.starts_with('{')) {
+        let Ok(diagnostic) = serde_json::from_str::<Diagnostic>(line) else { continue };
+        if !is_summary(&diagnostic) {
+            push_snapshot(&mut snapshot, &diagnostic, 0);
+        }
+    }
+    snapshot
+}
+
+fn is_summary(diagnostic: &Diagnostic) -> bool {
+    let message = diagnostic.message.as_str();
+    diagnostic.level == "failure-note"
+        || (diagnostic.spans.is_empty()
+            && diagnostic.children.is_empty()
+            && (message.starts_with("aborting due to")
+                || message.ends_with("warning emitted")
+                || message.ends_with("warnings emitted")))
+}
+
+fn push_snapshot(snapshot: &mut String, diagnostic: &Diagnostic, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let header = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", diagnostic.level, code.code),
+        None => diagnostic.level.clone(),
+    };
+    push_snapshot_line(snapshot, &indent, &header, Some(&diagnostic.message));
+
+    let indent = "  ".repeat(depth + 1);
+    for span in &diagnostic.spans {
+        let location = format!(
+            "{}:{}:{}-{}:{}",
+            span.file_name, span.line_start, span.column_start, span.line_end, span.column_end
+        );
+        match &span.suggested_replacement {
+            Some(replacement) => {
+                let applicability =
+                    span.suggestion_applicability.as_deref().unwrap_or("Unspecified");
+                writeln!(snapshot, "{indent}suggestion {applicability} {location} {replacement:?}")
+                    .unwrap();
+            }
+            None => {
+                let kind = if span.is_primary { "primary" } else { "span" };
+                push_snapshot_line(
+                    snapshot,
+                    &indent,
+                    &format!("{kind} {location}"),
+                    span.label.as_deref(),
+                );
+            }
+        }
+    }
+    for child in &diagnostic.children {
+        push_snapshot(snapshot, child, depth + 1);
+    }
+}
+
+fn push_snapshot_line(snapshot: &mut String, indent: &str, key: &str, message: Option<&str>) {
+    match message.filter(|message| !message.is_empty()) {
+        Some(message) => {
+            writeln!(snapshot, "{indent}{key}: {}", message.replace('\n', "\\n")).unwrap()
+        }
+        None => writeln!(snapshot, "{indent}{key}").unwrap(),
+    }
+}
+
+/// Strips the message text that `messages` says to ignore from a snapshot produced by
+/// [`render_snapshot`], so that two snapshots can be compared with that tolerance.
+pub fn mask_snapshot_messages(snapshot: &str, messages: SnapshotMessages) -> String {
+    snapshot
+        .lines()
+        .map(|line| {
+            let masked = match line.split_once(": ") {
+                // Suggested replacements are compared verbatim, whatever the tolerance.
+                _ if line.trim_start().starts_with("suggestion ") => line,
+                None => line,
+                Some((key, message)) => match messages {
+                    SnapshotMessages::Exact => line,
+                    SnapshotMessages::FirstLine => {
+                        &line[..key.len() + 2 + message.find("\\n").unwrap_or(message.len())]
+                    }
+                    SnapshotMessages::Ignore => key,
+                },
+            };
+            format!("{masked}\n")
+        })
+        .collect()
+}
+
 pub fn parse_output(file_name: &str, output: &str) -> Vec<Error> {
     let mut errors = Vec::new();
     for line in output.lines() {
@@ -2439,10 +2439,26 @@ fn load_compare_outputs(
                     }
                 }
                 if !self.props.dont_check_compiler_stderr {
-                    if self
-                        .compare_output(stderr_kind, &normalized_stderr, &stderr, &expected_stderr)
-                        .should_error()
-                    {
+                    let outcome = match self.props.snapshot {
+                        Some(messages) => {
+                            // The snapshot replaces the rendered stderr, so make sure that
+                            // no stale `.stderr` file is left behind.
+                            if self
+                                .compare_output(stderr_kind, "", "", &expected_stderr)
+                                .should_error()
+                            {
+                                errors += 1;
+                            }
+                            self.compare_snapshot(proc_res, messages, explicit_format)
+                        }
+                        None => self.compare_output(
+                            stderr_kind,
+                            &normalized_stderr,
+                            &stderr,
+                            &expected_stderr,
+                        ),
+                    };
+                    if outcome.should_error() {
                         errors += 1;
                     }
                 }
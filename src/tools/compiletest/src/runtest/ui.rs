@@ -6,9 +6,10 @@
 use tracing::debug;
 
 use super::{
-    AllowUnused, Emit, FailMode, LinkToAux, PassMode, RunFailMode, RunResult, TargetLocation,
-    TestCx, TestOutput, Truncated, UI_FIXED, WillExecute,
+    AllowUnused, CompareOutcome, Emit, FailMode, LinkToAux, PassMode, ProcRes, RunFailMode,
+    RunResult, TargetLocation, TestCx, TestOutput, Truncated, UI_FIXED, WillExecute,
 };
+use crate::common::{SnapshotMessages, UI_SNAPSHOT};
 use crate::json;
 
 impl TestCx<'_> {
@@ -245,4 +246,32 @@ pub(super) fn run_ui_test(&self) {
             }
         }
     }
+
+    /// Compares the structural snapshot of the JSON diagnostics in `proc_res` against the
+    /// test's `.snap` file, ignoring the message text that `messages` says to ignore.
+    pub(super) fn compare_snapshot(
+        &self,
+        proc_res: &ProcRes,
+        messages: SnapshotMessages,
+        explicit_format: bool,
+    ) -> CompareOutcome {
+        if explicit_format {
+            self.fatal(
+                "`//@ snapshot` needs JSON diagnostics and can't be used with `--error-format`",
+            );
+        }
+
+        let expected = self.load_expected_output(UI_SNAPSHOT);
+        let snapshot = json::render_snapshot(&proc_res.stderr);
+        let normalized = self.normalize_output(&snapshot, &self.props.normalize_stderr);
+
+        // Message changes within the tolerance don't fail the test, but are still blessed so
+        // that the snapshot keeps showing the current text.
+        let within_tolerance = json::mask_snapshot_messages(&expected, messages)
+            == json::mask_snapshot_messages(&normalized, messages);
+        if within_tolerance && (!self.config.bless || expected == normalized) {
+            return CompareOutcome::Same;
+        }
+        self.compare_output(UI_SNAPSHOT, &normalized, &snapshot, &expected)
+    }
 }
@@ -1,5 +1,6 @@
+use crate::common::SnapshotMessages;
 use crate::debuggers::{extract_gdb_version, extract_lldb_version};
-use crate::is_test;
+use crate::{is_test, json};
 
 #[test]
 fn test_extract_gdb_version() {
@@ -87,3 +88,48 @@ enum Animal {
     let animal = "nya".parse::<Animal>();
     assert_eq!("unknown `Animal` variant: `nya`", animal.unwrap_err());
 }
+
+#[test]
+fn render_snapshot() {
+    let output = [
+        r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":null},"level":"error","spans":[{"file_name":"foo.rs","byte_start":30,"byte_end":35,"line_start":3,"line_end":3,"column_start":17,"column_end":22,"is_primary":true,"text":[],"label":"expected `u32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"try using a conversion method","code":null,"level":"help","spans":[{"file_name":"foo.rs","byte_start":35,"byte_end":35,"line_start":3,"line_end":3,"column_start":22,"column_end":22,"is_primary":true,"text":[],"label":null,"suggested_replacement":".parse()","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0308]: mismatched types\n"}"#,
+        r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}"#,
+        r#"{"$message_type":"diagnostic","message":"For more information about this error, try `rustc --explain E0308`.","code":null,"level":"failure-note","spans":[],"children":[],"rendered":"For more information about this error, try `rustc --explain E0308`.\n"}"#,
+    ]
+    .join("\n");
+
+    assert_eq!(
+        json::render_snapshot(&output),
+        "error[E0308]: mismatched types\n\
+         \x20 primary foo.rs:3:17-3:22: expected `u32`, found `&str`\n\
+         \x20 help: try using a conversion method\n\
+         \x20   suggestion MaybeIncorrect foo.rs:3:22-3:22 \".parse()\"\n"
+    );
+}
+
+#[test]
+fn mask_snapshot_messages() {
+    let snapshot = "error[E0599]: no method named `foo`\\nfound for `u8`\n\
+                    \x20 primary foo.rs:1:1-1:4: method not found\n\
+                    \x20 span foo.rs:2:1-2:4\n\
+                    \x20 help: there is a method: `bar`\n\
+                    \x20   suggestion MachineApplicable foo.rs:1:1-1:4 \"bar: u8\"\n";
+
+    assert_eq!(json::mask_snapshot_messages(snapshot, SnapshotMessages::Exact), snapshot);
+    assert_eq!(
+        json::mask_snapshot_messages(snapshot, SnapshotMessages::FirstLine),
+        "error[E0599]: no method named `foo`\n\
+         \x20 primary foo.rs:1:1-1:4: method not found\n\
+         \x20 span foo.rs:2:1-2:4\n\
+         \x20 help: there is a method: `bar`\n\
+         \x20   suggestion MachineApplicable foo.rs:1:1-1:4 \"bar: u8\"\n"
+    );
+    assert_eq!(
+        json::mask_snapshot_messages(snapshot, SnapshotMessages::Ignore),
+        "error[E0599]\n\
+         \x20 primary foo.rs:1:1-1:4\n\
+         \x20 span foo.rs:2:1-2:4\n\
+         \x20 help\n\
+         \x20   suggestion MachineApplicable foo.rs:1:1-1:4 \"bar: u8\"\n"
+    );
+}
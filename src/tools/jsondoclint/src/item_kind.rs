@@ -130,6 +130,9 @@ pub fn is_trait_or_alias(self) -> bool {
     pub fn is_type(self) -> bool {
         matches!(self, Kind::Struct | Kind::Enum | Kind::Union | Kind::TypeAlias)
     }
+    pub fn is_function(self) -> bool {
+        matches!(self, Kind::Function)
+    }
 
     pub fn from_item(i: &Item) -> Self {
         use Kind::*;
@@ -4,9 +4,9 @@
 use rustdoc_json_types::{
     AssocItemConstraint, AssocItemConstraintKind, Constant, Crate, DynTrait, Enum, Function,
     FunctionPointer, FunctionSignature, GenericArg, GenericArgs, GenericBound, GenericParamDef,
-    Generics, Id, Impl, ItemEnum, ItemSummary, Module, Path, Primitive, ProcMacro, Static, Struct,
-    StructKind, Term, Trait, TraitAlias, Type, TypeAlias, Union, Use, Variant, VariantKind,
-    WherePredicate,
+    Generics, Id, Impl, ItemEnum, ItemSummary, Module, Path, Primitive, ProcMacro, Reference,
+    ReferenceKind, Static, Struct, StructKind, Term, Trait, TraitAlias, Type, TypeAlias, Union,
+    Use, Variant, VariantKind, WherePredicate,
 };
 use serde_json::Value;
 
@@ -197,6 +197,18 @@ fn check_variant(&mut self, x: &'a Variant, id: &'a Id) {
     fn check_function(&mut self, x: &'a Function) {
         self.check_generics(&x.generics);
         self.check_function_signature(&x.sig);
+        x.references.iter().flatten().for_each(|r| self.check_reference(r));
+    }
+
+    fn check_reference(&mut self, x: &'a Reference) {
+        match &x.kind {
+            ReferenceKind::Call => self.add_function_id(&x.id),
+            ReferenceKind::ResolvedTraitMethod { trait_method } => {
+                self.add_function_id(&x.id);
+                self.add_function_id(trait_method);
+            }
+            ReferenceKind::Type | ReferenceKind::Value => self.add_any_id(&x.id),
+        }
     }
 
     fn check_trait(&mut self, x: &'a Trait, id: &Id) {
@@ -454,6 +466,10 @@ fn add_trait_or_alias_id(&mut self, id: &'a Id) {
         self.add_id_checked(id, Kind::is_trait_or_alias, "Trait (or TraitAlias)");
     }
 
+    fn add_function_id(&mut self, id: &'a Id) {
+        self.add_id_checked(id, Kind::is_function, "Function");
+    }
+
     fn add_type_id(&mut self, id: &'a Id) {
         self.add_id_checked(id, Kind::is_type, "Type (Struct, Enum, Union or TypeAlias)");
     }
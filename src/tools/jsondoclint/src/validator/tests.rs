@@ -209,6 +209,7 @@ fn errors_on_missing_path() {
                             abi: Abi::Rust,
                         },
                         has_body: true,
+                        references: None,
                     }),
                 },
             ),
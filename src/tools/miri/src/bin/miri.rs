@@ -39,7 +39,7 @@
 
 use miri::{
     BacktraceStyle, BorrowTrackerMethod, GenmcConfig, GenmcCtx, MiriConfig, MiriEntryFnType,
-    ProvenanceMode, RetagFields, TreeBorrowsParams, ValidationMode,
+    ProvenanceMode, ReplayTrace, RetagFields, TreeBorrowsParams, ValidationMode,
 };
 use rustc_abi::ExternAbi;
 use rustc_data_structures::sync;
@@ -652,6 +652,13 @@ fn main() {
             miri_config.gc_interval = interval;
         } else if let Some(param) = arg.strip_prefix("-Zmiri-measureme=") {
             miri_config.measureme_out = Some(param.to_string());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record=") {
+            miri_config.record_trace = Some(PathBuf::from(param));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay=") {
+            let trace = ReplayTrace::load(param.as_ref()).unwrap_or_else(|err| {
+                fatal_error!("-Zmiri-replay could not load trace `{param}`: {err}")
+            });
+            miri_config.replay_trace = Some(trace);
         } else if let Some(param) = arg.strip_prefix("-Zmiri-backtrace=") {
             miri_config.backtrace_style = match param {
                 "0" => BacktraceStyle::Off,
@@ -723,6 +730,32 @@ fn main() {
         fatal_error!("Only one of `-Zmiri-seed` and `-Zmiri-many-seeds can be set");
     }
 
+    // Recording and replaying work on a single execution.
+    if miri_config.record_trace.is_some() || miri_config.replay_trace.is_some() {
+        if miri_config.record_trace.is_some() && miri_config.replay_trace.is_some() {
+            fatal_error!("Only one of `-Zmiri-record` and `-Zmiri-replay` can be set");
+        }
+        if many_seeds.is_some() {
+            fatal_error!(
+                "`-Zmiri-record` and `-Zmiri-replay` cannot be used with `-Zmiri-many-seeds`"
+            );
+        }
+        if miri_config.genmc_config.is_some() {
+            fatal_error!("`-Zmiri-record` and `-Zmiri-replay` cannot be used in GenMC mode");
+        }
+    }
+    // The choices that are not in the trace are derived from its seed.
+    if let Some(trace) = &miri_config.replay_trace {
+        match miri_config.seed {
+            Some(seed) if seed != trace.seed =>
+                fatal_error!(
+                    "-Zmiri-seed={seed} does not match the seed {} of the replayed trace",
+                    trace.seed
+                ),
+            _ => miri_config.seed = Some(trace.seed),
+        }
+    }
+
     // Ensure we have parallelism for many-seeds mode.
     if many_seeds.is_some() && !rustc_args.iter().any(|arg| arg.starts_with("-Zthreads=")) {
         // Clamp to 20 threads; things get a less efficient beyond that due to lock contention.
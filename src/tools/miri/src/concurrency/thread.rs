@@ -110,6 +110,8 @@ pub enum BlockReason {
     Eventfd,
     /// Blocked on unnamed_socket.
     UnnamedSocket,
+    /// Blocked on a socket of the virtual network.
+    Socket,
 }
 
 /// The state of a thread.
@@ -755,12 +757,33 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
             .filter(|(_id, thread)| thread.state.is_enabled());
         // Pick a new thread, and switch to it.
         let new_thread = if thread_manager.fixed_scheduling {
-            threads_iter.next()
+            threads_iter.next().map(|(id, _thread)| id)
         } else {
-            threads_iter.choose(rng)
+            let new_thread = threads_iter.choose(rng).map(|(id, _thread)| id);
+            // This is a non-deterministic choice, record or replay it.
+            new_thread.map(|id| {
+                let is_enabled = |id| {
+                    this.machine
+                        .threads
+                        .threads
+                        .get(id)
+                        .is_some_and(|thread| thread.state.is_enabled())
+                };
+                this.trace_nondet(
+                    || id,
+                    |&id| TraceEvent::Schedule(id),
+                    |event| {
+                        match *event {
+                            TraceEvent::Schedule(id) if is_enabled(id) => Some(id),
+                            _ => None,
+                        }
+                    },
+                )
+            })
         };
 
-        if let Some((id, _thread)) = new_thread {
+        let thread_manager = &mut this.machine.threads;
+        if let Some(id) = new_thread {
             if thread_manager.active_thread != id {
                 info!(
                     "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
@@ -1206,9 +1229,9 @@ fn maybe_preempt_active_thread(&mut self) {
         use rand::Rng as _;
 
         let this = self.eval_context_mut();
-        if !this.machine.threads.fixed_scheduling
-            && this.machine.rng.get_mut().random_bool(this.machine.preemption_rate)
-        {
+        let preempt = !this.machine.threads.fixed_scheduling
+            && this.machine.rng.get_mut().random_bool(this.machine.preemption_rate);
+        if this.trace_preemption(preempt) {
             this.yield_active_thread();
         }
     }
@@ -273,6 +273,7 @@ fn buffered_read(
         thread_mgr: &ThreadManager<'_>,
         is_seqcst: bool,
         rng: &mut (impl rand::Rng + ?Sized),
+        trace: impl FnOnce(usize, &dyn Fn(usize) -> bool) -> usize,
         validate: impl FnOnce() -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Option<Scalar>, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -283,7 +284,7 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.active_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(is_seqcst, &clocks, &mut *rng, trace)
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -311,21 +312,48 @@ fn buffered_write(
     }
 
     /// Selects a valid store element in the buffer.
+    ///
+    /// The choice is passed to `trace` as the offset of the element from the latest store, together
+    /// with a function that checks whether another offset would be valid, so that it can be
+    /// recorded or replaced by a replayed one.
     fn fetch_store<R: rand::Rng + ?Sized>(
         &self,
         is_seqcst: bool,
         clocks: &ThreadClockSet,
         rng: &mut R,
+        trace: impl FnOnce(usize, &dyn Fn(usize) -> bool) -> usize,
     ) -> (&StoreElement, LoadRecency) {
         use rand::seq::IteratorRandom;
+        let chosen = self.load_candidates(is_seqcst, clocks).choose(rng);
+        let chosen = chosen.expect("store buffer cannot be empty");
+        let offset = self.buffer.iter().rev().position(|elem| std::ptr::eq(elem, chosen)).unwrap();
+        let offset = trace(offset, &|offset| {
+            offset < self.buffer.len()
+                && self
+                    .load_candidates(is_seqcst, clocks)
+                    .any(|elem| std::ptr::eq(elem, &self.buffer[self.buffer.len() - 1 - offset]))
+        });
+        if offset == 0 {
+            (&self.buffer[self.buffer.len() - 1], LoadRecency::Latest)
+        } else {
+            (&self.buffer[self.buffer.len() - 1 - offset], LoadRecency::Outdated)
+        }
+    }
+
+    /// The store elements in the buffer that a load can read from, from the latest one to the
+    /// oldest one.
+    fn load_candidates<'a>(
+        &'a self,
+        is_seqcst: bool,
+        clocks: &'a ThreadClockSet,
+    ) -> impl Iterator<Item = &'a StoreElement> {
         let mut found_sc = false;
         // FIXME: we want an inclusive take_while (stops after a false predicate, but
         // includes the element that gave the false), but such function doesn't yet
         // exist in the standard library https://github.com/rust-lang/rust/issues/62208
         // so we have to hack around it with keep_searching
         let mut keep_searching = true;
-        let candidates = self
-            .buffer
+        self.buffer
             .iter()
             .rev()
             .take_while(move |&store_elem| {
@@ -371,7 +399,7 @@ fn fetch_store<R: rand::Rng + ?Sized>(
 
                 true
             })
-            .filter(|&store_elem| {
+            .filter(move |&store_elem| {
                 if is_seqcst && store_elem.is_seqcst {
                     // An SC load needs to ignore all but last store maked SC (stores not marked SC are not
                     // affected)
@@ -381,14 +409,7 @@ fn fetch_store<R: rand::Rng + ?Sized>(
                 } else {
                     true
                 }
-            });
-
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
-        if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
-            (chosen, LoadRecency::Latest)
-        } else {
-            (chosen, LoadRecency::Outdated)
-        }
+            })
     }
 
     /// ATOMIC STORE IMPL in the paper (except we don't need the location's vector clock)
@@ -511,6 +532,19 @@ fn buffered_atomic_read(
                         &this.machine.threads,
                         atomic == AtomicReadOrd::SeqCst,
                         &mut *rng,
+                        |offset, is_valid| {
+                            this.trace_nondet(
+                                || offset,
+                                |&offset| TraceEvent::Load(offset),
+                                |event| {
+                                    match *event {
+                                        TraceEvent::Load(offset) if is_valid(offset) =>
+                                            Some(offset),
+                                        _ => None,
+                                    }
+                                },
+                            )
+                        },
                         validate,
                     )?;
                     if global.track_outdated_loads && recency == LoadRecency::Outdated {
@@ -139,6 +139,7 @@ pub enum NonHaltingDiagnostic {
         ptr: Pointer,
     },
     ExternTypeReborrow,
+    ReplayDivergence(ReplayDivergence),
 }
 
 /// Level of Miri specific diagnostics
@@ -633,6 +634,7 @@ pub fn emit_diagnostic(&self, e: NonHaltingDiagnostic) {
                 ("sharing memory with a native function".to_string(), DiagLevel::Warning),
             ExternTypeReborrow =>
                 ("reborrow of reference to `extern type`".to_string(), DiagLevel::Warning),
+            ReplayDivergence(..) => ("the replay diverged here".to_string(), DiagLevel::Warning),
             CreatedPointerTag(..)
             | PoppedPointerTag(..)
             | CreatedAlloc(..)
@@ -671,12 +673,24 @@ pub fn emit_diagnostic(&self, e: NonHaltingDiagnostic) {
                 format!("weak memory emulation: outdated value returned from load at {ptr}"),
             ExternTypeReborrow =>
                 format!("reborrow of a reference to `extern type` is not properly supported"),
+            ReplayDivergence(divergence) =>
+                format!("replay diverged from the trace at line {}", divergence.line),
         };
 
         let notes = match &e {
             ProgressReport { block_count } => {
                 vec![note!("so far, {block_count} basic blocks have been executed")]
             }
+            ReplayDivergence(divergence) => {
+                let expected = match &divergence.expected {
+                    Some(event) => format!("the trace contains {event}"),
+                    None => format!("the trace ended"),
+                };
+                vec![
+                    note!("{expected}, but the execution needed {}", divergence.found),
+                    note!("the execution continues with live choices from here on"),
+                ]
+            }
             _ => vec![],
         };
 
@@ -172,6 +172,10 @@ pub struct MiriConfig {
     pub float_nondet: bool,
     /// Whether floating-point operations can have a non-deterministic rounding error.
     pub float_rounding_error: bool,
+    /// If `Some`, record the non-deterministic choices of the execution to the given file.
+    pub record_trace: Option<PathBuf>,
+    /// If `Some`, take the non-deterministic choices of the execution from the given trace.
+    pub replay_trace: Option<ReplayTrace>,
 }
 
 impl Default for MiriConfig {
@@ -214,6 +218,8 @@ fn default() -> MiriConfig {
             force_intrinsic_fallback: false,
             float_nondet: true,
             float_rounding_error: true,
+            record_trace: None,
+            replay_trace: None,
         }
     }
 }
@@ -530,7 +536,10 @@ pub fn eval_entry<'tcx>(
     let Err(err) = res.report_err();
 
     // Show diagnostic, if any.
-    let (return_code, leak_check) = report_error(&ecx, err)?;
+    let res = report_error(&ecx, err);
+    // Write out the recorded trace even if the execution failed, that is when it is most useful.
+    ecx.machine.nondet_trace.finish(tcx);
+    let (return_code, leak_check) = res?;
 
     // We inform GenMC that the execution is complete.
     if let Some(genmc_ctx) = ecx.machine.data_race.as_genmc_ref()
@@ -428,6 +428,17 @@ fn gen_random(&mut self, ptr: Pointer, len: u64) -> InterpResult<'tcx> {
             let rng = this.machine.rng.get_mut();
             rng.fill_bytes(&mut data);
         }
+        let len = data.len();
+        let data = this.trace_nondet(
+            || data,
+            |data| TraceEvent::Random(data.clone()),
+            |event| {
+                match event {
+                    TraceEvent::Random(bytes) if bytes.len() == len => Some(bytes.clone()),
+                    _ => None,
+                }
+            },
+        );
 
         this.write_bytes_ptr(ptr, data.iter().copied())
     }
@@ -88,6 +88,7 @@
 mod math;
 mod operator;
 mod provenance_gc;
+mod record_replay;
 mod shims;
 
 // Establish a "crate-wide prelude": we often import `crate::*`.
@@ -152,6 +153,9 @@ pub mod native_lib {
 };
 pub use crate::operator::EvalContextExt as _;
 pub use crate::provenance_gc::{EvalContextExt as _, LiveAllocs, VisitProvenance, VisitWith};
+pub use crate::record_replay::{
+    EvalContextExt as _, NondetTrace, ReplayDivergence, ReplayTrace, TraceEvent,
+};
 pub use crate::shims::EmulateItemResult;
 pub use crate::shims::env::{EnvVars, EvalContextExt as _};
 pub use crate::shims::foreign_items::{DynSym, EvalContextExt as _};
@@ -473,9 +473,15 @@ pub struct MiriMachine<'tcx> {
     /// The list of all EpollEventInterest.
     pub(crate) epoll_interests: shims::EpollInterestTable,
 
+    /// The loopback network that IPv4 and IPv6 sockets are attached to.
+    pub(crate) network: shims::VirtualNetwork,
+
     /// This machine's monotone clock.
     pub(crate) monotonic_clock: MonotonicClock,
 
+    /// The state of `-Zmiri-record` and `-Zmiri-replay`.
+    pub(crate) nondet_trace: NondetTrace,
+
     /// The set of threads.
     pub(crate) threads: ThreadManager<'tcx>,
 
@@ -627,6 +633,10 @@ pub(crate) fn new(
             measureme::Profiler::new(path).expect("Couldn't create `measureme` profiler")
         });
         let rng = StdRng::seed_from_u64(config.seed.unwrap_or(0));
+        let nondet_trace = NondetTrace::new(config).unwrap_or_else(|err| {
+            let path = config.record_trace.as_deref().unwrap();
+            tcx.dcx().fatal(format!("failed to create trace `{}`: {err}", path.display()))
+        });
         let borrow_tracker = config.borrow_tracker.map(|bt| bt.instantiate_global_state(config));
         let data_race = if config.genmc_config.is_some() {
             // `genmc_ctx` persists across executions, so we don't create a new one here.
@@ -690,6 +700,7 @@ pub(crate) fn new(
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             epoll_interests: shims::EpollInterestTable::new(),
+            network: Default::default(),
             dirs: Default::default(),
             layouts,
             threads,
@@ -713,6 +724,7 @@ pub(crate) fn new(
             report_progress: config.report_progress,
             basic_block_count: 0,
             monotonic_clock: MonotonicClock::new(config.isolated_op == IsolatedOp::Allow),
+            nondet_trace,
             #[cfg(all(unix, feature = "native-lib"))]
             native_lib: config.native_lib.iter().map(|lib_file_path| {
                 let host_triple = rustc_session::config::host_tuple();
@@ -894,10 +906,12 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             alloc_addresses,
             fds,
             epoll_interests:_,
+            network: _,
             tcx: _,
             isolated_op: _,
             validation: _,
             monotonic_clock: _,
+            nondet_trace: _,
             layouts: _,
             static_roots: _,
             profiler: _,
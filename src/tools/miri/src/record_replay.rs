@@ -0,0 +1,358 @@
+//! Recording and replaying the non-deterministic choices of an execution.
+//!
+//! With `-Zmiri-record=<file>`, the choices Miri makes on behalf of the interpreted program are
+//! written to a trace file: which thread the scheduler switches to, when the active thread is
+//! preempted, which store a weak memory load reads from, and the random bytes returned to the
+//! program. When isolation is disabled, the inputs the program gets from the host are recorded
+//! as well: the current time, the environment, and the data read from files and stdin.
+//!
+//! With `-Zmiri-replay=<file>`, these choices are taken from the trace instead, so an execution
+//! found with `-Zmiri-many-seeds` can be reproduced without the host it ran on. Choices that are
+//! not in the trace (e.g. the addresses of allocations) are still derived from the seed, which is
+//! stored in the trace. When the execution needs a choice that does not match the next event of
+//! the trace, for example because the program was changed, Miri reports that the replay diverged
+//! and continues with live choices from then on.
+//!
+//! Timeouts (e.g. of `sleep` or `pthread_cond_timedwait`) are still measured with the live clock
+//! of the host, so executions that depend on them can diverge when isolation is disabled.
+//!
+//! The trace is a text file. Its first line contains the seed, and each following line contains
+//! one event, see [`TraceEvent`]. Byte strings are written in hexadecimal.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rustc_middle::ty::TyCtxt;
+
+use crate::*;
+
+const HEADER: &str = "miri-trace seed=";
+
+/// A non-deterministic choice of an execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// `schedule <thread>`: the scheduler switched to the given thread.
+    Schedule(ThreadId),
+    /// `preempt <blocks>`: the active thread was preempted after executing this many basic blocks
+    /// since the previous event.
+    Preempt(u64),
+    /// `load <offset>`: a weak memory load read from the store that is this many stores older
+    /// than the latest one in modification order.
+    Load(usize),
+    /// `random <bytes>`: random bytes were generated for the program.
+    Random(Vec<u8>),
+    /// `realtime <nanos>`: the system time of the host, in nanoseconds since the Unix epoch.
+    RealTime(Duration),
+    /// `monotonic <nanos>`: the monotonic time of the host, in nanoseconds since Miri started.
+    MonotonicTime(Duration),
+    /// `env <name>=<value> ...`: the environment variables that were forwarded from the host.
+    Env(Vec<(Vec<u8>, Vec<u8>)>),
+    /// `read <bytes>` or `read-error <code>`: data read from a host file, or the OS error code of
+    /// a failed read (`?` if the error has no code).
+    Read(Result<Vec<u8>, Option<i32>>),
+}
+
+impl TraceEvent {
+    fn write(&self, out: &mut impl io::Write) -> io::Result<()> {
+        match self {
+            TraceEvent::Schedule(thread) => writeln!(out, "schedule {}", thread.to_u32()),
+            TraceEvent::Preempt(blocks) => writeln!(out, "preempt {blocks}"),
+            TraceEvent::Load(offset) => writeln!(out, "load {offset}"),
+            TraceEvent::Random(bytes) => writeln!(out, "random {}", to_hex(bytes)),
+            TraceEvent::RealTime(time) => writeln!(out, "realtime {}", time.as_nanos()),
+            TraceEvent::MonotonicTime(time) => writeln!(out, "monotonic {}", time.as_nanos()),
+            TraceEvent::Env(vars) => {
+                write!(out, "env")?;
+                for (name, value) in vars {
+                    write!(out, " {}={}", to_hex(name), to_hex(value))?;
+                }
+                writeln!(out)
+            }
+            TraceEvent::Read(Ok(bytes)) => writeln!(out, "read {}", to_hex(bytes)),
+            TraceEvent::Read(Err(Some(code))) => writeln!(out, "read-error {code}"),
+            TraceEvent::Read(Err(None)) => writeln!(out, "read-error ?"),
+        }
+    }
+
+    fn parse(line: &str) -> Option<TraceEvent> {
+        let (kind, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let nanos = |arg: &str| {
+            let nanos = arg.parse::<u128>().ok()?;
+            let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+            Some(Duration::new(secs, u32::try_from(nanos % 1_000_000_000).unwrap()))
+        };
+        Some(match kind {
+            "schedule" => TraceEvent::Schedule(ThreadId::new_unchecked(arg.parse().ok()?)),
+            "preempt" => TraceEvent::Preempt(arg.parse().ok()?),
+            "load" => TraceEvent::Load(arg.parse().ok()?),
+            "random" => TraceEvent::Random(from_hex(arg)?),
+            "realtime" => TraceEvent::RealTime(nanos(arg)?),
+            "monotonic" => TraceEvent::MonotonicTime(nanos(arg)?),
+            "env" => {
+                let var = |var: &str| {
+                    let (name, value) = var.split_once('=')?;
+                    Some((from_hex(name)?, from_hex(value)?))
+                };
+                TraceEvent::Env(arg.split_whitespace().map(var).collect::<Option<_>>()?)
+            }
+            "read" => TraceEvent::Read(Ok(from_hex(arg)?)),
+            "read-error" if arg == "?" => TraceEvent::Read(Err(None)),
+            "read-error" => TraceEvent::Read(Err(Some(arg.parse().ok()?))),
+            _ => return None,
+        })
+    }
+
+    /// A short description of the event for diagnostics, which leaves out the bytes.
+    fn describe(&self) -> String {
+        match self {
+            TraceEvent::Random(bytes) => format!("`random` ({} bytes)", bytes.len()),
+            TraceEvent::Env(vars) => format!("`env` ({} variables)", vars.len()),
+            TraceEvent::Read(Ok(bytes)) => format!("`read` ({} bytes)", bytes.len()),
+            _ => {
+                let mut line = Vec::new();
+                self.write(&mut line).unwrap();
+                format!("`{}`", String::from_utf8(line).unwrap().trim_end())
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{byte:02x}").unwrap();
+    }
+    hex
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// A trace loaded with `-Zmiri-replay`.
+#[derive(Clone, Debug)]
+pub struct ReplayTrace {
+    pub seed: u64,
+    events: Vec<TraceEvent>,
+}
+
+impl ReplayTrace {
+    pub fn load(path: &Path) -> Result<ReplayTrace, String> {
+        let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut lines = contents.lines();
+        let seed = lines
+            .next()
+            .and_then(|header| header.strip_prefix(HEADER))
+            .and_then(|seed| seed.parse().ok())
+            .ok_or_else(|| "not a Miri trace file".to_string())?;
+        let events = lines
+            .enumerate()
+            .map(|(i, line)| {
+                // The header is line 1.
+                TraceEvent::parse(line).ok_or_else(|| format!("invalid event on line {}", i + 2))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ReplayTrace { seed, events })
+    }
+}
+
+/// The replay left the trace: the execution needed a choice that does not match the next event.
+#[derive(Debug)]
+pub struct ReplayDivergence {
+    /// The line of the trace that could not be replayed.
+    pub line: usize,
+    /// The event on that line, or `None` if the trace ended.
+    pub expected: Option<String>,
+    /// The choice the execution made instead.
+    pub found: String,
+}
+
+enum Mode {
+    Off,
+    Record {
+        out: BufWriter<File>,
+        path: PathBuf,
+        /// The first error that happened while writing the trace. Recording stops at that point.
+        error: Option<io::Error>,
+    },
+    Replay {
+        events: VecDeque<TraceEvent>,
+        /// The line of the next event.
+        line: usize,
+    },
+}
+
+/// The per-machine state of `-Zmiri-record` and `-Zmiri-replay`.
+pub struct NondetTrace {
+    mode: RefCell<Mode>,
+    /// The number of basic blocks executed since the previous event, for [`TraceEvent::Preempt`].
+    blocks: Cell<u64>,
+}
+
+impl NondetTrace {
+    pub fn new(config: &MiriConfig) -> io::Result<NondetTrace> {
+        let mode = if let Some(path) = &config.record_trace {
+            let mut out = BufWriter::new(File::create(path)?);
+            writeln!(out, "{HEADER}{}", config.seed.unwrap_or(0))?;
+            Mode::Record { out, path: path.clone(), error: None }
+        } else if let Some(trace) = &config.replay_trace {
+            Mode::Replay { events: trace.events.iter().cloned().collect(), line: 2 }
+        } else {
+            Mode::Off
+        };
+        Ok(NondetTrace { mode: RefCell::new(mode), blocks: Cell::new(0) })
+    }
+
+    /// Returns the choice `live` made by the execution, after recording it, or the choice of the
+    /// next event of the replayed trace. `replay` returns `None` if the event does not apply
+    /// to this choice, in which case the replay diverges and the live choice is used.
+    fn choose<T>(
+        &self,
+        live: impl FnOnce() -> T,
+        record: impl FnOnce(&T) -> TraceEvent,
+        replay: impl FnOnce(&TraceEvent) -> Option<T>,
+    ) -> (T, Option<ReplayDivergence>) {
+        let mut mode = self.mode.borrow_mut();
+        match &mut *mode {
+            Mode::Off => (live(), None),
+            Mode::Record { out, error, .. } => {
+                let value = live();
+                if error.is_none() {
+                    self.blocks.set(0);
+                    if let Err(err) = record(&value).write(out) {
+                        *error = Some(err);
+                    }
+                }
+                (value, None)
+            }
+            Mode::Replay { events, line } => {
+                if let Some(value) = events.front().and_then(replay) {
+                    events.pop_front();
+                    *line += 1;
+                    self.blocks.set(0);
+                    return (value, None);
+                }
+                let value = live();
+                let divergence = ReplayDivergence {
+                    line: *line,
+                    expected: events.front().map(TraceEvent::describe),
+                    found: record(&value).describe(),
+                };
+                *mode = Mode::Off;
+                (value, Some(divergence))
+            }
+        }
+    }
+
+    /// Returns whether the active thread is preempted at the end of the current basic block,
+    /// given the live decision.
+    ///
+    /// When replaying, the live decision is ignored as long as the trace says when the next
+    /// preemption happens. The replay diverges if the trace already ended but the execution
+    /// preempts, or if the next preemption of the trace was missed.
+    fn preempt(&self, live: bool) -> (bool, Option<ReplayDivergence>) {
+        let mut mode = self.mode.borrow_mut();
+        if matches!(*mode, Mode::Off) {
+            return (live, None);
+        }
+        let blocks = self.blocks.get() + 1;
+        self.blocks.set(blocks);
+        match &mut *mode {
+            Mode::Off => unreachable!(),
+            Mode::Record { out, error, .. } => {
+                if live && error.is_none() {
+                    if let Err(err) = TraceEvent::Preempt(blocks).write(out) {
+                        *error = Some(err);
+                    }
+                    self.blocks.set(0);
+                }
+                (live, None)
+            }
+            Mode::Replay { events, line } => {
+                match events.front() {
+                    Some(&TraceEvent::Preempt(expected)) if expected == blocks => {
+                        events.pop_front();
+                        *line += 1;
+                        self.blocks.set(0);
+                        return (true, None);
+                    }
+                    // The preemption happens later, or the next event is a different choice.
+                    Some(&TraceEvent::Preempt(expected)) if expected > blocks =>
+                        return (false, None),
+                    Some(event) if !matches!(event, TraceEvent::Preempt(_)) =>
+                        return (false, None),
+                    None if !live => return (false, None),
+                    _ => {}
+                }
+                let found = if live {
+                    TraceEvent::Preempt(blocks).describe()
+                } else {
+                    format!("no preemption after {blocks} basic blocks")
+                };
+                let divergence = ReplayDivergence {
+                    line: *line,
+                    expected: events.front().map(TraceEvent::describe),
+                    found,
+                };
+                *mode = Mode::Off;
+                (live, Some(divergence))
+            }
+        }
+    }
+
+    /// Completes the recording or the replay when the execution ends.
+    pub fn finish(&self, tcx: TyCtxt<'_>) {
+        match &mut *self.mode.borrow_mut() {
+            Mode::Off => {}
+            Mode::Record { out, path, error } => {
+                if let Some(err) = error.take().map_or_else(|| out.flush().err(), Some) {
+                    tcx.dcx().err(format!("failed to write trace `{}`: {err}", path.display()));
+                }
+            }
+            Mode::Replay { events, line } =>
+                if !events.is_empty() {
+                    tcx.dcx().warn(format!(
+                        "the execution ended before the end of the replayed trace, at line {line}"
+                    ));
+                },
+        }
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Records the non-deterministic choice `live`, or replays it from the trace.
+    /// See [`NondetTrace::choose`].
+    fn trace_nondet<T>(
+        &self,
+        live: impl FnOnce() -> T,
+        record: impl FnOnce(&T) -> TraceEvent,
+        replay: impl FnOnce(&TraceEvent) -> Option<T>,
+    ) -> T {
+        let this = self.eval_context_ref();
+        let (value, divergence) = this.machine.nondet_trace.choose(live, record, replay);
+        if let Some(divergence) = divergence {
+            this.emit_diagnostic(NonHaltingDiagnostic::ReplayDivergence(divergence));
+        }
+        value
+    }
+
+    /// Records or replays whether the active thread is preempted at the end of the current
+    /// basic block. This is queried for every basic block, but only preemptions are recorded.
+    fn trace_preemption(&self, live: bool) -> bool {
+        let this = self.eval_context_ref();
+        let (preempt, divergence) = this.machine.nondet_trace.preempt(live);
+        if let Some(divergence) = divergence {
+            this.emit_diagnostic(NonHaltingDiagnostic::ReplayDivergence(divergence));
+        }
+        preempt
+    }
+}
@@ -2,6 +2,7 @@
 
 use rustc_data_structures::fx::FxHashMap;
 
+use self::shims::os_str::bytes_to_os_str;
 use self::shims::unix::UnixEnvVars;
 use self::shims::windows::WindowsEnvVars;
 use crate::*;
@@ -33,12 +34,34 @@ pub(crate) fn init(
         // Skip the loop entirely if we don't want to forward anything.
         let mut env_vars = FxHashMap::default();
         if ecx.machine.communicate() || !config.forwarded_env_vars.is_empty() {
-            for (name, value) in &config.env {
-                let forward = ecx.machine.communicate()
-                    || config.forwarded_env_vars.iter().any(|v| **v == *name);
-                if forward {
-                    env_vars.insert(OsString::from(name), OsString::from(value));
-                }
+            let forwarded = || {
+                config
+                    .env
+                    .iter()
+                    .filter(|(name, _)| {
+                        ecx.machine.communicate()
+                            || config.forwarded_env_vars.iter().any(|v| **v == *name)
+                    })
+                    .map(|(name, value)| {
+                        (name.as_encoded_bytes().to_vec(), value.as_encoded_bytes().to_vec())
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let forwarded = ecx.trace_nondet(
+                forwarded,
+                |vars| TraceEvent::Env(vars.clone()),
+                |event| {
+                    match event {
+                        TraceEvent::Env(vars) => Some(vars.clone()),
+                        _ => None,
+                    }
+                },
+            );
+            for (name, value) in forwarded {
+                env_vars.insert(
+                    bytes_to_os_str(&name)?.to_os_string(),
+                    bytes_to_os_str(&value)?.to_os_string(),
+                );
             }
         }
 
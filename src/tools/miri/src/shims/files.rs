@@ -534,15 +534,35 @@ fn read_from_host(
     ) -> InterpResult<'tcx, Result<usize, IoError>> {
         let this = self.eval_context_mut();
 
-        let mut bytes = vec![0; len];
-        let result = file.read(&mut bytes);
+        // The read is recorded or replayed like other inputs from the host. When it is replayed,
+        // the host is not accessed at all.
+        let result = this.trace_nondet(
+            || -> io::Result<Vec<u8>> {
+                let mut bytes = vec![0; len];
+                let read_size = file.read(&mut bytes)?;
+                // Crucially, if fewer than `bytes.len()` bytes were read, only that much must be
+                // written into the output buffer!
+                bytes.truncate(read_size);
+                Ok(bytes)
+            },
+            |result| {
+                TraceEvent::Read(result.as_ref().map(Vec::clone).map_err(io::Error::raw_os_error))
+            },
+            |event| {
+                match event {
+                    TraceEvent::Read(Ok(bytes)) if bytes.len() <= len => Some(Ok(bytes.clone())),
+                    TraceEvent::Read(Err(Some(code))) =>
+                        Some(Err(io::Error::from_raw_os_error(*code))),
+                    TraceEvent::Read(Err(None)) => Some(Err(ErrorKind::Other.into())),
+                    _ => None,
+                }
+            },
+        );
         match result {
-            Ok(read_size) => {
+            Ok(bytes) => {
                 // If reading to `bytes` did not fail, we write those bytes to the buffer.
-                // Crucially, if fewer than `bytes.len()` bytes were read, only write
-                // that much into the output buffer!
-                this.write_bytes_ptr(ptr, bytes[..read_size].iter().copied())?;
-                interp_ok(Ok(read_size))
+                this.write_bytes_ptr(ptr, bytes.iter().copied())?;
+                interp_ok(Ok(bytes.len()))
             }
             Err(e) => interp_ok(Err(IoError::HostError(e))),
         }
@@ -17,6 +17,46 @@ pub fn system_time_to_duration<'tcx>(time: &SystemTime) -> InterpResult<'tcx, Du
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
 pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns the system time of the host, which is recorded and replayed like other inputs from
+    /// the host.
+    fn host_system_time(&self) -> SystemTime {
+        let this = self.eval_context_ref();
+        this.trace_nondet(
+            SystemTime::now,
+            |now| {
+                let time = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+                TraceEvent::RealTime(time)
+            },
+            |event| {
+                match *event {
+                    TraceEvent::RealTime(time) => SystemTime::UNIX_EPOCH.checked_add(time),
+                    _ => None,
+                }
+            },
+        )
+    }
+
+    /// Returns the time of the machine's monotone clock since its epoch.
+    fn monotonic_time_since_epoch(&self) -> Duration {
+        let this = self.eval_context_ref();
+        let clock = &this.machine.monotonic_clock;
+        let now = || clock.now().duration_since(clock.epoch());
+        if !this.machine.communicate() {
+            // The virtual clock only depends on the execution itself.
+            return now();
+        }
+        this.trace_nondet(
+            now,
+            |&time| TraceEvent::MonotonicTime(time),
+            |event| {
+                match *event {
+                    TraceEvent::MonotonicTime(time) => Some(time),
+                    _ => None,
+                }
+            },
+        )
+    }
+
     fn parse_clockid(&self, clk_id: Scalar) -> Option<TimeoutClock> {
         // This clock support is deliberately minimal because a lot of clock types have fiddly
         // properties (is it possible for Miri to be suspended independently of the host?). If you
@@ -72,13 +112,9 @@ fn clock_gettime(
         let duration = match this.parse_clockid(clk_id) {
             Some(TimeoutClock::RealTime) => {
                 this.check_no_isolation("`clock_gettime` with `REALTIME` clocks")?;
-                system_time_to_duration(&SystemTime::now())?
+                system_time_to_duration(&this.host_system_time())?
             }
-            Some(TimeoutClock::Monotonic) =>
-                this.machine
-                    .monotonic_clock
-                    .now()
-                    .duration_since(this.machine.monotonic_clock.epoch()),
+            Some(TimeoutClock::Monotonic) => this.monotonic_time_since_epoch(),
             None => {
                 return this.set_last_error_and_return(LibcError("EINVAL"), dest);
             }
@@ -111,7 +147,7 @@ fn gettimeofday(
             return this.set_last_error_and_return_i32(LibcError("EINVAL"));
         }
 
-        let duration = system_time_to_duration(&SystemTime::now())?;
+        let duration = system_time_to_duration(&this.host_system_time())?;
         let tv_sec = duration.as_secs();
         let tv_usec = duration.subsec_micros();
 
@@ -220,7 +256,7 @@ fn GetSystemTimeAsFileTime(
 
         let filetime = this.deref_pointer_as(LPFILETIME_op, this.windows_ty_layout("FILETIME"))?;
 
-        let duration = this.system_time_since_windows_epoch(&SystemTime::now())?;
+        let duration = this.system_time_since_windows_epoch(&this.host_system_time())?;
         let duration_ticks = this.windows_ticks_for(duration)?;
 
         let dwLowDateTime = u32::try_from(duration_ticks & 0x00000000FFFFFFFF).unwrap();
@@ -241,8 +277,7 @@ fn QueryPerformanceCounter(
 
         // QueryPerformanceCounter uses a hardware counter as its basis.
         // Miri will emulate a counter with a resolution of 1 nanosecond.
-        let duration =
-            this.machine.monotonic_clock.now().duration_since(this.machine.monotonic_clock.epoch());
+        let duration = this.monotonic_time_since_epoch();
         let qpc = i64::try_from(duration.as_nanos()).map_err(|_| {
             err_unsup_format!("programs running longer than 2^63 nanoseconds are not supported")
         })?;
@@ -305,8 +340,7 @@ fn mach_absolute_time(&self) -> InterpResult<'tcx, Scalar> {
 
         // This returns a u64, with time units determined dynamically by `mach_timebase_info`.
         // We return plain nanoseconds.
-        let duration =
-            this.machine.monotonic_clock.now().duration_since(this.machine.monotonic_clock.epoch());
+        let duration = this.monotonic_time_since_epoch();
         let res = u64::try_from(duration.as_nanos()).map_err(|_| {
             err_unsup_format!("programs running longer than 2^64 nanoseconds are not supported")
         })?;
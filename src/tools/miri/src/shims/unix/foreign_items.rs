@@ -541,6 +541,163 @@ fn emulate_foreign_item_inner(
                 this.write_scalar(result, dest)?;
             }
 
+            // Sockets on the virtual loopback network
+            "socket" => {
+                let [domain, type_, protocol] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [sockfd, addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.bind(sockfd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [sockfd, backlog] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.listen(sockfd, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "connect" => {
+                let [sockfd, addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.connect(sockfd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [sockfd, addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(sockfd, addr, addrlen, /*flags*/ None, dest)?;
+            }
+            "accept4" => {
+                // Currently this function does not exist on all Unixes, e.g. on macOS.
+                this.check_target_os(&["linux", "android", "freebsd"], link_name)?;
+                let [sockfd, addr, addrlen, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(sockfd, addr, addrlen, Some(flags), dest)?;
+            }
+            "getsockname" => {
+                let [sockfd, addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getsockname(sockfd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [sockfd, addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getpeername(sockfd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "setsockopt" => {
+                let [sockfd, level, optname, optval, optlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.setsockopt(sockfd, level, optname, optval, optlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockopt" => {
+                let [sockfd, level, optname, optval, optlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getsockopt(sockfd, level, optname, optval, optlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "shutdown" => {
+                let [sockfd, how] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.shutdown(sockfd, how)?;
+                this.write_scalar(result, dest)?;
+            }
+            "send" => {
+                let [sockfd, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.send(sockfd, buf, len, flags, /*dest_addr*/ None, dest)?;
+            }
+            "sendto" => {
+                let [sockfd, buf, len, flags, dest_addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(
+                        extern "C" fn(
+                            i32,
+                            *const _,
+                            usize,
+                            i32,
+                            *const _,
+                            libc::socklen_t,
+                        ) -> isize
+                    ),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.send(sockfd, buf, len, flags, Some((dest_addr, addrlen)), dest)?;
+            }
+            "recv" => {
+                let [sockfd, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recv(sockfd, buf, len, flags, /*src_addr*/ None, dest)?;
+            }
+            "recvfrom" => {
+                let [sockfd, buf, len, flags, src_addr, addrlen] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32, *mut _, *mut _) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recv(sockfd, buf, len, flags, Some((src_addr, addrlen)), dest)?;
+            }
+
             // Time
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim_sig(
@@ -0,0 +1,1528 @@
+//! This implements IPv4 and IPv6 sockets (`AF_INET` and `AF_INET6`) on top of a virtual network
+//! that is entirely implemented inside Miri. The network only has a loopback interface: sockets
+//! can only talk to other sockets of the interpreted program, and nothing ever reaches the host.
+//!
+//! To keep execution deterministic, connections are established immediately (there is no
+//! handshake that could be observed half-way through), and ports are handed out in a fixed order.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use rustc_abi::Size;
+use rustc_data_structures::fx::FxHashMap;
+
+use crate::concurrency::VClock;
+use crate::shims::files::{
+    EvalContextExt as _, FileDescription, FileDescriptionExt, FileDescriptionRef,
+    WeakFileDescriptionRef,
+};
+use crate::shims::unix::UnixFileDescription;
+use crate::shims::unix::linux_like::epoll::{EpollReadyEvents, EvalContextExt as _};
+use crate::*;
+
+/// The maximum number of bytes that can be waiting to be received on a socket.
+/// Like for socketpairs, this number is arbitrary as the value can always
+/// be configured in the real system.
+const MAX_SOCKET_BUFFER_CAPACITY: usize = 212992;
+
+/// The largest payload of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// The first port handed out when a socket is bound to port 0
+/// (this is the start of the IANA ephemeral port range).
+const FIRST_EPHEMERAL_PORT: u16 = 49152;
+
+/// The largest backlog `listen` accepts, like `SOMAXCONN` on Linux.
+const MAX_BACKLOG: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SocketType {
+    /// A TCP socket (`SOCK_STREAM`).
+    Stream,
+    /// A UDP socket (`SOCK_DGRAM`).
+    Datagram,
+}
+
+/// The virtual network all sockets of the interpreted program are attached to.
+#[derive(Debug)]
+pub struct VirtualNetwork {
+    /// The sockets that are bound to a port, indexed by their type and port.
+    /// Stream and datagram sockets have separate port spaces, like TCP and UDP.
+    ports: FxHashMap<(SocketType, u16), WeakFileDescriptionRef<InetSocket>>,
+    /// The next port to try when a socket needs to be bound to an unused port.
+    next_ephemeral_port: u16,
+}
+
+impl Default for VirtualNetwork {
+    fn default() -> Self {
+        VirtualNetwork { ports: FxHashMap::default(), next_ephemeral_port: FIRST_EPHEMERAL_PORT }
+    }
+}
+
+impl VirtualNetwork {
+    /// Returns the socket bound to the given port, if any.
+    fn lookup(&self, socket_type: SocketType, port: u16) -> Option<FileDescriptionRef<InetSocket>> {
+        self.ports.get(&(socket_type, port)).and_then(|socket| socket.upgrade())
+    }
+
+    /// Binds `socket` to `port`, or to an unused ephemeral port if `port` is 0.
+    /// Returns the port the socket got bound to.
+    fn bind(&mut self, socket: &FileDescriptionRef<InetSocket>, port: u16) -> Result<u16, IoError> {
+        let socket_type = socket.socket_type;
+        let port = if port == 0 {
+            self.find_ephemeral_port(socket_type).ok_or(LibcError("EADDRINUSE"))?
+        } else if self.lookup(socket_type, port).is_some() {
+            return Err(LibcError("EADDRINUSE"));
+        } else {
+            port
+        };
+        self.ports.insert((socket_type, port), FileDescriptionRef::downgrade(socket));
+        Ok(port)
+    }
+
+    fn find_ephemeral_port(&mut self, socket_type: SocketType) -> Option<u16> {
+        for _ in FIRST_EPHEMERAL_PORT..=u16::MAX {
+            let port = self.next_ephemeral_port;
+            self.next_ephemeral_port = port.checked_add(1).unwrap_or(FIRST_EPHEMERAL_PORT);
+            if self.lookup(socket_type, port).is_none() {
+                return Some(port);
+            }
+        }
+        None
+    }
+
+    /// Forgets about the ports of sockets that have been closed.
+    fn release_closed_ports(&mut self) {
+        self.ports.retain(|_, socket| socket.upgrade().is_some());
+    }
+}
+
+/// A socket attached to the virtual network.
+#[derive(Debug)]
+struct InetSocket {
+    socket_type: SocketType,
+    /// Whether this is an `AF_INET6` socket.
+    is_ipv6: bool,
+    state: RefCell<SocketState>,
+    /// Integer socket options set through `setsockopt`, indexed by level and name.
+    options: RefCell<FxHashMap<(i32, i32), i32>>,
+    /// Whether this fd is non-blocking or not.
+    is_nonblock: Cell<bool>,
+    /// A list of thread ids blocked on an operation on this socket.
+    /// They are all unblocked whenever the state of the socket changes, and then retry.
+    blocked_tid: RefCell<Vec<ThreadId>>,
+}
+
+#[derive(Debug)]
+enum SocketState {
+    /// Freshly created by `socket`.
+    Unbound,
+    /// A stream socket bound to a local address, but neither listening nor connected.
+    Bound(SocketAddr),
+    /// A stream socket accepting connections.
+    Listening {
+        local: SocketAddr,
+        backlog: usize,
+        /// Connections that have been established but not accepted yet.
+        pending: VecDeque<FileDescriptionRef<InetSocket>>,
+    },
+    /// A connected stream socket.
+    Connected(Connection),
+    /// A bound datagram socket.
+    Datagram(DatagramQueue),
+}
+
+/// One end of an established stream connection.
+#[derive(Debug)]
+struct Connection {
+    local: SocketAddr,
+    peer_addr: SocketAddr,
+    /// The other end of the connection. This is a weak reference because the other side may be
+    /// closed before us; reads will then return EOF and writes will fail with EPIPE.
+    peer: WeakFileDescriptionRef<InetSocket>,
+    /// The bytes sent by the peer that have not been received yet.
+    readbuf: VecDeque<u8>,
+    /// The clock of the sends that filled `readbuf`, and of the `connect` that created
+    /// this connection.
+    clock: VClock,
+    /// Whether receiving has been shut down on this end.
+    read_shutdown: bool,
+    /// Whether sending has been shut down on this end.
+    write_shutdown: bool,
+}
+
+impl Connection {
+    fn new(
+        local: SocketAddr,
+        peer_addr: SocketAddr,
+        peer: WeakFileDescriptionRef<InetSocket>,
+    ) -> Self {
+        Connection {
+            local,
+            peer_addr,
+            peer,
+            readbuf: VecDeque::new(),
+            clock: VClock::default(),
+            read_shutdown: false,
+            write_shutdown: false,
+        }
+    }
+
+    /// Whether the peer will never send us anything again.
+    fn is_peer_done(&self) -> bool {
+        self.peer.upgrade().is_none_or(|peer| {
+            match &*peer.state.borrow() {
+                SocketState::Connected(peer_conn) => peer_conn.write_shutdown,
+                _ => true,
+            }
+        })
+    }
+}
+
+/// The state of a bound datagram socket.
+#[derive(Debug)]
+struct DatagramQueue {
+    local: SocketAddr,
+    /// The default destination set by `connect`. When set, datagrams from other addresses are
+    /// dropped.
+    peer_addr: Option<SocketAddr>,
+    /// The datagrams that have been sent to us but not received yet.
+    queue: VecDeque<Datagram>,
+    /// The total payload size of `queue`.
+    queued_bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Datagram {
+    source: SocketAddr,
+    data: Vec<u8>,
+    clock: VClock,
+}
+
+impl SocketState {
+    fn local_addr(&self) -> Option<SocketAddr> {
+        match self {
+            SocketState::Unbound => None,
+            SocketState::Bound(local) | SocketState::Listening { local, .. } => Some(*local),
+            SocketState::Connected(conn) => Some(conn.local),
+            SocketState::Datagram(datagrams) => Some(datagrams.local),
+        }
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        match self {
+            SocketState::Connected(conn) => Some(conn.peer_addr),
+            SocketState::Datagram(datagrams) => datagrams.peer_addr,
+            _ => None,
+        }
+    }
+}
+
+impl InetSocket {
+    fn new(socket_type: SocketType, is_ipv6: bool, state: SocketState) -> Self {
+        InetSocket {
+            socket_type,
+            is_ipv6,
+            state: RefCell::new(state),
+            options: RefCell::new(FxHashMap::default()),
+            is_nonblock: Cell::new(false),
+            blocked_tid: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The state of this socket once it is bound to `local`.
+    fn bound_state(&self, local: SocketAddr) -> SocketState {
+        match self.socket_type {
+            SocketType::Stream => SocketState::Bound(local),
+            SocketType::Datagram =>
+                SocketState::Datagram(DatagramQueue {
+                    local,
+                    peer_addr: None,
+                    queue: VecDeque::new(),
+                    queued_bytes: 0,
+                }),
+        }
+    }
+
+    fn loopback(&self) -> IpAddr {
+        if self.is_ipv6 { Ipv6Addr::LOCALHOST.into() } else { Ipv4Addr::LOCALHOST.into() }
+    }
+
+    /// The address other sockets see as the source of our packets: if we are bound to the
+    /// unspecified address, that is the loopback address, as there is no other interface.
+    fn source_addr(&self, local: SocketAddr) -> SocketAddr {
+        if local.ip().is_unspecified() {
+            SocketAddr::new(self.loopback(), local.port())
+        } else {
+            local
+        }
+    }
+}
+
+impl FileDescription for InetSocket {
+    fn name(&self) -> &'static str {
+        match self.socket_type {
+            SocketType::Stream => "TCP socket",
+            SocketType::Datagram => "UDP socket",
+        }
+    }
+
+    fn close<'tcx>(
+        self,
+        communicate_allowed: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        // Threads still blocked on this socket will notice that it is gone.
+        for thread_id in self.blocked_tid.take() {
+            ecx.unblock_thread(thread_id, BlockReason::Socket)?;
+        }
+        match self.state.into_inner() {
+            SocketState::Connected(conn) => {
+                if let Some(peer) = conn.peer.upgrade() {
+                    // Our peer can now read EOF.
+                    notify_socket(ecx, peer)?;
+                }
+            }
+            SocketState::Listening { pending, .. } => {
+                for conn in pending {
+                    // Connections that were never accepted are closed along with the listener.
+                    conn.close_ref(communicate_allowed, ecx)?.ok();
+                }
+            }
+            _ => {}
+        }
+        ecx.machine.network.release_closed_ports();
+        interp_ok(Ok(()))
+    }
+
+    fn read<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let finish = callback!(
+            @capture<'tcx> {
+                finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+            }
+            |this, result: Result<(usize, Option<SocketAddr>), IoError>| {
+                finish.call(this, result.map(|(read_size, _source)| read_size))
+            }
+        );
+        inet_socket_recv(self, ptr, len, /* peek */ false, ecx, finish)
+    }
+
+    fn write<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        inet_socket_send(self, ptr, len, /* dest_addr */ None, ecx, finish)
+    }
+
+    fn nondet_short_accesses(&self) -> bool {
+        // Datagrams are always sent and received as a whole.
+        self.socket_type == SocketType::Stream
+    }
+
+    fn as_unix<'tcx>(&self, _ecx: &MiriInterpCx<'tcx>) -> &dyn UnixFileDescription {
+        self
+    }
+
+    fn get_flags<'tcx>(&self, ecx: &mut MiriInterpCx<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let mut flags = ecx.eval_libc_i32("O_RDWR");
+        if self.is_nonblock.get() {
+            flags |= ecx.eval_libc_i32("O_NONBLOCK");
+        }
+        interp_ok(Scalar::from_i32(flags))
+    }
+
+    fn set_flags<'tcx>(
+        &self,
+        mut flag: i32,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let o_nonblock = ecx.eval_libc_i32("O_NONBLOCK");
+        let o_rdonly = ecx.eval_libc_i32("O_RDONLY");
+        let o_wronly = ecx.eval_libc_i32("O_WRONLY");
+        let o_rdwr = ecx.eval_libc_i32("O_RDWR");
+
+        // O_NONBLOCK flag can be set / unset by user.
+        if flag & o_nonblock == o_nonblock {
+            self.is_nonblock.set(true);
+            flag &= !o_nonblock;
+        } else {
+            self.is_nonblock.set(false);
+        }
+
+        // Ignore all file access mode flags.
+        flag &= !(o_rdonly | o_wronly | o_rdwr);
+
+        // Throw error if there is any unsupported flag.
+        if flag != 0 {
+            throw_unsup_format!("fcntl: only O_NONBLOCK is supported for F_SETFL on sockets")
+        }
+
+        interp_ok(Scalar::from_i32(0))
+    }
+}
+
+impl UnixFileDescription for InetSocket {
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let mut epoll_ready_events = EpollReadyEvents::new();
+        match &*self.state.borrow() {
+            SocketState::Unbound | SocketState::Bound(_) =>
+                match self.socket_type {
+                    // Like on Linux, a stream socket that is not connected reports a hangup.
+                    SocketType::Stream => {
+                        epoll_ready_events.epollout = true;
+                        epoll_ready_events.epollhup = true;
+                    }
+                    SocketType::Datagram => epoll_ready_events.epollout = true,
+                },
+            SocketState::Listening { pending, .. } =>
+                epoll_ready_events.epollin = !pending.is_empty(),
+            SocketState::Connected(conn) => {
+                let peer_done = conn.is_peer_done();
+                // Reading returns EOF once the peer is done, so it does not block.
+                epoll_ready_events.epollin =
+                    !conn.readbuf.is_empty() || peer_done || conn.read_shutdown;
+                epoll_ready_events.epollrdhup = peer_done;
+                match conn.peer.upgrade() {
+                    Some(peer) =>
+                        if let SocketState::Connected(peer_conn) = &*peer.state.borrow() {
+                            epoll_ready_events.epollout =
+                                peer_conn.readbuf.len() < MAX_SOCKET_BUFFER_CAPACITY;
+                        },
+                    None => {
+                        // Writes will fail with EPIPE, so they do not block either.
+                        epoll_ready_events.epollout = true;
+                        epoll_ready_events.epollhup = true;
+                    }
+                }
+            }
+            SocketState::Datagram(datagrams) => {
+                epoll_ready_events.epollin = !datagrams.queue.is_empty();
+                // Sending a datagram never blocks: it is dropped if it does not fit.
+                epoll_ready_events.epollout = true;
+            }
+        }
+        interp_ok(epoll_ready_events)
+    }
+}
+
+/// Unblocks all threads blocked on `socket` so that they retry their operation,
+/// and notifies epoll about the changed readiness of the socket.
+fn notify_socket<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    socket: FileDescriptionRef<InetSocket>,
+) -> InterpResult<'tcx> {
+    let waiting_threads = std::mem::take(&mut *socket.blocked_tid.borrow_mut());
+    // FIXME: We can randomize the order of unblocking.
+    for thread_id in waiting_threads {
+        ecx.unblock_thread(thread_id, BlockReason::Socket)?;
+    }
+    ecx.check_and_update_readiness(socket)
+}
+
+/// Blocks the active thread until `socket` changes, and then calls `retry`.
+/// If the socket is closed in the meantime, `retry` is called with `None`.
+fn block_on_socket<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    socket: &FileDescriptionRef<InetSocket>,
+    retry: DynMachineCallback<'tcx, Option<FileDescriptionRef<InetSocket>>>,
+) {
+    socket.blocked_tid.borrow_mut().push(ecx.active_thread());
+    // Only keep a weak ref, so that the socket can still be closed by another thread.
+    let weak_socket = FileDescriptionRef::downgrade(socket);
+    ecx.block_thread(
+        BlockReason::Socket,
+        None,
+        callback!(
+            @capture<'tcx> {
+                weak_socket: WeakFileDescriptionRef<InetSocket>,
+                retry: DynMachineCallback<'tcx, Option<FileDescriptionRef<InetSocket>>>,
+            }
+            |this, unblock: UnblockKind| {
+                assert_eq!(unblock, UnblockKind::Ready);
+                retry.call(this, weak_socket.upgrade())
+            }
+        ),
+    );
+}
+
+/// Accepts a pending connection on a listening socket, and writes the new fd to `dest`.
+fn inet_socket_accept<'tcx>(
+    socket: FileDescriptionRef<InetSocket>,
+    addr: Pointer,
+    addrlen: Pointer,
+    is_nonblock: bool,
+    ecx: &mut MiriInterpCx<'tcx>,
+    dest: MPlaceTy<'tcx>,
+) -> InterpResult<'tcx> {
+    let conn = match &mut *socket.state.borrow_mut() {
+        SocketState::Listening { pending, .. } => pending.pop_front(),
+        _ => return ecx.set_last_error_and_return(LibcError("EINVAL"), &dest),
+    };
+
+    let Some(conn) = conn else {
+        if socket.is_nonblock.get() {
+            return ecx.set_last_error_and_return(ErrorKind::WouldBlock, &dest);
+        }
+        block_on_socket(
+            ecx,
+            &socket,
+            callback!(
+                @capture<'tcx> {
+                    addr: Pointer,
+                    addrlen: Pointer,
+                    is_nonblock: bool,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, socket: Option<FileDescriptionRef<InetSocket>>| {
+                    match socket {
+                        Some(socket) =>
+                            inet_socket_accept(socket, addr, addrlen, is_nonblock, this, dest),
+                        None => this.set_last_error_and_return(LibcError("EBADF"), &dest),
+                    }
+                }
+            ),
+        );
+        return interp_ok(());
+    };
+
+    conn.is_nonblock.set(is_nonblock);
+    let peer_addr = match &*conn.state.borrow() {
+        SocketState::Connected(conn) => {
+            // Synchronize with the `connect` that created this connection.
+            ecx.acquire_clock(&conn.clock);
+            conn.peer_addr
+        }
+        _ => unreachable!(),
+    };
+    let fd = ecx.machine.fds.insert(conn);
+    if !ecx.ptr_is_null(addr)? {
+        ecx.write_socket_addr(peer_addr, addr, addrlen)?;
+    }
+    ecx.write_int(fd, &dest)
+}
+
+/// Sends data on a socket. `dest_addr` is only used by datagram sockets.
+fn inet_socket_send<'tcx>(
+    socket: FileDescriptionRef<InetSocket>,
+    ptr: Pointer,
+    len: usize,
+    dest_addr: Option<SocketAddr>,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    match socket.socket_type {
+        SocketType::Stream => stream_send(socket, ptr, len, ecx, finish),
+        SocketType::Datagram => {
+            let result = datagram_send(&socket, ptr, len, dest_addr, ecx)?;
+            finish.call(ecx, result)
+        }
+    }
+}
+
+fn stream_send<'tcx>(
+    socket: FileDescriptionRef<InetSocket>,
+    ptr: Pointer,
+    len: usize,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let peer = match &*socket.state.borrow() {
+        SocketState::Connected(conn) if !conn.write_shutdown => conn.peer.upgrade(),
+        SocketState::Connected(_) => None,
+        _ => return finish.call(ecx, Err(LibcError("ENOTCONN"))),
+    };
+    let Some(peer) = peer else {
+        // The peer has been closed, or we shut down sending.
+        return finish.call(ecx, Err(ErrorKind::BrokenPipe.into()));
+    };
+
+    // Always succeed on write size 0.
+    if len == 0 {
+        return finish.call(ecx, Ok(0));
+    }
+
+    let mut peer_state = peer.state.borrow_mut();
+    let SocketState::Connected(peer_conn) = &mut *peer_state else { unreachable!() };
+    let available_space = MAX_SOCKET_BUFFER_CAPACITY.strict_sub(peer_conn.readbuf.len());
+    if available_space == 0 {
+        drop(peer_state);
+        if socket.is_nonblock.get() {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        // The peer notifies us when it receives something.
+        block_on_socket(
+            ecx,
+            &socket,
+            callback!(
+                @capture<'tcx> {
+                    ptr: Pointer,
+                    len: usize,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, socket: Option<FileDescriptionRef<InetSocket>>| {
+                    match socket {
+                        Some(socket) => stream_send(socket, ptr, len, this, finish),
+                        None => finish.call(this, Err(LibcError("EBADF"))),
+                    }
+                }
+            ),
+        );
+        return interp_ok(());
+    }
+
+    // Remember this clock so that receiving can synchronize with us.
+    ecx.release_clock(|clock| {
+        peer_conn.clock.join(clock);
+    });
+    let write_size = len.min(available_space);
+    let actual_write_size = ecx.write_to_host(&mut peer_conn.readbuf, write_size, ptr)?.unwrap();
+    assert_eq!(actual_write_size, write_size);
+    drop(peer_state);
+
+    notify_socket(ecx, peer)?;
+    finish.call(ecx, Ok(write_size))
+}
+
+/// Sends a datagram. This never blocks: like on a real network, datagrams that cannot be
+/// delivered are silently dropped.
+fn datagram_send<'tcx>(
+    socket: &FileDescriptionRef<InetSocket>,
+    ptr: Pointer,
+    len: usize,
+    dest_addr: Option<SocketAddr>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx, Result<usize, IoError>> {
+    if len > MAX_DATAGRAM_SIZE {
+        return interp_ok(Err(LibcError("EMSGSIZE")));
+    }
+
+    // Sending from an unbound socket binds it to an ephemeral port.
+    if matches!(*socket.state.borrow(), SocketState::Unbound) {
+        let port = match ecx.machine.network.bind(socket, 0) {
+            Ok(port) => port,
+            Err(e) => return interp_ok(Err(e)),
+        };
+        let local = SocketAddr::new(socket.loopback(), port);
+        *socket.state.borrow_mut() = socket.bound_state(local);
+    }
+
+    let (source, dest_addr) = match &*socket.state.borrow() {
+        SocketState::Datagram(datagrams) => {
+            let Some(dest_addr) = dest_addr.or(datagrams.peer_addr) else {
+                return interp_ok(Err(LibcError("EDESTADDRREQ")));
+            };
+            (socket.source_addr(datagrams.local), dest_addr)
+        }
+        _ => unreachable!(),
+    };
+    if !dest_addr.ip().is_loopback() && !dest_addr.ip().is_unspecified() {
+        return interp_ok(Err(LibcError("ENETUNREACH")));
+    }
+
+    let data = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?.to_vec();
+    let Some(target) = ecx.machine.network.lookup(SocketType::Datagram, dest_addr.port()) else {
+        // Nobody is listening on that port.
+        return interp_ok(Ok(len));
+    };
+    if target.is_ipv6 != socket.is_ipv6 {
+        return interp_ok(Ok(len));
+    }
+
+    let mut target_state = target.state.borrow_mut();
+    let SocketState::Datagram(datagrams) = &mut *target_state else { unreachable!() };
+    if datagrams.peer_addr.is_some_and(|peer_addr| peer_addr != source)
+        || datagrams.queued_bytes.strict_add(len) > MAX_SOCKET_BUFFER_CAPACITY
+    {
+        // The target only accepts datagrams from its peer, or its buffer is full.
+        return interp_ok(Ok(len));
+    }
+    let mut clock = VClock::default();
+    ecx.release_clock(|release_clock| clock.join(release_clock));
+    datagrams.queued_bytes = datagrams.queued_bytes.strict_add(len);
+    datagrams.queue.push_back(Datagram { source, data, clock });
+    drop(target_state);
+
+    notify_socket(ecx, target)?;
+    interp_ok(Ok(len))
+}
+
+/// Receives data from a socket. For datagram sockets, the address the data was sent from is
+/// passed to `finish` as well.
+fn inet_socket_recv<'tcx>(
+    socket: FileDescriptionRef<InetSocket>,
+    ptr: Pointer,
+    len: usize,
+    peek: bool,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<(usize, Option<SocketAddr>), IoError>>,
+) -> InterpResult<'tcx> {
+    let mut state = socket.state.borrow_mut();
+    let is_empty = match &mut *state {
+        SocketState::Connected(conn) => {
+            if conn.readbuf.is_empty() {
+                if conn.read_shutdown || conn.is_peer_done() {
+                    // 0 bytes successfully read indicates end-of-file.
+                    drop(state);
+                    return finish.call(ecx, Ok((0, None)));
+                }
+                true
+            } else {
+                // Synchronize with all previous sends to this buffer.
+                // FIXME: this over-synchronizes; a more precise approach would be to
+                // only sync with the sends whose data we will receive.
+                ecx.acquire_clock(&conn.clock);
+                let read_size = if peek {
+                    let (front, back) = conn.readbuf.as_slices();
+                    ecx.read_from_host(io::Read::chain(front, back), len, ptr)?.unwrap()
+                } else {
+                    ecx.read_from_host(&mut conn.readbuf, len, ptr)?.unwrap()
+                };
+                let peer = conn.peer.upgrade();
+                drop(state);
+                // There is space in our buffer again, so our peer may be able to send.
+                if !peek && let Some(peer) = peer {
+                    notify_socket(ecx, peer)?;
+                }
+                return finish.call(ecx, Ok((read_size, None)));
+            }
+        }
+        SocketState::Datagram(datagrams) => {
+            if let Some(datagram) = datagrams.queue.front() {
+                let datagram = if peek {
+                    datagram.clone()
+                } else {
+                    datagrams.queued_bytes = datagrams.queued_bytes.strict_sub(datagram.data.len());
+                    datagrams.queue.pop_front().unwrap()
+                };
+                drop(state);
+                ecx.acquire_clock(&datagram.clock);
+                // Whatever does not fit into the buffer is discarded.
+                let read_size = len.min(datagram.data.len());
+                ecx.write_bytes_ptr(ptr, datagram.data[..read_size].iter().copied())?;
+                return finish.call(ecx, Ok((read_size, Some(datagram.source))));
+            }
+            true
+        }
+        // Nothing can be sent to an unbound datagram socket, but receiving on it is still allowed.
+        SocketState::Unbound if socket.socket_type == SocketType::Datagram => true,
+        _ => false,
+    };
+    drop(state);
+
+    if !is_empty {
+        return finish.call(ecx, Err(LibcError("ENOTCONN")));
+    }
+    if socket.is_nonblock.get() {
+        // Since there is no ErrorKind for EAGAIN, WouldBlock is used.
+        return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+    }
+    block_on_socket(
+        ecx,
+        &socket,
+        callback!(
+            @capture<'tcx> {
+                ptr: Pointer,
+                len: usize,
+                peek: bool,
+                finish: DynMachineCallback<'tcx, Result<(usize, Option<SocketAddr>), IoError>>,
+            }
+            |this, socket: Option<FileDescriptionRef<InetSocket>>| {
+                match socket {
+                    Some(socket) => inet_socket_recv(socket, ptr, len, peek, this, finish),
+                    None => finish.call(this, Err(LibcError("EBADF"))),
+                }
+            }
+        ),
+    );
+    interp_ok(())
+}
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Looks up the socket of the virtual network referred to by `fd_num`.
+    fn inet_socket(
+        &self,
+        fd_num: i32,
+    ) -> InterpResult<'tcx, Result<FileDescriptionRef<InetSocket>, IoError>> {
+        let this = self.eval_context_ref();
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            return interp_ok(Err(LibcError("EBADF")));
+        };
+        match fd.downcast::<InetSocket>() {
+            Some(socket) => interp_ok(Ok(socket)),
+            None => interp_ok(Err(LibcError("ENOTSOCK"))),
+        }
+    }
+
+    /// Reads a `sockaddr_in` or `sockaddr_in6` (depending on the address family of the socket)
+    /// from the interpreted program.
+    fn read_socket_addr(
+        &self,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+        is_ipv6: bool,
+    ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
+        let this = self.eval_context_ref();
+
+        let addr = this.read_pointer(addr)?;
+        let addrlen = u64::from(this.read_scalar(addrlen)?.to_u32()?);
+
+        let (expected_family, layout) = if is_ipv6 {
+            (this.eval_libc_i32("AF_INET6"), this.libc_ty_layout("sockaddr_in6"))
+        } else {
+            (this.eval_libc_i32("AF_INET"), this.libc_ty_layout("sockaddr_in"))
+        };
+        if addrlen < layout.size.bytes() {
+            return interp_ok(Err(LibcError("EINVAL")));
+        }
+
+        let sockaddr = this.ptr_to_mplace(addr, this.libc_ty_layout("sockaddr"));
+        let family = this.project_field_named(&sockaddr, "sa_family")?;
+        let family = this.read_scalar(&family)?.to_uint(family.layout.size)?;
+        if family != u128::try_from(expected_family).unwrap() {
+            return interp_ok(Err(LibcError("EAFNOSUPPORT")));
+        }
+
+        let sockaddr = this.ptr_to_mplace(addr, layout);
+        let (port, ip) = if is_ipv6 {
+            (
+                this.project_field_named(&sockaddr, "sin6_port")?,
+                this.project_field_named(&sockaddr, "sin6_addr")?,
+            )
+        } else {
+            (
+                this.project_field_named(&sockaddr, "sin_port")?,
+                this.project_field_named(&sockaddr, "sin_addr")?,
+            )
+        };
+        // Both the port and the address are stored in network byte order.
+        let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+        let port = u16::from_be_bytes(port.try_into().unwrap());
+        let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), ip.layout.size)?;
+        let ip: IpAddr = if is_ipv6 {
+            <[u8; 16]>::try_from(ip).unwrap().into()
+        } else {
+            <[u8; 4]>::try_from(ip).unwrap().into()
+        };
+        interp_ok(Ok(SocketAddr::new(ip, port)))
+    }
+
+    /// Writes `socket_addr` as a `sockaddr_in` or `sockaddr_in6` to `addr`, and its size to
+    /// `addrlen`. On input, `addrlen` holds the size of the buffer at `addr`.
+    fn write_socket_addr(
+        &mut self,
+        socket_addr: SocketAddr,
+        addr: Pointer,
+        addrlen: Pointer,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let addrlen = this.ptr_to_mplace(addrlen, this.libc_ty_layout("socklen_t"));
+        let buf_len = u64::from(this.read_scalar(&addrlen)?.to_u32()?);
+
+        let layout = match socket_addr {
+            SocketAddr::V4(_) => this.libc_ty_layout("sockaddr_in"),
+            SocketAddr::V6(_) => this.libc_ty_layout("sockaddr_in6"),
+        };
+        if buf_len < layout.size.bytes() {
+            throw_unsup_format!("truncating socket addresses to a too small buffer is unsupported");
+        }
+
+        // Zero everything first, to initialize the padding and the fields we do not set.
+        this.write_bytes_ptr(addr, std::iter::repeat_n(0u8, layout.size.bytes_usize()))?;
+        let sockaddr = this.ptr_to_mplace(addr, layout);
+        let (prefix, family, ip) = match socket_addr {
+            SocketAddr::V4(socket_addr) =>
+                ("sin", this.eval_libc_i32("AF_INET"), socket_addr.ip().octets().to_vec()),
+            SocketAddr::V6(socket_addr) =>
+                ("sin6", this.eval_libc_i32("AF_INET6"), socket_addr.ip().octets().to_vec()),
+        };
+        let family_field = this.project_field_named(&sockaddr, &format!("{prefix}_family"))?;
+        this.write_int(family, &family_field)?;
+        // The BSDs also store the size of the address in the address itself.
+        if let Some(len_field) =
+            this.try_project_field_named(&sockaddr, &format!("{prefix}_len"))?
+        {
+            this.write_int(layout.size.bytes(), &len_field)?;
+        }
+        // Both the port and the address are stored in network byte order.
+        let port_field = this.project_field_named(&sockaddr, &format!("{prefix}_port"))?;
+        this.write_bytes_ptr(port_field.ptr(), socket_addr.port().to_be_bytes())?;
+        let ip_field = this.project_field_named(&sockaddr, &format!("{prefix}_addr"))?;
+        this.write_bytes_ptr(ip_field.ptr(), ip)?;
+
+        this.write_int(layout.size.bytes(), &addrlen)
+    }
+
+    /// Returns the default value of a supported integer socket option, or `None` if the option
+    /// is not supported.
+    fn socket_option_default(&self, socket: &InetSocket, level: i32, optname: i32) -> Option<i32> {
+        let this = self.eval_context_ref();
+        let option = |option_level: &str, option_name: &str| {
+            level == this.eval_libc_i32(option_level) && optname == this.eval_libc_i32(option_name)
+        };
+        if option("SOL_SOCKET", "SO_TYPE") {
+            Some(match socket.socket_type {
+                SocketType::Stream => this.eval_libc_i32("SOCK_STREAM"),
+                SocketType::Datagram => this.eval_libc_i32("SOCK_DGRAM"),
+            })
+        } else if option("SOL_SOCKET", "SO_ERROR") {
+            // Errors are always reported directly by the failing operation.
+            Some(0)
+        } else if option("SOL_SOCKET", "SO_REUSEADDR")
+            || option("SOL_SOCKET", "SO_KEEPALIVE")
+            || option("SOL_SOCKET", "SO_BROADCAST")
+        {
+            Some(0)
+        } else if socket.socket_type == SocketType::Stream && option("IPPROTO_TCP", "TCP_NODELAY") {
+            Some(0)
+        } else if !socket.is_ipv6 && option("IPPROTO_IP", "IP_TTL") {
+            Some(64)
+        } else if socket.is_ipv6 && option("IPPROTO_IPV6", "IPV6_V6ONLY") {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut flags = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let mut is_sock_nonblock = false;
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_sock_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+
+        let is_ipv6 = if domain == this.eval_libc_i32("AF_INET") {
+            false
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            true
+        } else {
+            throw_unsup_format!(
+                "socket: domain {:#x} is unsupported, only AF_INET and AF_INET6 are allowed",
+                domain
+            );
+        };
+        let (socket_type, protocol_name) = if flags == this.eval_libc_i32("SOCK_STREAM") {
+            (SocketType::Stream, "IPPROTO_TCP")
+        } else if flags == this.eval_libc_i32("SOCK_DGRAM") {
+            (SocketType::Datagram, "IPPROTO_UDP")
+        } else {
+            throw_unsup_format!(
+                "socket: type {:#x} is unsupported, only SOCK_STREAM, SOCK_DGRAM, \
+                 SOCK_CLOEXEC and SOCK_NONBLOCK are allowed",
+                flags
+            );
+        };
+        if protocol != 0 && protocol != this.eval_libc_i32(protocol_name) {
+            throw_unsup_format!(
+                "socket: protocol {protocol} is unsupported, only 0 and {protocol_name} are allowed"
+            );
+        }
+
+        let socket = InetSocket::new(socket_type, is_ipv6, SocketState::Unbound);
+        socket.is_nonblock.set(is_sock_nonblock);
+        interp_ok(Scalar::from_i32(this.machine.fds.insert_new(socket)))
+    }
+
+    fn bind(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let addr = match this.read_socket_addr(addr, addrlen, socket.is_ipv6)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        if !matches!(*socket.state.borrow(), SocketState::Unbound) {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        // The loopback interface is the only one we have.
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return this.set_last_error_and_return_i32(LibcError("EADDRNOTAVAIL"));
+        }
+        let port = match this.machine.network.bind(&socket, addr.port()) {
+            Ok(port) => port,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        *socket.state.borrow_mut() = socket.bound_state(SocketAddr::new(addr.ip(), port));
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn listen(&mut self, sockfd: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let backlog = this.read_scalar(backlog)?.to_i32()?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if socket.socket_type != SocketType::Stream {
+            return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+        }
+        // Like on Linux, the backlog is silently clamped, and a backlog of 0 still allows one
+        // pending connection.
+        let new_backlog = usize::try_from(backlog).unwrap_or(0).clamp(1, MAX_BACKLOG);
+
+        let local = match &mut *socket.state.borrow_mut() {
+            SocketState::Unbound => None,
+            SocketState::Bound(local) => Some(*local),
+            SocketState::Listening { backlog, .. } => {
+                *backlog = new_backlog;
+                return interp_ok(Scalar::from_i32(0));
+            }
+            _ => return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+        };
+        // Listening on an unbound socket binds it to an ephemeral port on all interfaces.
+        let local = match local {
+            Some(local) => local,
+            None => {
+                let port = match this.machine.network.bind(&socket, 0) {
+                    Ok(port) => port,
+                    Err(e) => return this.set_last_error_and_return_i32(e),
+                };
+                let unspecified: IpAddr = if socket.is_ipv6 {
+                    Ipv6Addr::UNSPECIFIED.into()
+                } else {
+                    Ipv4Addr::UNSPECIFIED.into()
+                };
+                SocketAddr::new(unspecified, port)
+            }
+        };
+        *socket.state.borrow_mut() =
+            SocketState::Listening { local, backlog: new_backlog, pending: VecDeque::new() };
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn connect(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let target = match this.read_socket_addr(addr, addrlen, socket.is_ipv6)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if !target.ip().is_loopback() && !target.ip().is_unspecified() {
+            return this.set_last_error_and_return_i32(LibcError("ENETUNREACH"));
+        }
+        // Connecting to the unspecified address means connecting to the local host.
+        let target = socket.source_addr(target);
+
+        let local = match &*socket.state.borrow() {
+            SocketState::Unbound => None,
+            SocketState::Bound(local) => Some(*local),
+            SocketState::Datagram(datagrams) => Some(datagrams.local),
+            SocketState::Listening { .. } => {
+                return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+            }
+            SocketState::Connected(_) => {
+                return this.set_last_error_and_return_i32(LibcError("EISCONN"));
+            }
+        };
+
+        if socket.socket_type == SocketType::Stream {
+            let listener = this
+                .machine
+                .network
+                .lookup(SocketType::Stream, target.port())
+                .filter(|listener| listener.is_ipv6 == socket.is_ipv6);
+            let has_room = listener.as_ref().is_some_and(|listener| {
+                matches!(
+                    &*listener.state.borrow(),
+                    SocketState::Listening { backlog, pending, .. } if pending.len() < *backlog
+                )
+            });
+            let (Some(listener), true) = (listener, has_room) else {
+                return this.set_last_error_and_return_i32(LibcError("ECONNREFUSED"));
+            };
+
+            // Connecting binds the socket to an ephemeral port.
+            let local = match local {
+                Some(local) => local,
+                None =>
+                    match this.machine.network.bind(&socket, 0) {
+                        Ok(port) => SocketAddr::new(socket.loopback(), port),
+                        Err(e) => return this.set_last_error_and_return_i32(e),
+                    },
+            };
+            let local = socket.source_addr(local);
+
+            // The connection is established right away; `accept` just hands out the other end.
+            let mut server_conn =
+                Connection::new(target, local, FileDescriptionRef::downgrade(&socket));
+            // Let `accept` synchronize with us.
+            this.release_clock(|clock| server_conn.clock.join(clock));
+            let server = this.machine.fds.new_ref(InetSocket::new(
+                SocketType::Stream,
+                socket.is_ipv6,
+                SocketState::Connected(server_conn),
+            ));
+            *socket.state.borrow_mut() = SocketState::Connected(Connection::new(
+                local,
+                target,
+                FileDescriptionRef::downgrade(&server),
+            ));
+            match &mut *listener.state.borrow_mut() {
+                SocketState::Listening { pending, .. } => pending.push_back(server),
+                _ => unreachable!(),
+            }
+            notify_socket(this, listener)?;
+        } else {
+            // Connecting a datagram socket just sets its default destination.
+            if local.is_none() {
+                let port = match this.machine.network.bind(&socket, 0) {
+                    Ok(port) => port,
+                    Err(e) => return this.set_last_error_and_return_i32(e),
+                };
+                *socket.state.borrow_mut() =
+                    socket.bound_state(SocketAddr::new(socket.loopback(), port));
+            }
+            match &mut *socket.state.borrow_mut() {
+                SocketState::Datagram(datagrams) => datagrams.peer_addr = Some(target),
+                _ => unreachable!(),
+            }
+        }
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn accept4(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        let mut is_nonblock = false;
+        if flags != 0 {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            // As usual we ignore CLOEXEC.
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+        if flags != 0 {
+            return this.set_last_error_and_return(LibcError("EINVAL"), dest);
+        }
+
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return(e, dest),
+        };
+        if socket.socket_type != SocketType::Stream {
+            return this.set_last_error_and_return(LibcError("EOPNOTSUPP"), dest);
+        }
+        inet_socket_accept(socket, addr, addrlen, is_nonblock, this, dest.clone())
+    }
+
+    fn getsockname(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        let local = socket.state.borrow().local_addr();
+        // An unbound socket reports the unspecified address with port 0.
+        let local = local.unwrap_or_else(|| {
+            if socket.is_ipv6 {
+                SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+            } else {
+                SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)
+            }
+        });
+        this.write_socket_addr(local, addr, addrlen)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getpeername(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        let Some(peer_addr) = socket.state.borrow().peer_addr() else {
+            return this.set_last_error_and_return_i32(LibcError("ENOTCONN"));
+        };
+        this.write_socket_addr(peer_addr, addr, addrlen)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn setsockopt(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        optname: &OpTy<'tcx>,
+        optval: &OpTy<'tcx>,
+        optlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let optname = this.read_scalar(optname)?.to_i32()?;
+        let optval = this.read_pointer(optval)?;
+        let optlen = this.read_scalar(optlen)?.to_u32()?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        if this.socket_option_default(&socket, level, optname).is_none() {
+            throw_unsup_format!(
+                "setsockopt: option {optname:#x} at level {level:#x} is unsupported"
+            );
+        }
+        let int_layout = this.machine.layouts.i32;
+        if u64::from(optlen) < int_layout.size.bytes() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value = this.read_scalar(&this.ptr_to_mplace(optval, int_layout))?.to_i32()?;
+        // The options only affect the performance of a real network, so we just remember them.
+        socket.options.borrow_mut().insert((level, optname), value);
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockopt(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        optname: &OpTy<'tcx>,
+        optval: &OpTy<'tcx>,
+        optlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let optname = this.read_scalar(optname)?.to_i32()?;
+        let optval = this.read_pointer(optval)?;
+        let optlen =
+            this.ptr_to_mplace(this.read_pointer(optlen)?, this.libc_ty_layout("socklen_t"));
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        let Some(default) = this.socket_option_default(&socket, level, optname) else {
+            throw_unsup_format!(
+                "getsockopt: option {optname:#x} at level {level:#x} is unsupported"
+            );
+        };
+        let int_layout = this.machine.layouts.i32;
+        if u64::from(this.read_scalar(&optlen)?.to_u32()?) < int_layout.size.bytes() {
+            throw_unsup_format!("getsockopt: truncating option values is unsupported");
+        }
+        let value = socket.options.borrow().get(&(level, optname)).copied().unwrap_or(default);
+        this.write_int(value, &this.ptr_to_mplace(optval, int_layout))?;
+        this.write_int(int_layout.size.bytes(), &optlen)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn shutdown(&mut self, sockfd: &OpTy<'tcx>, how: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+        let socket = match this.inet_socket(sockfd)? {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        };
+
+        let peer = match &mut *socket.state.borrow_mut() {
+            SocketState::Connected(conn) => {
+                conn.read_shutdown |= read;
+                conn.write_shutdown |= write;
+                conn.peer.upgrade()
+            }
+            _ => return this.set_last_error_and_return_i32(LibcError("ENOTCONN")),
+        };
+        // Blocked receivers on either end may now see EOF.
+        notify_socket(this, socket)?;
+        if let Some(peer) = peer {
+            notify_socket(this, peer)?;
+        }
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Implements `send` and, if `dest_addr` is given, `sendto`.
+    fn send(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        dest_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+
+        // We never raise SIGPIPE, so MSG_NOSIGNAL does not change anything.
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            flags &= !this.eval_libc_i32("MSG_NOSIGNAL");
+        }
+        if flags != 0 {
+            throw_unsup_format!(
+                "send: flags {flags:#x} are unsupported, only MSG_NOSIGNAL is allowed"
+            );
+        }
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // We cap the number of sent bytes to the largest value that we are able to fit in both
+        // the host's and target's `isize`, like `write` does.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let Some(fd) = this.machine.fds.get(sockfd) else {
+            return this.set_last_error_and_return(LibcError("EBADF"), dest);
+        };
+        let finish = {
+            let dest = dest.clone();
+            callback!(
+                @capture<'tcx> {
+                    len: usize,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, result: Result<usize, IoError>| {
+                    match result {
+                        Ok(write_size) => {
+                            assert!(write_size <= len);
+                            // This must fit since `len` fits.
+                            this.write_int(u64::try_from(write_size).unwrap(), &dest)
+                        }
+                        Err(e) => this.set_last_error_and_return(e, &dest),
+                    }
+                }
+            )
+        };
+        let Some(socket) = fd.clone().downcast::<InetSocket>() else {
+            // Without flags, `send` is equivalent to `write` on other file descriptions.
+            if dest_addr.is_some() {
+                throw_unsup_format!("sendto: only IPv4 and IPv6 sockets are supported");
+            }
+            return fd.write(this.machine.communicate(), buf, len, this, finish);
+        };
+        let dest_addr = match dest_addr {
+            Some((addr, addrlen)) =>
+                match this.read_socket_addr(addr, addrlen, socket.is_ipv6)? {
+                    Ok(addr) => Some(addr),
+                    Err(e) => return this.set_last_error_and_return(e, dest),
+                },
+            None => None,
+        };
+        inet_socket_send(socket, buf, len, dest_addr, this, finish)
+    }
+
+    /// Implements `recv` and, if `src_addr` is given, `recvfrom`.
+    fn recv(
+        &mut self,
+        sockfd: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        src_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let sockfd = this.read_scalar(sockfd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+        let (addr, addrlen) = match src_addr {
+            Some((addr, addrlen)) => (this.read_pointer(addr)?, this.read_pointer(addrlen)?),
+            None => (Pointer::null(), Pointer::null()),
+        };
+
+        let msg_peek = this.eval_libc_i32("MSG_PEEK");
+        let peek = flags & msg_peek == msg_peek;
+        flags &= !msg_peek;
+        if flags != 0 {
+            throw_unsup_format!("recv: flags {flags:#x} are unsupported, only MSG_PEEK is allowed");
+        }
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // We cap the number of received bytes to the largest value that we are able to fit in
+        // both the host's and target's `isize`, like `read` does.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let Some(fd) = this.machine.fds.get(sockfd) else {
+            return this.set_last_error_and_return(LibcError("EBADF"), dest);
+        };
+        let Some(socket) = fd.clone().downcast::<InetSocket>() else {
+            // Without flags, `recv` is equivalent to `read` on other file descriptions.
+            if peek || !this.ptr_is_null(addr)? {
+                throw_unsup_format!(
+                    "recv: only IPv4 and IPv6 sockets support receiving with flags"
+                );
+            }
+            let finish = {
+                let dest = dest.clone();
+                callback!(
+                    @capture<'tcx> {
+                        len: usize,
+                        dest: MPlaceTy<'tcx>,
+                    }
+                    |this, result: Result<usize, IoError>| {
+                        match result {
+                            Ok(read_size) => {
+                                assert!(read_size <= len);
+                                // This must fit since `len` fits.
+                                this.write_int(u64::try_from(read_size).unwrap(), &dest)
+                            }
+                            Err(e) => this.set_last_error_and_return(e, &dest),
+                        }
+                    }
+                )
+            };
+            return fd.read(this.machine.communicate(), buf, len, this, finish);
+        };
+        let finish = {
+            let dest = dest.clone();
+            callback!(
+                @capture<'tcx> {
+                    len: usize,
+                    addr: Pointer,
+                    addrlen: Pointer,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, result: Result<(usize, Option<SocketAddr>), IoError>| {
+                    match result {
+                        Ok((read_size, source)) => {
+                            assert!(read_size <= len);
+                            if let Some(source) = source && !this.ptr_is_null(addr)? {
+                                this.write_socket_addr(source, addr, addrlen)?;
+                            }
+                            // This must fit since `len` fits.
+                            this.write_int(u64::try_from(read_size).unwrap(), &dest)
+                        }
+                        Err(e) => this.set_last_error_and_return(e, &dest),
+                    }
+                }
+            )
+        };
+        inet_socket_recv(socket, buf, len, peek, this, finish)
+    }
+}
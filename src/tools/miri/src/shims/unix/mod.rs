@@ -3,6 +3,7 @@
 mod env;
 mod fd;
 mod fs;
+mod inet_socket;
 mod mem;
 mod sync;
 mod thread;
@@ -19,6 +20,7 @@
 pub use self::env::{EvalContextExt as _, UnixEnvVars};
 pub use self::fd::{EvalContextExt as _, UnixFileDescription};
 pub use self::fs::{DirTable, EvalContextExt as _};
+pub use self::inet_socket::{EvalContextExt as _, VirtualNetwork};
 pub use self::linux_like::epoll::EpollInterestTable;
 pub use self::mem::EvalContextExt as _;
 pub use self::sync::EvalContextExt as _;
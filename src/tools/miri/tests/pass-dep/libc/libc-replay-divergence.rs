@@ -0,0 +1,11 @@
+//@ignore-target: windows # no libc
+//@compile-flags: -Zmiri-deterministic-concurrency -Zmiri-replay=tests/pass-dep/libc/libc-replay-divergence.trace
+
+// The trace contains more random bytes than the program asks for, so the replay diverges and
+// the execution continues with live choices.
+fn main() {
+    let mut buf = [0u8; 4];
+    let ret = unsafe { libc::getentropy(buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(ret, 0);
+    assert_ne!(buf, [1, 2, 3, 4]);
+}
@@ -0,0 +1,22 @@
+//@ignore-target: windows # no libc
+//@compile-flags: -Zmiri-preemption-rate=0 -Zmiri-replay=tests/pass-dep/libc/libc-replay-schedule-divergence.trace
+
+// When the main thread blocks on the join, the trace switches to a thread that does not exist,
+// so the replay diverges and the scheduler picks the spawned thread instead.
+
+use std::{mem, ptr};
+
+extern "C" fn thread_start(_null: *mut libc::c_void) -> *mut libc::c_void {
+    ptr::null_mut()
+}
+
+fn main() {
+    unsafe {
+        let mut native: libc::pthread_t = mem::zeroed();
+        assert_eq!(
+            libc::pthread_create(&mut native, ptr::null(), thread_start, ptr::null_mut()),
+            0
+        );
+        assert_eq!(libc::pthread_join(native, ptr::null_mut()), 0);
+    }
+}
@@ -0,0 +1,11 @@
+//@ignore-target: windows # no libc
+//@compile-flags: -Zmiri-deterministic-concurrency -Zmiri-replay=tests/pass-dep/libc/libc-replay.trace
+
+// The random bytes are taken from the replayed trace instead of the seeded RNG.
+fn main() {
+    let mut buf = [0u8; 4];
+    unsafe {
+        assert_eq!(libc::getentropy(buf.as_mut_ptr().cast(), buf.len()), 0);
+    }
+    assert_eq!(buf, [0xde, 0xad, 0xbe, 0xef]);
+}
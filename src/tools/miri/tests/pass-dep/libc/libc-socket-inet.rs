@@ -0,0 +1,492 @@
+//@only-target: linux android # uses SOCK_NONBLOCK and accept4
+// test_blocking_accept depends on a deterministic schedule.
+//@compile-flags: -Zmiri-deterministic-concurrency
+
+use std::mem::{MaybeUninit, size_of};
+use std::thread;
+
+#[path = "../../utils/libc.rs"]
+mod libc_utils;
+use libc_utils::{read_all, write_all};
+
+fn main() {
+    test_tcp();
+    test_tcp_ipv6();
+    test_ephemeral_ports();
+    test_bind_errors();
+    test_connect_refused();
+    test_nonblocking();
+    test_blocking_accept();
+    test_shutdown();
+    test_close_peer();
+    test_peek();
+    test_sockopt();
+    test_udp();
+    test_udp_connected();
+}
+
+/// Turns the return value of a libc function into a `Result`, reading `errno` on failure.
+fn cvt<T: Ord + From<i8>>(ret: T) -> std::io::Result<T> {
+    if ret < T::from(0) { Err(std::io::Error::last_os_error()) } else { Ok(ret) }
+}
+
+fn sockaddr_in(port: u16) -> libc::sockaddr_in {
+    let mut addr: libc::sockaddr_in = unsafe { MaybeUninit::zeroed().assume_init() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_port = port.to_be();
+    addr.sin_addr.s_addr = u32::from_be_bytes([127, 0, 0, 1]).to_be();
+    addr
+}
+
+fn sockaddr_in6(port: u16) -> libc::sockaddr_in6 {
+    let mut addr: libc::sockaddr_in6 = unsafe { MaybeUninit::zeroed().assume_init() };
+    addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+    addr.sin6_port = port.to_be();
+    addr.sin6_addr.s6_addr[15] = 1;
+    addr
+}
+
+/// Creates a socket of the given type and binds it to the given IPv4 loopback port.
+fn bound_socket(ty: libc::c_int, port: u16) -> libc::c_int {
+    let fd = unsafe { cvt(libc::socket(libc::AF_INET, ty, 0)).unwrap() };
+    let addr = sockaddr_in(port);
+    unsafe {
+        cvt(libc::bind(
+            fd,
+            (&raw const addr).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        ))
+        .unwrap()
+    };
+    fd
+}
+
+fn local_port(fd: libc::c_int) -> u16 {
+    let mut addr = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    unsafe { cvt(libc::getsockname(fd, addr.as_mut_ptr().cast(), &mut len)).unwrap() };
+    assert_eq!(len as usize, size_of::<libc::sockaddr_in>());
+    let addr = unsafe { addr.assume_init() };
+    assert_eq!(addr.sin_family, libc::AF_INET as libc::sa_family_t);
+    u16::from_be(addr.sin_port)
+}
+
+fn connect(fd: libc::c_int, port: u16) -> libc::c_int {
+    let addr = sockaddr_in(port);
+    unsafe {
+        libc::connect(
+            fd,
+            (&raw const addr).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    }
+}
+
+/// Returns a connected pair of TCP sockets: the client and the accepted server end.
+fn tcp_pair() -> (libc::c_int, libc::c_int) {
+    let listener = bound_socket(libc::SOCK_STREAM, 0);
+    unsafe { cvt(libc::listen(listener, 1)).unwrap() };
+    let client = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    cvt(connect(client, local_port(listener))).unwrap();
+    let server =
+        unsafe { cvt(libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut())) }.unwrap();
+    cvt(unsafe { libc::close(listener) }).unwrap();
+    (client, server)
+}
+
+fn test_tcp() {
+    let listener = bound_socket(libc::SOCK_STREAM, 0);
+    unsafe { cvt(libc::listen(listener, 16)).unwrap() };
+    let port = local_port(listener);
+
+    let client = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    cvt(connect(client, port)).unwrap();
+    let client_port = local_port(client);
+
+    let mut peer = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let server = unsafe {
+        cvt(libc::accept4(listener, peer.as_mut_ptr().cast(), &mut len, libc::SOCK_CLOEXEC))
+            .unwrap()
+    };
+    let peer = unsafe { peer.assume_init() };
+    assert_eq!(u16::from_be(peer.sin_port), client_port);
+    assert_eq!(local_port(server), port);
+
+    // getpeername on the client returns the listener's address.
+    let mut addr = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut len = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    unsafe { cvt(libc::getpeername(client, addr.as_mut_ptr().cast(), &mut len)).unwrap() };
+    assert_eq!(u16::from_be(unsafe { addr.assume_init() }.sin_port), port);
+
+    // Send data in both directions.
+    let data = b"hello";
+    let res = unsafe { libc::send(client, data.as_ptr().cast(), data.len(), libc::MSG_NOSIGNAL) };
+    assert_eq!(res, 5);
+    let mut buf = [0u8; 5];
+    let res = unsafe { read_all(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 5);
+    assert_eq!(&buf, data);
+
+    let res = unsafe { write_all(server, b"world".as_ptr().cast(), 5) };
+    assert_eq!(res, 5);
+    let res = unsafe { libc::recv(client, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert!(res > 0 && res <= 5);
+
+    cvt(unsafe { libc::close(client) }).unwrap();
+    cvt(unsafe { libc::close(server) }).unwrap();
+    cvt(unsafe { libc::close(listener) }).unwrap();
+}
+
+fn test_tcp_ipv6() {
+    let listener = unsafe { cvt(libc::socket(libc::AF_INET6, libc::SOCK_STREAM, 0)).unwrap() };
+    let addr = sockaddr_in6(0);
+    let addrlen = size_of::<libc::sockaddr_in6>() as libc::socklen_t;
+    unsafe { cvt(libc::bind(listener, (&raw const addr).cast(), addrlen)).unwrap() };
+    unsafe { cvt(libc::listen(listener, 1)).unwrap() };
+
+    let mut local = MaybeUninit::<libc::sockaddr_in6>::uninit();
+    let mut len = addrlen;
+    unsafe { cvt(libc::getsockname(listener, local.as_mut_ptr().cast(), &mut len)).unwrap() };
+    assert_eq!(len, addrlen);
+    let local = unsafe { local.assume_init() };
+    assert_eq!(local.sin6_family, libc::AF_INET6 as libc::sa_family_t);
+    assert_eq!(local.sin6_addr.s6_addr, addr.sin6_addr.s6_addr);
+
+    let client = unsafe { cvt(libc::socket(libc::AF_INET6, libc::SOCK_STREAM, 0)).unwrap() };
+    unsafe { cvt(libc::connect(client, (&raw const local).cast(), addrlen)).unwrap() };
+    let server =
+        unsafe { cvt(libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut())).unwrap() };
+
+    let res = unsafe { write_all(client, b"abc".as_ptr().cast(), 3) };
+    assert_eq!(res, 3);
+    let mut buf = [0u8; 3];
+    let res = unsafe { read_all(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf, b"abc");
+
+    cvt(unsafe { libc::close(client) }).unwrap();
+    cvt(unsafe { libc::close(server) }).unwrap();
+    cvt(unsafe { libc::close(listener) }).unwrap();
+}
+
+fn test_ephemeral_ports() {
+    // Unbound sockets report port 0.
+    let fd = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    assert_eq!(local_port(fd), 0);
+    cvt(unsafe { libc::close(fd) }).unwrap();
+
+    // Binding to port 0 picks distinct ports from the ephemeral range.
+    let a = bound_socket(libc::SOCK_STREAM, 0);
+    let b = bound_socket(libc::SOCK_STREAM, 0);
+    assert!(local_port(a) >= 49152);
+    assert!(local_port(b) >= 49152);
+    assert_ne!(local_port(a), local_port(b));
+
+    // TCP and UDP have separate port spaces.
+    let c = bound_socket(libc::SOCK_DGRAM, local_port(a));
+    assert_eq!(local_port(c), local_port(a));
+
+    // Closing a socket releases its port.
+    let port = local_port(a);
+    cvt(unsafe { libc::close(a) }).unwrap();
+    let a = bound_socket(libc::SOCK_STREAM, port);
+    assert_eq!(local_port(a), port);
+
+    for fd in [a, b, c] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
+
+fn test_bind_errors() {
+    let a = bound_socket(libc::SOCK_STREAM, 0);
+    let fd = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+
+    // Port already in use.
+    let addr = sockaddr_in(local_port(a));
+    let addrlen = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let err = unsafe { cvt(libc::bind(fd, (&raw const addr).cast(), addrlen)).unwrap_err() };
+    assert_eq!(err.raw_os_error(), Some(libc::EADDRINUSE));
+
+    // Only the loopback interface exists.
+    let mut addr = sockaddr_in(0);
+    addr.sin_addr.s_addr = u32::from_be_bytes([10, 0, 0, 1]).to_be();
+    let err = unsafe { cvt(libc::bind(fd, (&raw const addr).cast(), addrlen)).unwrap_err() };
+    assert_eq!(err.raw_os_error(), Some(libc::EADDRNOTAVAIL));
+
+    // Socket operations on something that is not a socket.
+    let mut fds = [-1, -1];
+    cvt(unsafe { libc::pipe(fds.as_mut_ptr()) }).unwrap();
+    let err = unsafe { cvt(libc::listen(fds[0], 1)).unwrap_err() };
+    assert_eq!(err.raw_os_error(), Some(libc::ENOTSOCK));
+
+    for fd in [a, fd, fds[0], fds[1]] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
+
+fn test_connect_refused() {
+    // Nobody is listening on that port.
+    let bound = bound_socket(libc::SOCK_STREAM, 0);
+    let fd = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    let err = cvt(connect(fd, local_port(bound))).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ECONNREFUSED));
+
+    // The backlog is full.
+    unsafe { cvt(libc::listen(bound, 1)).unwrap() };
+    cvt(connect(fd, local_port(bound))).unwrap();
+    let fd2 = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    let err = cvt(connect(fd2, local_port(bound))).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ECONNREFUSED));
+
+    for fd in [bound, fd, fd2] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
+
+fn test_nonblocking() {
+    let listener = bound_socket(libc::SOCK_STREAM | libc::SOCK_NONBLOCK, 0);
+    unsafe { cvt(libc::listen(listener, 1)).unwrap() };
+
+    // No pending connection.
+    let err = unsafe {
+        cvt(libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut())).unwrap_err()
+    };
+    assert_eq!(err.raw_os_error(), Some(libc::EAGAIN));
+
+    let client = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    cvt(connect(client, local_port(listener))).unwrap();
+    let server = unsafe {
+        cvt(libc::accept4(
+            listener,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            libc::SOCK_NONBLOCK,
+        ))
+        .unwrap()
+    };
+    assert_eq!(unsafe { libc::fcntl(server, libc::F_GETFL) }, libc::O_RDWR | libc::O_NONBLOCK);
+
+    // No data available.
+    let mut buf = [0u8; 1];
+    let err =
+        unsafe { cvt(libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0)).unwrap_err() };
+    assert_eq!(err.raw_os_error(), Some(libc::EAGAIN));
+
+    // Fill the buffer of the server.
+    cvt(unsafe { libc::fcntl(client, libc::F_SETFL, libc::O_NONBLOCK) }).unwrap();
+    let data = vec![0u8; 0x10000];
+    loop {
+        let res = unsafe { libc::send(client, data.as_ptr().cast(), data.len(), 0) };
+        if res < 0 {
+            assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EAGAIN));
+            break;
+        }
+    }
+
+    for fd in [listener, client, server] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
+
+fn test_blocking_accept() {
+    let listener = bound_socket(libc::SOCK_STREAM, 0);
+    unsafe { cvt(libc::listen(listener, 1)).unwrap() };
+    let port = local_port(listener);
+
+    let thread = thread::spawn(move || {
+        // This blocks until the main thread connects.
+        let server = unsafe {
+            cvt(libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut())).unwrap()
+        };
+        // This blocks until the main thread sends data.
+        let mut buf = [0u8; 3];
+        let res = unsafe { read_all(server, buf.as_mut_ptr().cast(), buf.len()) };
+        assert_eq!(res, 3);
+        assert_eq!(&buf, b"abc");
+        cvt(unsafe { libc::close(server) }).unwrap();
+    });
+
+    thread::yield_now();
+    let client = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+    cvt(connect(client, port)).unwrap();
+    thread::yield_now();
+    let res = unsafe { write_all(client, b"abc".as_ptr().cast(), 3) };
+    assert_eq!(res, 3);
+    thread.join().unwrap();
+
+    cvt(unsafe { libc::close(client) }).unwrap();
+    cvt(unsafe { libc::close(listener) }).unwrap();
+}
+
+fn test_shutdown() {
+    let (client, server) = tcp_pair();
+
+    // After the client shuts down writing, the server reads EOF.
+    cvt(unsafe { libc::shutdown(client, libc::SHUT_WR) }).unwrap();
+    let mut buf = [0u8; 1];
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 0);
+    // ...and the client cannot write anymore.
+    let err = unsafe {
+        cvt(libc::send(client, buf.as_ptr().cast(), buf.len(), libc::MSG_NOSIGNAL)).unwrap_err()
+    };
+    assert_eq!(err.raw_os_error(), Some(libc::EPIPE));
+    // The other direction still works.
+    let res = unsafe { write_all(server, b"a".as_ptr().cast(), 1) };
+    assert_eq!(res, 1);
+    let res = unsafe { libc::recv(client, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 1);
+
+    cvt(unsafe { libc::close(client) }).unwrap();
+    cvt(unsafe { libc::close(server) }).unwrap();
+}
+
+fn test_close_peer() {
+    let (client, server) = tcp_pair();
+
+    // Data sent before closing can still be received.
+    let res = unsafe { write_all(client, b"ab".as_ptr().cast(), 2) };
+    assert_eq!(res, 2);
+    cvt(unsafe { libc::close(client) }).unwrap();
+    let mut buf = [0u8; 2];
+    let res = unsafe { read_all(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 2);
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 0);
+
+    let err = unsafe {
+        cvt(libc::send(server, buf.as_ptr().cast(), buf.len(), libc::MSG_NOSIGNAL)).unwrap_err()
+    };
+    assert_eq!(err.raw_os_error(), Some(libc::EPIPE));
+
+    cvt(unsafe { libc::close(server) }).unwrap();
+}
+
+fn test_peek() {
+    let (client, server) = tcp_pair();
+
+    let res = unsafe { write_all(client, b"abc".as_ptr().cast(), 3) };
+    assert_eq!(res, 3);
+    let mut buf = [0u8; 3];
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), 1, libc::MSG_PEEK) };
+    assert_eq!(res, 1);
+    assert_eq!(buf[0], b'a');
+    // Peeking did not consume the data.
+    let res = unsafe { read_all(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf, b"abc");
+
+    cvt(unsafe { libc::close(client) }).unwrap();
+    cvt(unsafe { libc::close(server) }).unwrap();
+}
+
+fn test_sockopt() {
+    let fd = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap() };
+
+    let get = |level, name| {
+        let mut val: libc::c_int = -1;
+        let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+        unsafe { cvt(libc::getsockopt(fd, level, name, (&raw mut val).cast(), &mut len)).unwrap() };
+        assert_eq!(len as usize, size_of::<libc::c_int>());
+        val
+    };
+    let set = |level, name, val: libc::c_int| unsafe {
+        cvt(libc::setsockopt(
+            fd,
+            level,
+            name,
+            (&raw const val).cast(),
+            size_of::<libc::c_int>() as libc::socklen_t,
+        ))
+        .unwrap()
+    };
+
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_TYPE), libc::SOCK_STREAM);
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_ERROR), 0);
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_REUSEADDR), 0);
+    set(libc::SOL_SOCKET, libc::SO_REUSEADDR, 1);
+    assert_eq!(get(libc::SOL_SOCKET, libc::SO_REUSEADDR), 1);
+    set(libc::IPPROTO_TCP, libc::TCP_NODELAY, 1);
+    assert_eq!(get(libc::IPPROTO_TCP, libc::TCP_NODELAY), 1);
+    assert_eq!(get(libc::IPPROTO_IP, libc::IP_TTL), 64);
+
+    cvt(unsafe { libc::close(fd) }).unwrap();
+}
+
+fn test_udp() {
+    let a = bound_socket(libc::SOCK_DGRAM, 0);
+    let b = bound_socket(libc::SOCK_DGRAM, 0);
+    let addr_b = sockaddr_in(local_port(b));
+    let addrlen = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+
+    // Datagrams keep their boundaries.
+    for msg in [&b"first"[..], b"second"] {
+        let res = unsafe {
+            libc::sendto(a, msg.as_ptr().cast(), msg.len(), 0, (&raw const addr_b).cast(), addrlen)
+        };
+        assert_eq!(res, msg.len() as isize);
+    }
+    let mut buf = [0u8; 16];
+    let mut src = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut len = addrlen;
+    let res = unsafe {
+        libc::recvfrom(b, buf.as_mut_ptr().cast(), buf.len(), 0, src.as_mut_ptr().cast(), &mut len)
+    };
+    assert_eq!(res, 5);
+    assert_eq!(&buf[..5], b"first");
+    assert_eq!(u16::from_be(unsafe { src.assume_init() }.sin_port), local_port(a));
+    // The rest of a datagram that does not fit into the buffer is discarded.
+    let res = unsafe { libc::recv(b, buf.as_mut_ptr().cast(), 3, 0) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf[..3], b"sec");
+
+    // Datagrams to ports nobody is bound to are dropped.
+    let nobody = sockaddr_in(1);
+    let res =
+        unsafe { libc::sendto(a, buf.as_ptr().cast(), 1, 0, (&raw const nobody).cast(), addrlen) };
+    assert_eq!(res, 1);
+
+    // An unbound socket gets bound when sending.
+    let c = unsafe { cvt(libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0)).unwrap() };
+    let res =
+        unsafe { libc::sendto(c, b"x".as_ptr().cast(), 1, 0, (&raw const addr_b).cast(), addrlen) };
+    assert_eq!(res, 1);
+    assert_ne!(local_port(c), 0);
+    let res = unsafe { libc::recv(b, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 1);
+
+    for fd in [a, b, c] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
+
+fn test_udp_connected() {
+    let a = bound_socket(libc::SOCK_DGRAM, 0);
+    let b = bound_socket(libc::SOCK_DGRAM, 0);
+    let c = bound_socket(libc::SOCK_DGRAM, 0);
+    cvt(connect(a, local_port(b))).unwrap();
+    cvt(connect(b, local_port(a))).unwrap();
+
+    // A connected socket only receives datagrams from its peer.
+    let addr_b = sockaddr_in(local_port(b));
+    let addrlen = size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let res =
+        unsafe { libc::sendto(c, b"x".as_ptr().cast(), 1, 0, (&raw const addr_b).cast(), addrlen) };
+    assert_eq!(res, 1);
+    let res = unsafe { libc::send(a, b"y".as_ptr().cast(), 1, 0) };
+    assert_eq!(res, 1);
+    let mut buf = [0u8; 1];
+    let res = unsafe { libc::recv(b, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 1);
+    assert_eq!(buf[0], b'y');
+
+    // Nothing else is queued.
+    cvt(unsafe { libc::fcntl(b, libc::F_SETFL, libc::O_NONBLOCK) }).unwrap();
+    let err = unsafe { cvt(libc::recv(b, buf.as_mut_ptr().cast(), buf.len(), 0)).unwrap_err() };
+    assert_eq!(err.raw_os_error(), Some(libc::EAGAIN));
+
+    for fd in [a, b, c] {
+        cvt(unsafe { libc::close(fd) }).unwrap();
+    }
+}
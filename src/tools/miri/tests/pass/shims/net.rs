@@ -0,0 +1,50 @@
+//@only-target: linux android # other targets set socket options we do not support
+
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp();
+    test_udp();
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, peer) = listener.accept().unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), peer);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "ping");
+        stream.write_all(b"pong").unwrap();
+    });
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+    stream.set_nodelay(true).unwrap();
+    stream.write_all(b"ping").unwrap();
+    stream.shutdown(Shutdown::Write).unwrap();
+    let mut buf = String::new();
+    stream.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "pong");
+    server.join().unwrap();
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    a.send_to(b"hello", b.local_addr().unwrap()).unwrap();
+    let mut buf = [0; 16];
+    let (len, src) = b.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"hello");
+    assert_eq!(src, a.local_addr().unwrap());
+
+    b.connect(src).unwrap();
+    b.send(b"world").unwrap();
+    let len = a.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"world");
+}
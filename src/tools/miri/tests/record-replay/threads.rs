@@ -0,0 +1,24 @@
+//! The order in which the threads run depends on the scheduler and on preemption, so the replay
+//! has to reproduce the recorded schedule to print the same order.
+
+use std::sync::Mutex;
+use std::thread;
+
+static ORDER: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+fn main() {
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            thread::spawn(move || {
+                for _ in 0..3 {
+                    ORDER.lock().unwrap().push(i);
+                    thread::yield_now();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("{:?}", ORDER.lock().unwrap());
+}
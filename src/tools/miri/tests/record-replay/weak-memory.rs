@@ -0,0 +1,29 @@
+//! Store buffering: each thread may read an outdated value from the store buffer, so the replay
+//! has to reproduce the recorded loads to print the same outcomes.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+
+static X: AtomicUsize = AtomicUsize::new(0);
+static Y: AtomicUsize = AtomicUsize::new(0);
+
+fn store_buffering() -> (usize, usize) {
+    X.store(0, Relaxed);
+    Y.store(0, Relaxed);
+    let a = thread::spawn(|| {
+        X.store(1, Relaxed);
+        Y.load(Relaxed)
+    });
+    let b = thread::spawn(|| {
+        Y.store(1, Relaxed);
+        X.load(Relaxed)
+    });
+    (a.join().unwrap(), b.join().unwrap())
+}
+
+fn main() {
+    for _ in 0..10 {
+        println!("{:?}", store_buffering());
+    }
+}
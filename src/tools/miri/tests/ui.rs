@@ -8,7 +8,7 @@
 use colored::*;
 use regex::bytes::Regex;
 use ui_test::build_manager::BuildManager;
-use ui_test::color_eyre::eyre::{Context, Result};
+use ui_test::color_eyre::eyre::{Context, Result, ensure};
 use ui_test::custom_flags::edition::Edition;
 use ui_test::dependencies::DependencyBuilder;
 use ui_test::per_test_config::TestConfig;
@@ -303,6 +303,60 @@ fn ui(
         .with_context(|| format!("ui tests in {path} for {target} failed"))
 }
 
+/// Records executions of the programs in `tests/record-replay` with `-Zmiri-record` for a few
+/// seeds, and checks that replaying the traces with `-Zmiri-replay` reproduces their output
+/// without diverging.
+fn record_replay(target: &str, tmpdir: &Path) -> Result<()> {
+    let msg = format!("## Running record-replay tests for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let sysroot =
+        env::var("MIRI_SYSROOT").expect("MIRI_SYSROOT must be set to run the ui test suite");
+    let mut files = std::fs::read_dir("tests/record-replay")?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+    for file in files.iter().filter(|file| file.extension().is_some_and(|ext| ext == "rs")) {
+        let name = file.file_stem().unwrap().to_str().unwrap();
+        for seed in 0..8 {
+            let trace = tmpdir.join(format!("{name}-{seed}.trace"));
+            let run = |flags: &[String]| {
+                Command::new(miri_path())
+                    .arg(file)
+                    .args(["--edition", "2021", "--target", target])
+                    .arg(format!("--sysroot={sysroot}"))
+                    .args(flags)
+                    .output()
+            };
+            let recorded = run(&[
+                format!("-Zmiri-seed={seed}"),
+                format!("-Zmiri-record={}", trace.display()),
+            ])?;
+            ensure!(
+                recorded.status.success(),
+                "recording {} with seed {seed} failed:\n{}",
+                file.display(),
+                String::from_utf8_lossy(&recorded.stderr)
+            );
+            let replayed = run(&[format!("-Zmiri-replay={}", trace.display())])?;
+            let stderr = String::from_utf8_lossy(&replayed.stderr);
+            ensure!(
+                replayed.status.success() && !stderr.contains("diverged"),
+                "replaying {} with seed {seed} failed:\n{stderr}",
+                file.display()
+            );
+            ensure!(
+                replayed.stdout == recorded.stdout,
+                "replaying {} with seed {seed} printed\n{}\ninstead of\n{}",
+                file.display(),
+                String::from_utf8_lossy(&replayed.stdout),
+                String::from_utf8_lossy(&recorded.stdout)
+            );
+        }
+    }
+    Ok(())
+}
+
 fn get_host() -> String {
     rustc_version::VersionMeta::for_command(std::process::Command::new(miri_path()))
         .expect("failed to parse rustc version info")
@@ -334,6 +388,7 @@ fn main() -> Result<()> {
     ui(Mode::Panic, "tests/panic", &target, WithDependencies, tmpdir.path())?;
     ui(Mode::Fail, "tests/fail", &target, WithoutDependencies, tmpdir.path())?;
     ui(Mode::Fail, "tests/fail-dep", &target, WithDependencies, tmpdir.path())?;
+    record_replay(&target, tmpdir.path())?;
     if cfg!(all(unix, feature = "native-lib")) && target == host {
         ui(Mode::Pass, "tests/native-lib/pass", &target, WithoutDependencies, tmpdir.path())?;
         ui(Mode::Fail, "tests/native-lib/fail", &target, WithoutDependencies, tmpdir.path())?;
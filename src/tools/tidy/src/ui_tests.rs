@@ -119,7 +119,7 @@ fn recursively_check_ui_tests<'issues>(
             // must strip all of them.
             let testname =
                 file_path.file_name().unwrap().to_str().unwrap().split_once('.').unwrap().0;
-            if ext == "stderr" || ext == "stdout" || ext == "fixed" {
+            if ext == "stderr" || ext == "stdout" || ext == "fixed" || ext == "snap" {
                 check_stray_output_snapshot(bad, file_path, testname);
                 check_empty_output_snapshot(bad, file_path);
             }
@@ -144,6 +144,7 @@ fn check_unexpected_extension(bad: &mut bool, file_path: &Path, ext: &str) {
         "svg",    // expected svg file, corresponds to a rs file, equivalent to stderr
         "stdout", // expected stdout file, corresponds to a rs file
         "fixed",  // expected source file after applying fixes
+        "snap",   // expected structural diagnostics snapshot, corresponds to a rs file
         "md",     // test directory descriptions
         "ftl",    // translation tests
     ];
@@ -0,0 +1,11 @@
+//@ compile-flags: -Copt-level=3 -Zmir-outline-cold-paths
+
+#![crate_type = "lib"]
+
+#[inline]
+pub fn checked_sub(x: u32, y: u32) -> u32 {
+    if x > y {
+        panic!("x {} is larger than y {}", x, y);
+    }
+    y - x
+}
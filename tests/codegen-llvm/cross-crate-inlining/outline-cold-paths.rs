@@ -0,0 +1,18 @@
+//@ compile-flags: -Copt-level=3 -Csymbol-mangling-version=v0
+//@ aux-build:outline_cold_paths.rs
+
+#![crate_type = "lib"]
+
+extern crate outline_cold_paths;
+
+// Check that a function with a cold path outlined by `-Zmir-outline-cold-paths` is inlined into
+// another crate, which then defines its own copy of the outlined path.
+// CHECK: define {{.*}}void @[[COLD:_R.*11checked_sub.*4cold.*]]({{.*}}) unnamed_addr
+
+// CHECK-LABEL: @outer(
+#[no_mangle]
+pub fn outer(x: u32, y: u32) -> u32 {
+    // CHECK-NOT: call {{.*}}11checked_sub(
+    // CHECK: call void @[[COLD]](
+    outline_cold_paths::checked_sub(x, y)
+}
@@ -0,0 +1,22 @@
+// Checks that `-Zmir-outline-cold-paths` moves the formatting of a panic message into a cold
+// function which is not inlined.
+//@ compile-flags: -Copt-level=3 -Zmir-outline-cold-paths -Csymbol-mangling-version=v0
+
+#![crate_type = "lib"]
+
+// CHECK: define {{.*}}void @[[COLD:_R.*5check.*4cold]]({{.*}}) unnamed_addr #[[ATTRS:[0-9]+]]
+// CHECK: call {{.*}}@{{.*}}panic_fmt
+
+// CHECK-LABEL: @check(
+#[no_mangle]
+pub fn check(x: u32, y: u32) -> u32 {
+    // CHECK-NOT: panic_fmt
+    // CHECK: call void @[[COLD]](i32 {{.*}}%x, i32 {{.*}}%y)
+    // CHECK-NOT: panic_fmt
+    if x > y {
+        panic!("x {} is larger than y {}", x, y);
+    }
+    y - x
+}
+
+// CHECK: attributes #[[ATTRS]] = { {{.*}}cold{{.*}} }
@@ -0,0 +1,30 @@
+//@ incremental
+//@ compile-flags: -Copt-level=0 -Zcgu-profile={{src-base}}/partitioning/profile-guided.folded
+
+#![crate_type = "lib"]
+
+// This test ensures that the hot callees of a `-Zcgu-profile` profile are moved
+// into the CGU of their callers, together with the inlined items only they use,
+// and that other items stay in the CGU of their module.
+
+pub mod caller {
+    //~ MONO_ITEM fn caller::run @@ profile_guided-caller[External]
+    pub fn run() {
+        crate::callee::hot();
+        crate::callee::cold();
+    }
+}
+
+pub mod callee {
+    //~ MONO_ITEM fn callee::hot @@ profile_guided-caller[External]
+    pub fn hot() {
+        helper();
+    }
+
+    //~ MONO_ITEM fn callee::helper @@ profile_guided-caller[Internal]
+    #[inline(always)]
+    fn helper() {}
+
+    //~ MONO_ITEM fn callee::cold @@ profile_guided-callee[External]
+    pub fn cold() {}
+}
@@ -0,0 +1,116 @@
+//@ test-mir-pass: BoxToStack
+//@ compile-flags: -O -Zmir-enable-passes=+Inline
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+#![feature(liballoc_internals)]
+
+// EMIT_MIR box_to_stack.main.BoxToStack.diff
+fn main() {
+    // CHECK-LABEL: fn main(
+    // CHECK: debug x => [[x:_.*]];
+    // CHECK-NOT: exchange_malloc
+    // CHECK-NOT: ShallowInitBox
+    // CHECK: [[contents:_.*]] = const 42_i32;
+    // CHECK: [[tmp:_.*]] = copy [[contents]];
+    // CHECK: [[x]] = Add(move [[tmp]], const 0_i32);
+    // CHECK-NOT: drop(
+    let x = *(std::boxed::box_new(42)) + 0;
+}
+
+// EMIT_MIR box_to_stack.returned.BoxToStack.diff
+fn returned() -> Box<i32> {
+    // CHECK-LABEL: fn returned(
+    // CHECK: [[ptr:_.*]] = alloc::alloc::exchange_malloc(
+    // CHECK: [[box:_.*]] = ShallowInitBox(move [[ptr]], i32);
+    // CHECK: _0 = move [[box]];
+    std::boxed::box_new(7)
+}
+
+// EMIT_MIR box_to_stack.box_new.BoxToStack.diff
+fn box_new() -> i32 {
+    // CHECK-LABEL: fn box_new(
+    // CHECK: debug b => [[contents:_.*]];
+    // CHECK-NOT: Box::<i32>::new(
+    // CHECK: [[contents]] = const 42_i32;
+    // CHECK: _0 = copy [[contents]];
+    // CHECK-NOT: drop(
+    let b = Box::new(42);
+    *b
+}
+
+// EMIT_MIR box_to_stack.passed.BoxToStack.diff
+fn passed() {
+    // CHECK-LABEL: fn passed(
+    // CHECK: [[box:_.*]] = Box::<i32>::new(const 5_i32)
+    // CHECK: consume(move [[box]])
+    consume(Box::new(5));
+}
+
+#[inline(never)]
+fn consume(_: Box<i32>) {}
+
+// EMIT_MIR box_to_stack.stored.BoxToStack.diff
+fn stored() -> i32 {
+    // CHECK-LABEL: fn stored(
+    // CHECK: [[box:_.*]] = Box::<i32>::new(const 1_i32)
+    // CHECK: [[pair:_.*]] = (move [[box]], const 2_i32);
+    // CHECK: drop([[pair]])
+    let pair = (Box::new(1), 2);
+    pair.1
+}
+
+// EMIT_MIR box_to_stack.in_loop.BoxToStack.diff
+fn in_loop(n: i32) -> i32 {
+    // The box of the previous iteration is still live when the next one is allocated.
+    // CHECK-LABEL: fn in_loop(
+    // CHECK: debug b => [[b:_.*]];
+    // CHECK: [[box:_.*]] = Box::<i32>::new(
+    // CHECK: [[b]] = move [[box]];
+    // CHECK: drop([[b]])
+    let mut b;
+    let mut i = 0;
+    loop {
+        b = Box::new(i);
+        i += 1;
+        if i == n {
+            break;
+        }
+    }
+    *b
+}
+
+struct D(i32);
+
+impl Drop for D {
+    fn drop(&mut self) {}
+}
+
+// EMIT_MIR box_to_stack.needs_drop.BoxToStack.diff
+fn needs_drop() -> i32 {
+    // CHECK-LABEL: fn needs_drop(
+    // CHECK: debug b => [[contents:_.*]];
+    // CHECK-NOT: Box::<D>::new(
+    // CHECK: [[contents]] = move {{_.*}};
+    // CHECK: _0 = copy ([[contents]].0: i32);
+    // CHECK: drop([[contents]])
+    let b = Box::new(D(3));
+    b.0
+}
+
+// EMIT_MIR box_to_stack.at_limit.BoxToStack.diff
+fn at_limit() {
+    // CHECK-LABEL: fn at_limit(
+    // CHECK: debug _b => [[contents:_.*]];
+    // CHECK-NOT: Box::<[u8; 4096]>::new(
+    // CHECK: [[contents]] = move {{_.*}};
+    // CHECK-NOT: drop(
+    let _b = Box::new([0u8; 4096]);
+}
+
+// EMIT_MIR box_to_stack.too_large.BoxToStack.diff
+fn too_large() {
+    // CHECK-LABEL: fn too_large(
+    // CHECK: [[box:_.*]] = Box::<[u8; 4097]>::new(
+    // CHECK: drop([[box]])
+    let _b = Box::new([0u8; 4097]);
+}
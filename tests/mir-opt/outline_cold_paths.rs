@@ -0,0 +1,50 @@
+//@ test-mir-pass: OutlineColdPaths
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+#![crate_type = "lib"]
+
+#[inline(never)]
+fn describe(x: u32) -> u64 {
+    x as u64
+}
+
+#[inline(never)]
+fn fail(_code: u64, _y: u32) -> ! {
+    loop {}
+}
+
+// EMIT_MIR outline_cold_paths.check.OutlineColdPaths.diff
+pub fn check(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn check(
+    // CHECK: bb1: {
+    // CHECK-NEXT: = const {check as fn(u32, u32) -> !}(move _1, move _2)
+    // CHECK-NOT: describe(
+    // CHECK-NOT: fail(
+    if x > y {
+        fail(describe(x), y);
+    }
+    y - x
+}
+
+// The block calling `describe` is reachable from both branches, so it is outlined once and the
+// cheap branches jump to the call of the outlined path.
+// EMIT_MIR outline_cold_paths.shared.OutlineColdPaths.diff
+pub fn shared(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn shared(
+    // CHECK: _3 = const 1_u32;
+    // CHECK-NEXT: goto -> [[cold:bb.*]];
+    // CHECK: _3 = const 2_u32;
+    // CHECK: goto -> [[cold]];
+    // CHECK: [[cold]]: {
+    // CHECK-NEXT: = const {shared as fn(u32, u32) -> !}(move _2, move _3)
+    // CHECK-NOT: describe(
+    // CHECK-NOT: fail(
+    let code = if x > y {
+        1
+    } else if x == y {
+        2
+    } else {
+        return y - x;
+    };
+    fail(describe(code), y)
+}
@@ -0,0 +1,14 @@
+#![feature(rustc_attrs)]
+// When built with `--cfg second`, the previous session of this crate must have
+// been reused: its query results are green and its object file is not rebuilt.
+#![rustc_partition_reused(module = "lib", cfg = "second")]
+
+#[rustc_clean(cfg = "second")]
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[rustc_clean(cfg = "second")]
+pub fn mul(a: u32, b: u32) -> u32 {
+    a * b
+}
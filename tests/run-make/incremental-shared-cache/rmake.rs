@@ -0,0 +1,52 @@
+//@ needs-target-std
+//
+// A session exported to the incremental shared cache by one checkout of a crate
+// should be imported by another checkout, in another directory and with an empty
+// incremental directory, and its results should be reused. `lib.rs` asserts the
+// reuse with `#[rustc_clean]` and `#[rustc_partition_reused]`.
+
+use run_make_support::{cwd, rfs, rustc};
+
+fn compile(checkout: &str, incremental: &str, args: &[&str]) -> String {
+    rustc()
+        .current_dir(checkout)
+        .input("lib.rs")
+        .crate_type("lib")
+        .incremental(cwd().join(incremental))
+        .arg(format!("-Zincremental-shared-cache={}", cwd().join("shared").display()))
+        .arg("-Zincremental-info")
+        .arg("-Zquery-dep-graph")
+        .args(args)
+        .run()
+        .stderr_utf8()
+}
+
+fn main() {
+    for checkout in ["first", "second"] {
+        rfs::create_dir(checkout);
+        rfs::copy("lib.rs", format!("{checkout}/lib.rs"));
+    }
+
+    let first = compile("first", "incr-first", &[]);
+    assert!(first.contains("[incremental] shared cache: "), "{first}");
+    assert!(rfs::read_dir("shared/manifests").count() > 0);
+
+    // Without debuginfo, the location of the checkout doesn't matter.
+    let second = compile("second", "incr-second", &["--cfg", "second"]);
+    assert!(!second.contains("shared cache: 0 files imported"), "{second}");
+    assert!(second.contains("files imported"), "{second}");
+
+    // With debuginfo, the working directory is embedded in the output, so
+    // checkouts only share their results if it is remapped.
+    compile("first", "incr-first-g", &["-g"]);
+    let unmapped = compile("second", "incr-second-g", &["-g"]);
+    assert!(!unmapped.contains("files imported"), "{unmapped}");
+
+    let remap = |checkout: &str| {
+        format!("--remap-path-prefix={}=/checkout", cwd().join(checkout).display())
+    };
+    compile("first", "incr-first-remapped", &["-g", &remap("first")]);
+    let second =
+        compile("second", "incr-second-remapped", &["-g", &remap("second"), "--cfg", "second"]);
+    assert!(second.contains("files imported"), "{second}");
+}
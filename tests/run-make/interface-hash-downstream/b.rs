@@ -0,0 +1,3 @@
+pub fn count_and_double(x: u32) -> (u32, u32) {
+    (a::count(x), a::double(x))
+}
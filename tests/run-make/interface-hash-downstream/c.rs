@@ -0,0 +1,4 @@
+fn main() {
+    let (count, double) = b::count_and_double(2);
+    println!("{count} {double}");
+}
@@ -0,0 +1,54 @@
+// Checks that crates record the interface hash of their dependencies, and that it is only
+// informational: with `c` depending on `b` depending on `a`, `c` still cannot be built against a
+// rebuilt `a` without rebuilding `b`, even if only private items of `a` changed.
+
+//@ ignore-cross-compile
+
+use run_make_support::{rfs, run, rust_lib_name, rustc};
+
+const A: &str = r#"
+pub fn count(x: u32) -> u32 {
+    helper(x)
+}
+
+#[inline]
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn helper(x: u32) -> u32 {
+    x + 1
+}
+"#;
+
+/// Builds `a` from `source` and returns its interface hash.
+fn build_a(source: &str) -> String {
+    rfs::write("a.rs", source);
+    rustc().input("a.rs").crate_type("rlib").run();
+    let root = rustc().arg("-Zls=root").input(rust_lib_name("a")).run().stdout_utf8();
+    let line = root.lines().find(|line| line.starts_with("interface_hash ")).unwrap();
+    line.split_whitespace().nth(1).unwrap().to_owned()
+}
+
+fn main() {
+    let interface_hash = build_a(A);
+    rustc().input("b.rs").crate_type("rlib").extern_("a", rust_lib_name("a")).run();
+    rustc().input("c.rs").extern_("b", rust_lib_name("b")).run();
+    run("c").assert_stdout_equals("3 4\n");
+
+    // `b` records the interface hash of `a` next to its crate hash.
+    rustc()
+        .arg("-Zls=root")
+        .input(rust_lib_name("b"))
+        .run()
+        .assert_stdout_contains(format!("interface_hash {interface_hash}"));
+
+    // Changing the body of a private function keeps the interface of `a`, but dependencies are
+    // still matched on their crate hash, so `b` has to be rebuilt.
+    assert_eq!(build_a(&A.replace("x + 1", "x + 2")), interface_hash);
+    rustc()
+        .input("c.rs")
+        .extern_("b", rust_lib_name("b"))
+        .run_fail()
+        .assert_stderr_contains("found possibly newer version of crate `a` which `b` depends on");
+}
@@ -0,0 +1,69 @@
+// Checks that the interface hash stored in the crate metadata only changes when something
+// downstream crates can observe changes, unlike the crate hash.
+
+//@ ignore-cross-compile
+
+use run_make_support::{rfs, rust_lib_name, rustc};
+
+const ORIGINAL: &str = r#"
+pub struct Counter {
+    count: u32,
+}
+
+pub fn count(counter: &Counter) -> u32 {
+    helper(counter.count)
+}
+
+#[inline]
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn helper(x: u32) -> u32 {
+    x + 1
+}
+"#;
+
+/// Compiles `source` and returns its crate hash and its interface hash.
+fn hashes(source: &str) -> (String, String) {
+    rfs::write("lib.rs", source);
+    rustc().input("lib.rs").crate_type("rlib").crate_name("foo").run();
+    let root = rustc().arg("-Zls=root").input(rust_lib_name("foo")).run().stdout_utf8();
+    let hash = |prefix: &str| {
+        let line = root.lines().find(|line| line.starts_with(prefix)).unwrap();
+        line.split_whitespace().nth(1).unwrap().to_owned()
+    };
+    (hash("hash "), hash("interface_hash "))
+}
+
+fn main() {
+    let (crate_hash, interface_hash) = hashes(ORIGINAL);
+
+    // Changing the body of a private function, and moving items around, does not change the
+    // interface.
+    let private_change =
+        ORIGINAL.replace("x + 1", "x + 2").replace("\npub fn count", "\n\npub fn count");
+    let (new_crate_hash, new_interface_hash) = hashes(&private_change);
+    assert_ne!(crate_hash, new_crate_hash);
+    assert_eq!(interface_hash, new_interface_hash);
+
+    // The body of an inlinable function is part of the interface.
+    let (_, new_interface_hash) = hashes(&ORIGINAL.replace("x * 2", "x * 3"));
+    assert_ne!(interface_hash, new_interface_hash);
+
+    // So are the signatures of public functions...
+    let (_, new_interface_hash) = hashes(
+        &ORIGINAL
+            .replace("-> u32 {\n    helper", "-> u64 {\n    helper")
+            .replace("helper(counter.count)", "helper(counter.count) as u64"),
+    );
+    assert_ne!(interface_hash, new_interface_hash);
+
+    // ... and the private fields of public types, which determine their layout.
+    let (_, new_interface_hash) = hashes(
+        &ORIGINAL
+            .replace("count: u32,", "count: u64,")
+            .replace("helper(counter.count)", "helper(counter.count as u32)"),
+    );
+    assert_ne!(interface_hash, new_interface_hash);
+}
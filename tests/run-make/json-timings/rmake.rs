@@ -0,0 +1,32 @@
+//@ ignore-cross-compile
+
+// Checks that `--json=timings` reports the start and end of every major compilation section,
+// as well as the slowest items of the sections that track them.
+
+use run_make_support::rustc;
+
+fn main() {
+    let output = rustc()
+        .input("main.rs")
+        .error_format("json")
+        .json("timings")
+        .arg("-Zunstable-options")
+        .run();
+
+    for section in
+        ["parse", "expand", "resolve", "typeck", "borrowck", "metadata", "codegen", "link"]
+    {
+        output
+            .assert_stderr_contains(format!(
+                r#"{{"$message_type":"section_timing","name":"{section}","event":"start""#
+            ))
+            .assert_stderr_contains(format!(
+                r#"{{"$message_type":"section_timing","name":"{section}","event":"end""#
+            ));
+    }
+    output
+        .assert_stderr_contains(r#"{"$message_type":"item_timing","section":"typeck","item":"add""#)
+        .assert_stderr_contains(
+            r#"{"$message_type":"item_timing","section":"borrowck","item":"main""#,
+        );
+}
@@ -0,0 +1,43 @@
+// Check libtest's CTRF (JSON) output against snapshots.
+
+//@ ignore-cross-compile
+//@ needs-unwind (test file contains #[should_panic] test)
+
+use run_make_support::{cmd, diff, rustc, serde_json};
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+
+    run_tests(&[], "output-default.json");
+    run_tests(&["--show-output"], "output-stdout-success.json");
+}
+
+#[track_caller]
+fn run_tests(extra_args: &[&str], expected_file: &str) {
+    let cmd_out = cmd("./f")
+        .env("RUST_BACKTRACE", "0")
+        .args(&["-Zunstable-options", "--test-threads=1", "--format=ctrf"])
+        .args(extra_args)
+        .run_fail();
+    let test_stdout = &cmd_out.stdout_utf8();
+
+    // Verify that the test process output is a single JSON document.
+    let report: serde_json::Value = match serde_json::from_str(test_stdout) {
+        Ok(report) => report,
+        Err(e) => panic!(
+            "could not parse JSON: {e}\n\
+            \n\
+            === STDOUT ===\n\
+            {test_stdout}\
+            =============="
+        ),
+    };
+    assert_eq!(report["results"]["summary"]["tests"], 6);
+
+    diff()
+        .expected_file(expected_file)
+        .actual_text("stdout", test_stdout)
+        .normalize(r#"(?<prefix>"(start|stop)": )[0-9]+"#, r#"${prefix}"$$TIME""#)
+        .normalize(r"thread '(?P<name>.*?)' \(\d+\) panicked", "thread '$name' ($$TID) panicked")
+        .run();
+}
@@ -0,0 +1,11 @@
+trait Greet {}
+
+struct Wrapper<T>(T);
+
+impl<T: Greet> Greet for Wrapper<T> {}
+
+fn greet<T: Greet>(_: T) {}
+
+fn main() {
+    greet(Wrapper(1u8));
+}
@@ -0,0 +1,22 @@
+// Checks that `-Znext-solver-dump` writes the proof tree of an unsatisfied obligation,
+// including the impl candidate and the nested goal it was rejected because of.
+
+use run_make_support::{has_extension, rfs, rustc, shallow_find_files};
+
+fn main() {
+    rustc().input("main.rs").arg("-Znext-solver").arg("-Znext-solver-dump=proof-trees").run_fail();
+
+    let dumps = shallow_find_files("proof-trees", |path| has_extension(path, "json"));
+    assert_eq!(dumps.len(), 1, "expected a single proof tree, found {dumps:?}");
+    let dump = rfs::read_to_string(&dumps[0]);
+    for expected in [
+        r#""obligation": "Wrapper<u8>: Greet""#,
+        r#""kind": "unsatisfied""#,
+        r#""kind": "impl `"#,
+        r#""rejected_because": "the nested goal `u8: Greet` does not hold""#,
+        r#""goal": "u8: Greet""#,
+        r#""result": "no solution""#,
+    ] {
+        assert!(dump.contains(expected), "expected `{expected}` in:\n{dump}");
+    }
+}
@@ -0,0 +1,38 @@
+// Checks that `-Zself-profile-format=chrome` writes a Chrome trace-event JSON file
+// containing query events, generic activities and artifact sizes.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{has_extension, rfs, rustc, shallow_find_files};
+
+fn main() {
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .arg("-Zself-profile=profiles")
+        .arg("-Zself-profile-format=chrome")
+        .run();
+
+    let traces = shallow_find_files("profiles", |path| has_extension(path, "json"));
+    assert_eq!(traces.len(), 1, "expected a single trace, found {traces:?}");
+    let trace: Value = serde_json::from_str(&rfs::read_to_string(&traces[0])).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+
+    let has_event = |category: &str, name: &str, phase: &str| {
+        events.iter().any(|event| {
+            event["cat"] == category
+                && event["name"] == name
+                && event["ph"] == phase
+                && event["ts"].is_number()
+                && event["tid"].is_number()
+        })
+    };
+    assert!(has_event("Query", "typeck", "X"), "no `typeck` query event in {events:#?}");
+    assert!(
+        has_event("GenericActivity", "parse_crate", "X"),
+        "no `parse_crate` event in {events:#?}"
+    );
+    assert!(
+        has_event("ArtifactSize", "codegen_unit_size_estimate", "C"),
+        "no CGU size estimate in {events:#?}"
+    );
+}
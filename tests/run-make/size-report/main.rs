@@ -0,0 +1,10 @@
+#[inline(never)]
+fn generic<T: std::fmt::Debug>(t: T) {
+    println!("{t:?}");
+}
+
+fn main() {
+    generic(1u8);
+    generic("two");
+    generic([3u32; 3]);
+}
@@ -0,0 +1,40 @@
+// Checks that `-Zsize-report` attributes the symbols of the linked binary to crates and to the
+// generic functions they are instances of, in both the JSON and the text format.
+
+//@ only-elf
+// Other object file formats may not have a symbol table in the binary itself.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rfs, rustc};
+
+fn main() {
+    rustc().input("main.rs").opt_level("0").arg("-Zsize-report=json").run();
+
+    let report: Value =
+        serde_json::from_str(&rfs::read_to_string("main.size-report.json")).unwrap();
+    assert!(report["total_size"].as_u64().unwrap() > 0);
+
+    let crates = report["crates"].as_array().unwrap();
+    for crate_name in ["main", "std", "core"] {
+        assert!(
+            crates.iter().any(|krate| krate["name"] == crate_name),
+            "no `{crate_name}` crate in {crates:#?}"
+        );
+    }
+
+    let functions = report["functions"].as_array().unwrap();
+    let generic = functions
+        .iter()
+        .find(|function| function["name"] == "generic" && function["crate"] == "main")
+        .unwrap_or_else(|| panic!("no `generic` function in {functions:#?}"));
+    assert_eq!(generic["instances"], 3);
+
+    let symbols = report["symbols"].as_array().unwrap();
+    let instances = symbols.iter().filter(|symbol| symbol["function"] == "generic").count();
+    assert_eq!(instances, 3, "expected three instances of `generic` in {symbols:#?}");
+
+    rustc().input("main.rs").opt_level("0").arg("-Zsize-report").run();
+    let report = rfs::read_to_string("main.size-report.txt");
+    assert!(report.starts_with("size report for `"), "{report}");
+    assert!(report.lines().any(|line| line.ends_with("  generic (main)")), "{report}");
+}
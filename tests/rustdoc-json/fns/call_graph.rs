@@ -0,0 +1,45 @@
+//@ compile-flags: -Z json-call-graph
+
+//@ set callee = "$.index[?(@.name=='callee')].id"
+pub fn callee() {}
+
+//@ set Dog = "$.index[?(@.name=='Dog')].id"
+pub struct Dog;
+
+//@ set LIMIT = "$.index[?(@.name=='LIMIT')].id"
+pub const LIMIT: u32 = 4;
+
+pub trait Speak {
+    fn speak(&self);
+}
+
+//@ set trait_speak = "$.index[?(@.name=='Speak')].inner.trait.items[0]"
+//@ set dog_speak = "$.index[?(@.docs=='Dog speaks')].id"
+impl Speak for Dog {
+    /// Dog speaks
+    fn speak(&self) {}
+}
+
+//@ is "$.index[?(@.name=='caller')].inner.function.references[0].id" $callee
+//@ is "$.index[?(@.name=='caller')].inner.function.references[0].kind" '"call"'
+//@ has "$.index[?(@.name=='caller')].inner.function.references[*].id" $Dog
+//@ has "$.index[?(@.name=='caller')].inner.function.references[*].id" $LIMIT
+//@ has "$.index[?(@.name=='caller')].inner.function.references[*].id" $trait_speak
+//@ is "$.index[?(@.name=='caller')].inner.function.references[?(@.kind.resolved_trait_method)].id" $dog_speak
+//@ is "$.index[?(@.name=='caller')].inner.function.references[?(@.kind.resolved_trait_method)].kind.resolved_trait_method.trait_method" $trait_speak
+pub fn caller() -> u32 {
+    callee();
+    let dog = Dog;
+    dog.speak();
+    LIMIT
+}
+
+// Calls through a generic parameter cannot be resolved to an impl.
+//@ has "$.index[?(@.name=='generic')].inner.function.references[*].id" $trait_speak
+//@ !has "$.index[?(@.name=='generic')].inner.function.references[*].id" $dog_speak
+pub fn generic<T: Speak>(t: &T) {
+    t.speak();
+}
+
+// Functions without a body have no references.
+//@ is "$.index[?(@.name=='speak' && @.inner.function.has_body==false)].inner.function.references" null
@@ -0,0 +1,114 @@
+//@ run-pass
+//! Test that the optimized MIR of a function can be replaced through rustc_public.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::mir::visit::Location;
+use rustc_public::mir::{
+    Body, ConstOperand, MutMirVisitor, Mutability, Operand, Rvalue, Statement, StatementKind,
+};
+use rustc_public::rustc_internal;
+use rustc_public::ty::{ConstantKind, MirConst, RigidTy, Ty, TyKind, UintTy};
+use rustc_public::{CrateDef, CrateItem};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// Replace every `u32` constant in `answer` by `42`, and add a new local to it.
+fn replace_answer(item: CrateItem, body: &Body) -> Option<Body> {
+    if item.name() != "answer" {
+        return None;
+    }
+    let mut body = body.clone();
+    ReplaceConsts.visit_body(&mut body);
+
+    let span = body.span;
+    let local = body.new_local(Ty::bool_ty(), span, Mutability::Not);
+    let statements = &mut body.blocks[0].statements;
+    statements.insert(0, Statement { kind: StatementKind::StorageLive(local), span });
+    statements.insert(1, Statement { kind: StatementKind::StorageDead(local), span });
+    Some(body)
+}
+
+struct ReplaceConsts;
+
+impl MutMirVisitor for ReplaceConsts {
+    fn visit_mir_const(&mut self, constant: &mut MirConst, _location: Location) {
+        if constant.ty().kind() == TyKind::RigidTy(RigidTy::Uint(UintTy::U32)) {
+            *constant = MirConst::try_from_uint(42, UintTy::U32).unwrap();
+        }
+    }
+}
+
+fn test_hook() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let answer = items.iter().find(|item| item.name() == "answer").unwrap();
+    let body = answer.expect_body();
+    assert_eq!(body.inner_locals().len(), 1, "Expected the new local in {body:?}");
+    let constants: Vec<_> = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(|statement| match &statement.kind {
+            StatementKind::Assign(_, Rvalue::Use(Operand::Constant(ConstOperand { const_, .. }))) => {
+                Some(const_.clone())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(constants.len(), 1, "Expected a single constant in {body:?}");
+    let ConstantKind::Allocated(alloc) = constants[0].kind() else { unreachable!() };
+    assert_eq!(alloc.read_uint().unwrap(), 42);
+
+    // Other functions are not affected.
+    let other = items.iter().find(|item| item.name() == "other").unwrap();
+    assert_eq!(other.expect_body().inner_locals().len(), 0);
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "mir_hook_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::set_optimized_mir_hook(replace_answer).unwrap();
+    run!(args, test_hook).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub fn answer() -> u32 {{
+            1
+        }}
+
+        pub fn other() -> u32 {{
+            1
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//@ run-pass
+//! Test that invalid bodies returned by the optimized MIR hook are rejected with an error.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::mir::{BinOp, Body, Operand, Place, Rvalue, Statement, StatementKind};
+use rustc_public::rustc_internal;
+use rustc_public::{CompilerError, CrateDef, CrateItem};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// Turn the first statement of `bitand` into a checked `&`, which cannot overflow.
+fn checked_bitand(item: CrateItem, body: &Body) -> Option<Body> {
+    if item.name() != "bitand" {
+        return None;
+    }
+    let mut body = body.clone();
+    let span = body.span;
+    let operand = |local| Operand::Copy(Place { local, projection: vec![] });
+    let rvalue = Rvalue::CheckedBinaryOp(BinOp::BitAnd, operand(1), operand(2));
+    let statement = StatementKind::Assign(Place { local: 0, projection: vec![] }, rvalue);
+    body.blocks[0].statements.insert(0, Statement { kind: statement, span });
+    Some(body)
+}
+
+fn test_hook() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let bitand = items.iter().find(|item| item.name() == "bitand").unwrap();
+    // The replacement is rejected with a fatal error rather than an ICE.
+    let _ = bitand.expect_body();
+    unreachable!("the invalid body should have been rejected")
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "mir_hook_invalid_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::set_optimized_mir_hook(checked_bitand).unwrap();
+    assert_eq!(run!(args, test_hook), Err(CompilerError::Failed));
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub fn bitand(a: u32, b: u32) -> u32 {{
+            a & b
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,7 @@
+//! Checks that `//@ snapshot` compares the structure of the JSON diagnostics
+//! instead of the rendered stderr.
+//@ snapshot: first-line
+
+fn main() {
+    let x: u32 = "five"; //~ ERROR mismatched types
+}
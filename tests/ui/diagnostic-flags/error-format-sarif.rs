@@ -0,0 +1,8 @@
+//! Check that diagnostics are printed as a single SARIF log
+//! when `--error-format=sarif` is used.
+
+//@ compile-flags: --error-format=sarif -Zunstable-options
+
+fn main() {
+    let x: u32 = "five";
+}
@@ -0,0 +1,32 @@
+//! Test that `-Zexplain-inference` notes how the type variables involved in a type error were
+//! inferred.
+//@ compile-flags: -Zexplain-inference
+//@ dont-require-annotations: NOTE
+
+fn new_cell<T>() -> Option<T> {
+    None
+}
+
+fn fill<T>(cell: &mut Option<T>, value: T) {
+    *cell = Some(value);
+}
+
+fn mismatch() {
+    let mut cell = new_cell(); //~ NOTE the type of `cell` inferred as `Option<_>` here
+    fill(&mut cell, 1u8);
+    //~^ NOTE type parameter `T` of `fill` unified with type parameter `T` of `new_cell` here
+    //~| NOTE type parameter `T` of `fill` inferred as `u8` here
+    let _: Option<u16> = cell; //~ ERROR mismatched types
+}
+
+fn annotations_needed() {
+    let x = panic!();
+    x.clone();
+    //~^^ ERROR type annotations needed
+    //~| NOTE the declared type of `x` unified with the type of `x` here
+}
+
+fn main() {
+    mismatch();
+    annotations_needed();
+}
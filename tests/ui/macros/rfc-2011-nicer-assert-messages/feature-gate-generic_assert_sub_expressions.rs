@@ -0,0 +1,21 @@
+// -Zpanic_abort_tests makes this test work on panic=abort targets and
+// it's a no-op on panic=unwind targets
+//@ compile-flags: --test -Zpanic_abort_tests
+//@ run-pass
+
+#![feature(core_intrinsics, generic_assert)]
+
+fn add(a: i32, b: i32) -> i32 {
+  a + b
+}
+
+// Without `generic_assert_sub_expressions`, only variables are captured
+#[should_panic(expected = "Assertion failed: add(elem, 1) == 3\nWith captures:\n  elem = 1\n")]
+#[test]
+fn test() {
+  let elem = 1i32;
+  assert!(add(elem, 1) == 3);
+}
+
+fn main() {
+}
@@ -0,0 +1,21 @@
+//@ aux-build:common.rs
+//@ only-x86_64
+//@ run-pass
+//@ needs-unwind Asserting on contents of error message
+
+#![feature(core_intrinsics, generic_assert)]
+
+extern crate common;
+
+fn inc(elem: &mut i32) -> bool {
+  *elem += 1;
+  false
+}
+
+fn main() {
+  // `elem` is captured before it is mutably borrowed, not after the assertion failed
+  common::test!(
+    let mut elem = 2i32;
+    [ elem == 2 && inc(&mut elem) ] => "Assertion failed: elem == 2 && inc(&mut elem)\nWith captures:\n  elem = 2\n"
+  );
+}
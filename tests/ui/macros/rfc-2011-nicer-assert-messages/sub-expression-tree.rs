@@ -0,0 +1,65 @@
+//@ aux-build:common.rs
+// ignore-tidy-linelength
+//@ only-x86_64
+//@ run-pass
+//@ needs-unwind Asserting on contents of error message
+
+#![feature(core_intrinsics, generic_assert, generic_assert_sub_expressions)]
+
+extern crate common;
+
+fn inc(elem: &mut i32) -> bool {
+  *elem += 1;
+  false
+}
+
+fn main() {
+  // Intermediate values are displayed above the values they were computed from
+  common::test!(
+    let mut elem = 2i32;
+    [ (elem * 3 + 1) / 2 == elem ] => "Assertion failed: (elem * 3 + 1) / 2 == elem\nWith captures:\n  (elem * 3 + 1) / 2 = 3\n    elem * 3 + 1 = 7\n      elem * 3 = 6\n        elem = 2\n"
+  );
+
+  // Method chains, whose intermediate values can not be copied and are not captured
+  common::test!(
+    let mut elems = [1, 2, 3];
+    [ elems.len() == elems.iter().filter(|elem| **elem % 2 == 0).count() ] => "Assertion failed: elems.len() == elems.iter().filter(|elem| **elem % 2 == 0).count()\nWith captures:\n  elems.len() = 3\n  elems.iter().filter(|elem| **elem % 2 == 0).count() = 1\n    elems.iter().filter(|elem| **elem % 2 == 0) = N/A\n      elems.iter() = N/A\n"
+  );
+
+  // Variables that can not be copied are captured by reference
+  common::test!(
+    let mut elems = vec![1, 2, 3];
+    [ elems.repeat(2) == elems ] => "Assertion failed: elems.repeat(2) == elems\nWith captures:\n  elems.repeat(2) = N/A\n  elems = [1, 2, 3]\n"
+  );
+
+  // Unevaluated intermediate values (Expression short-circuited)
+  common::test!(
+    let mut elem = 2i32;
+    [ elem == 3 && elem.pow(2) == 9 ] => "Assertion failed: elem == 3 && elem.pow(2) == 9\nWith captures:\n  elem == 3 = false\n    elem = 2\n  elem.pow(2) == 9 = N/A\n    elem.pow(2) = N/A\n"
+  );
+
+  // Captured values don't stay borrowed
+  common::test!(
+    let mut elem = 2i32;
+    [ elem == 2 && inc(&mut elem) ] => "Assertion failed: elem == 2 && inc(&mut elem)\nWith captures:\n  elem == 2 = true\n    elem = 2\n  inc(&mut elem) = false\n"
+  );
+
+  // Nothing is formatted if the assertion passes
+  let loud = Loud(1);
+  assert!(loud == Loud(1) && loud.plus(1) == Loud(2));
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Loud(i32);
+
+impl Loud {
+  fn plus(self, n: i32) -> Self {
+    Loud(self.0 + n)
+  }
+}
+
+impl std::fmt::Debug for Loud {
+  fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    unreachable!("formatted although the assertion passed")
+  }
+}
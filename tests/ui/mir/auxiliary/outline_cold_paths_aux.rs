@@ -0,0 +1,9 @@
+//@ compile-flags: -O -Zmir-outline-cold-paths
+
+#[inline]
+pub fn checked_sub(x: u32, y: u32) -> u32 {
+    if x > y {
+        panic!("x {} is larger than y {}", x, y);
+    }
+    y - x
+}
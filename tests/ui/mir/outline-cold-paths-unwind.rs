@@ -0,0 +1,34 @@
+// Checks that a panic in a path outlined by `-Zmir-outline-cold-paths` unwinds through the
+// function it was outlined from, also when that function is inlined into another crate.
+//
+//@ run-pass
+//@ needs-unwind
+//@ compile-flags: -O -Zmir-outline-cold-paths
+//@ aux-build:outline_cold_paths_aux.rs
+
+extern crate outline_cold_paths_aux;
+
+use std::panic;
+
+#[inline(never)]
+fn check(x: u32, y: u32) -> u32 {
+    if x > y {
+        panic!("x {} is larger than y {}", x, y);
+    }
+    y - x
+}
+
+fn message(result: std::thread::Result<u32>) -> String {
+    *result.unwrap_err().downcast::<String>().unwrap()
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+
+    assert_eq!(check(1, 3), 2);
+    assert_eq!(message(panic::catch_unwind(|| check(4, 3))), "x 4 is larger than y 3");
+
+    assert_eq!(outline_cold_paths_aux::checked_sub(1, 3), 2);
+    let result = panic::catch_unwind(|| outline_cold_paths_aux::checked_sub(5, 3));
+    assert_eq!(message(result), "x 5 is larger than y 3");
+}